@@ -0,0 +1,98 @@
+extern crate criterion;
+extern crate fastrand;
+extern crate ropey;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ropey::attributed::AttributedRope;
+use ropey::Rope;
+use std::hint::black_box;
+
+const TEXT: &str = include_str!("large.txt");
+
+/// Creates an `AttributedRope` of `TEXT` with a run every `run_len` chars,
+/// like a highlighted source file.
+fn attributed_text(run_len: usize) -> AttributedRope<u32> {
+    let mut text = AttributedRope::new(Rope::from_str(TEXT), 0);
+    let len = text.len_chars();
+    for (i, start) in (0..len).step_by(run_len).enumerate() {
+        text.set_attrs(start..(start + run_len).min(len), i as u32 % 8);
+    }
+    text
+}
+
+//----
+
+fn attributed_edits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("attributed_edits");
+
+    group.bench_function("insert_random", |bench| {
+        let mut rng = fastrand::Rng::new();
+        let mut text = attributed_text(4);
+        bench.iter(|| {
+            let len = text.len_chars();
+            text.insert(rng.usize(0..(len + 1)), "a", rng.u32(0..8));
+        })
+    });
+
+    group.bench_function("insert_start", |bench| {
+        let mut rng = fastrand::Rng::new();
+        let mut text = attributed_text(4);
+        bench.iter(|| {
+            text.insert(0, "a", rng.u32(0..8));
+        })
+    });
+
+    group.bench_function("remove_random", |bench| {
+        let mut rng = fastrand::Rng::new();
+        let mut text = attributed_text(4);
+        bench.iter(|| {
+            let len = text.len_chars();
+            let start = rng.usize(0..(len + 1));
+            text.remove(start..(start + 1).min(len));
+
+            if text.len_chars() < TEXT.len() / 2 {
+                text = attributed_text(4);
+            }
+        })
+    });
+
+    group.bench_function("set_attrs_random", |bench| {
+        let mut rng = fastrand::Rng::new();
+        let mut text = attributed_text(4);
+        bench.iter(|| {
+            let len = text.len_chars();
+            let start = rng.usize(0..(len + 1));
+            text.set_attrs(start..(start + 3).min(len), rng.u32(0..8));
+        })
+    });
+}
+
+fn attributed_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("attributed_queries");
+
+    group.bench_function("attrs_at", |bench| {
+        let mut rng = fastrand::Rng::new();
+        let text = attributed_text(4);
+        let len = text.len_chars();
+        bench.iter(|| {
+            black_box(text.attrs_at(rng.usize(0..len)));
+        })
+    });
+
+    group.bench_function("runs_in", |bench| {
+        let mut rng = fastrand::Rng::new();
+        let text = attributed_text(4);
+        let len = text.len_chars();
+        bench.iter(|| {
+            let start = rng.usize(0..(len - 80));
+            for run in text.runs_in(start..(start + 80)) {
+                black_box(run);
+            }
+        })
+    });
+}
+
+//----
+
+criterion_group!(benches, attributed_edits, attributed_queries,);
+criterion_main!(benches);
@@ -1,6 +1,7 @@
 //! Example of decoding from another text encoding on-the-fly while reading.
-//! In this case, we're decoding from ISO/IEC 8859-1, which conveniently
-//! happens to map 1-to-1 to the first 256 unicode scalar values.
+//! In this case, we're decoding from ISO/IEC 8859-1, using the decoders in
+//! Ropey's `encoding` module.  (For whole files, `encoding::from_reader()`
+//! does all of this for you.)
 
 extern crate ropey;
 
@@ -8,6 +9,7 @@ use std::fs::File;
 use std::io;
 use std::io::Read;
 
+use ropey::encoding::{Decoder, Encoding};
 use ropey::RopeBuilder;
 
 fn main() {
@@ -24,8 +26,8 @@ fn main() {
 
     // Get everything set up to begin reading and decoding.
     let mut buf = vec![0u8; 1 << 14]; // Buffer for raw bytes.
-    let mut buf_str = String::with_capacity(1 << 14); // Buffer for decoded utf8.
     let mut builder = RopeBuilder::new();
+    let mut decoder = Decoder::new(Encoding::Latin1);
     let mut file = io::BufReader::new(File::open(&filepath).unwrap());
 
     // Read the data in chunks, decoding and appending to the rope builder
@@ -37,12 +39,9 @@ fn main() {
         }
 
         // Decode and append the chunk to the rope builder.
-        buf_str.clear();
-        for &byte in &buf[..n] {
-            buf_str.push(byte as char);
-        }
-        builder.append(&buf_str);
+        decoder.decode(&buf[..n], &mut builder);
     }
+    decoder.finish(&mut builder);
 
     // Build rope.
     let _rope = builder.finish();
@@ -0,0 +1,610 @@
+//! Text with attributes attached to char ranges.
+//!
+//! An [`AttributedRope`] pairs a `Rope` with a run-length encoded list of
+//! attributes (e.g. style ids or link targets), and keeps the two in sync
+//! as the text is edited:
+//!
+//! ```
+//! # use ropey::Rope;
+//! use ropey::attributed::AttributedRope;
+//!
+//! let mut text = AttributedRope::new(Rope::from_str("Hello world!"), "plain");
+//! text.set_attrs(6..11, "bold");
+//! text.insert(6, "big ", "italic");
+//!
+//! let runs: Vec<_> = text
+//!     .runs()
+//!     .map(|(range, attrs, slice)| (range, *attrs, slice.to_string()))
+//!     .collect();
+//! assert_eq!(
+//!     runs,
+//!     vec![
+//!         (0..6, "plain", "Hello ".to_string()),
+//!         (6..10, "italic", "big ".to_string()),
+//!         (10..15, "bold", "world".to_string()),
+//!         (15..16, "plain", "!".to_string()),
+//!     ]
+//! );
+//! ```
+//!
+//! Every char of the text has attributes, and adjacent runs with equal
+//! attributes are always merged into one.
+//!
+//! # Performance
+//!
+//! The runs are kept in a balanced tree of their lengths, separate from the
+//! `Rope`'s own tree.  Looking up the attributes at a char index takes
+//! O(log R) time, where R is the number of runs, and edits take
+//! O(log N + log R) time, plus O(K) for the K runs that an edit removes.
+//!
+//! The runs aren't stored in the `Rope`'s node summaries because those
+//! nodes are shared between clones and slices of the rope, and are split
+//! and rebalanced by chunk size with no regard for run boundaries.  Storing
+//! attributes there would mean making every node, iterator, and slice type
+//! generic over the attribute type, and a run spanning many chunks would
+//! have to be repeated in each of them.
+
+use std::ops::{Range, RangeBounds};
+
+use crate::rope::Rope;
+use crate::slice::RopeSlice;
+use crate::span_tree::{Span, SpanIter, SpanTree};
+use crate::str_utils::count_chars;
+use crate::{end_bound_to_num, start_bound_to_num};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Run<A> {
+    /// Length of the run in chars.
+    len: usize,
+    attrs: A,
+}
+
+impl<A> Span for Run<A> {
+    #[inline(always)]
+    fn span_len(&self) -> usize {
+        self.len
+    }
+}
+
+/// A `Rope` with attributes attached to its text.
+///
+/// See the [module documentation](self) for an overview.
+#[derive(Debug, Clone)]
+pub struct AttributedRope<A> {
+    text: Rope,
+    /// Covers the whole text, with no empty runs and no two adjacent runs
+    /// having equal attributes.
+    runs: SpanTree<Run<A>>,
+}
+
+impl<A: Clone + PartialEq> AttributedRope<A> {
+    //-----------------------------------------------------------------------
+    // Constructors
+
+    /// Creates an `AttributedRope` from `text`, with all of it having the
+    /// attributes `attrs`.
+    pub fn new(text: Rope, attrs: A) -> Self {
+        let len = text.len_chars();
+        let mut runs = SpanTree::new();
+        if len > 0 {
+            runs.insert(
+                0,
+                Run {
+                    len: len,
+                    attrs: attrs,
+                },
+            );
+        }
+        AttributedRope {
+            text: text,
+            runs: runs,
+        }
+    }
+
+    //-----------------------------------------------------------------------
+    // Informational methods
+
+    /// The underlying text.
+    #[inline]
+    pub fn rope(&self) -> &Rope {
+        &self.text
+    }
+
+    /// Discards the attributes, returning the underlying text.
+    #[inline]
+    pub fn into_rope(self) -> Rope {
+        self.text
+    }
+
+    /// Total number of chars in the text.
+    ///
+    /// Runs in O(1) time.
+    #[inline]
+    pub fn len_chars(&self) -> usize {
+        self.text.len_chars()
+    }
+
+    /// Total number of attribute runs.
+    ///
+    /// Runs in O(1) time.
+    #[inline]
+    pub fn len_runs(&self) -> usize {
+        self.runs.count()
+    }
+
+    //-----------------------------------------------------------------------
+    // Attribute methods
+
+    /// Returns the attributes of the char at `char_idx`.
+    ///
+    /// Runs in O(log R) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx >= len_chars()`).
+    #[inline]
+    pub fn attrs_at(&self, char_idx: usize) -> &A {
+        if let Some(attrs) = self.get_attrs_at(char_idx) {
+            attrs
+        } else {
+            panic!(
+                "Attempt to index past end of AttributedRope: char index {}, AttributedRope char length {}",
+                char_idx,
+                self.len_chars()
+            );
+        }
+    }
+
+    /// Non-panicking version of [`attrs_at()`](AttributedRope::attrs_at).
+    #[inline]
+    pub fn get_attrs_at(&self, char_idx: usize) -> Option<&A> {
+        let i = self.run_idx(char_idx);
+        self.runs.get(i).map(|id| &self.runs.item(id).attrs)
+    }
+
+    /// Sets the attributes of the chars in `char_range` to `attrs`.
+    ///
+    /// Runs in O(log R + K) time, where K is the number of runs replaced.
+    ///
+    /// Uses range syntax, e.g. `2..7`, `2..`, etc.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. `end > len_chars()`).
+    pub fn set_attrs<R>(&mut self, char_range: R, attrs: A)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.char_range_to_nums(char_range, "set_attrs()");
+        if start == end {
+            return;
+        }
+
+        let i = self.split_run_at(start);
+        let j = self.split_run_at(end);
+        self.runs.remove_range(i..j);
+        self.runs.insert(
+            i,
+            Run {
+                len: end - start,
+                attrs: attrs,
+            },
+        );
+        self.merge_runs_at(i + 1);
+        self.merge_runs_at(i);
+    }
+
+    /// Creates an iterator over the attribute runs of the text.
+    ///
+    /// Yields `(char_range, &attrs, slice)` tuples, where `slice` is the
+    /// text of the run.
+    #[inline]
+    pub fn runs(&self) -> Runs<'_, A> {
+        self.runs_in(..)
+    }
+
+    /// Creates an iterator over the attribute runs that overlap
+    /// `char_range`.
+    ///
+    /// The first and last runs are truncated to the bounds of `char_range`.
+    /// Otherwise identical to [`runs()`](AttributedRope::runs).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. `end > len_chars()`).
+    pub fn runs_in<R>(&self, char_range: R) -> Runs<'_, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.char_range_to_nums(char_range, "runs_in()");
+        let i = self.run_idx(start);
+        let j = if start == end {
+            i
+        } else {
+            self.run_idx(end - 1) + 1
+        };
+
+        Runs {
+            text: &self.text,
+            runs: self.runs.iter_range(i..j),
+            front: self.runs.offset_of_rank(i),
+            back: self.runs.offset_of_rank(j),
+            start: start,
+            end: end,
+        }
+    }
+
+    //-----------------------------------------------------------------------
+    // Edit methods
+
+    /// Inserts `text` at char index `char_idx`, with the attributes `attrs`.
+    ///
+    /// If `char_idx` is inside a run, that run is split around the inserted
+    /// text.
+    ///
+    /// Runs in O(log N + log R) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    pub fn insert(&mut self, char_idx: usize, text: &str, attrs: A) {
+        self.text.insert(char_idx, text);
+
+        let len = count_chars(text);
+        if len == 0 {
+            return;
+        }
+
+        let i = self.split_run_at(char_idx);
+        self.runs.insert(
+            i,
+            Run {
+                len: len,
+                attrs: attrs,
+            },
+        );
+        self.merge_runs_at(i + 1);
+        self.merge_runs_at(i);
+    }
+
+    /// Removes the text in the given char index range, along with its
+    /// attributes.
+    ///
+    /// Runs in O(log N + log R + K) time, where K is the number of runs
+    /// removed.
+    ///
+    /// Uses range syntax, e.g. `2..7`, `2..`, etc.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. `end > len_chars()`).
+    pub fn remove<R>(&mut self, char_range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.char_range_to_nums(char_range, "remove()");
+        if start == end {
+            return;
+        }
+
+        self.text.remove(start..end);
+
+        let i = self.split_run_at(start);
+        let j = self.split_run_at(end);
+        self.runs.remove_range(i..j);
+        self.merge_runs_at(i);
+    }
+
+    //-----------------------------------------------------------------------
+    // Internal utilities
+
+    fn char_range_to_nums<R>(&self, char_range: R, method: &str) -> (usize, usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = start_bound_to_num(char_range.start_bound()).unwrap_or(0);
+        let end = end_bound_to_num(char_range.end_bound()).unwrap_or_else(|| self.len_chars());
+        if start > end || end > self.len_chars() {
+            panic!(
+                "{}: invalid char range {}..{} for AttributedRope of char length {}",
+                method,
+                start,
+                end,
+                self.len_chars()
+            );
+        }
+        (start, end)
+    }
+
+    /// Returns the index of the run containing `char_idx`, or the number of
+    /// runs if `char_idx` is the end of the text.
+    #[inline]
+    fn run_idx(&self, char_idx: usize) -> usize {
+        self.runs
+            .partition_point(|start, r| start + r.len <= char_idx)
+    }
+
+    /// Makes sure a run starts at `char_idx`, splitting the run containing it
+    /// if needed, and returns the index of that run.  If `char_idx` is the
+    /// end of the text, returns the number of runs.
+    fn split_run_at(&mut self, char_idx: usize) -> usize {
+        let i = self.run_idx(char_idx);
+        let id = match self.runs.get(i) {
+            Some(id) => id,
+            None => return i,
+        };
+        let run_start = self.runs.offset_of_rank(i);
+        if run_start == char_idx {
+            return i;
+        }
+
+        let run = self.runs.item(id);
+        let tail = Run {
+            len: run_start + run.len - char_idx,
+            attrs: run.attrs.clone(),
+        };
+        self.runs.update_item(id, |r| r.len = char_idx - run_start);
+        self.runs.insert(i + 1, tail);
+        i + 1
+    }
+
+    /// Merges the runs at `run_idx - 1` and `run_idx` if they have equal
+    /// attributes.
+    fn merge_runs_at(&mut self, run_idx: usize) {
+        if run_idx == 0 || run_idx >= self.runs.count() {
+            return;
+        }
+        let prev = self.runs.get(run_idx - 1).unwrap();
+        let next = self.runs.get(run_idx).unwrap();
+        if self.runs.item(prev).attrs == self.runs.item(next).attrs {
+            let len = self.runs.remove_range(run_idx..(run_idx + 1))[0].len;
+            self.runs.update_item(prev, |r| r.len += len);
+        }
+    }
+
+    /// Debugging tool to make sure that the runs are consistent with the
+    /// text and with each other.
+    #[cfg(test)]
+    fn assert_runs(&self) {
+        let mut prev: Option<&Run<A>> = None;
+        for run in self.runs.iter_range(0..self.runs.count()) {
+            assert!(run.len > 0);
+            if let Some(prev) = prev {
+                assert!(prev.attrs != run.attrs);
+            }
+            prev = Some(run);
+        }
+        assert_eq!(
+            self.runs.offset_of_rank(self.runs.count()),
+            self.len_chars()
+        );
+    }
+}
+
+//==============================================================
+
+/// An iterator over the attribute runs of an [`AttributedRope`].
+///
+/// Yields `(char_range, &attrs, slice)` tuples.
+#[derive(Debug, Clone)]
+pub struct Runs<'a, A> {
+    text: &'a Rope,
+    runs: SpanIter<'a, Run<A>>,
+    front: usize, // Start of the next run from the front.
+    back: usize,  // End of the next run from the back.
+    // The iterated range, which the first and last runs are clipped to.
+    start: usize,
+    end: usize,
+}
+
+impl<'a, A> Runs<'a, A> {
+    #[inline(always)]
+    fn item(&self, run_start: usize, run: &'a Run<A>) -> (Range<usize>, &'a A, RopeSlice<'a>) {
+        let range = run_start.max(self.start)..(run_start + run.len).min(self.end);
+        (range.clone(), &run.attrs, self.text.slice(range))
+    }
+}
+
+impl<'a, A> Iterator for Runs<'a, A> {
+    type Item = (Range<usize>, &'a A, RopeSlice<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let run = self.runs.next()?;
+        let item = self.item(self.front, run);
+        self.front += run.len;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.runs.size_hint()
+    }
+}
+
+impl<'a, A> DoubleEndedIterator for Runs<'a, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let run = self.runs.next_back()?;
+        self.back -= run.len;
+        Some(self.item(self.back, run))
+    }
+}
+
+impl<'a, A> ExactSizeIterator for Runs<'a, A> {}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(text: &AttributedRope<u32>) -> Vec<(Range<usize>, u32, String)> {
+        text.runs()
+            .map(|(range, attrs, slice)| (range, *attrs, slice.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn new_01() {
+        let text = AttributedRope::new(Rope::from_str("Hello"), 1u32);
+        assert_eq!(runs(&text), vec![(0..5, 1, "Hello".to_string())]);
+        assert_eq!(*text.attrs_at(4), 1);
+        assert_eq!(text.get_attrs_at(5), None);
+
+        let empty = AttributedRope::new(Rope::new(), 1u32);
+        assert_eq!(empty.len_runs(), 0);
+        assert_eq!(empty.runs().count(), 0);
+        empty.assert_runs();
+    }
+
+    #[test]
+    fn set_attrs_01() {
+        let mut text = AttributedRope::new(Rope::from_str("Hello world!"), 0u32);
+        text.set_attrs(6..11, 1);
+        text.set_attrs(0..2, 2);
+        text.assert_runs();
+        assert_eq!(text.len_runs(), 4);
+        assert_eq!(*text.attrs_at(1), 2);
+        assert_eq!(*text.attrs_at(2), 0);
+        assert_eq!(*text.attrs_at(6), 1);
+        assert_eq!(*text.attrs_at(10), 1);
+        assert_eq!(*text.attrs_at(11), 0);
+
+        // Covering runs merges them back together.
+        text.set_attrs(.., 0);
+        text.assert_runs();
+        assert_eq!(runs(&text), vec![(0..12, 0, "Hello world!".to_string())]);
+    }
+
+    #[test]
+    fn insert_01() {
+        let mut text = AttributedRope::new(Rope::from_str("Hello world!"), 0u32);
+        text.insert(6, "big ", 1);
+        text.insert(16, "?", 2);
+        text.assert_runs();
+        assert_eq!(
+            runs(&text),
+            vec![
+                (0..6, 0, "Hello ".to_string()),
+                (6..10, 1, "big ".to_string()),
+                (10..16, 0, "world!".to_string()),
+                (16..17, 2, "?".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn insert_02() {
+        // Inserting with the same attributes as the neighbors merges runs.
+        let mut text = AttributedRope::new(Rope::from_str("Hello world!"), 0u32);
+        text.set_attrs(6..11, 1);
+        text.insert(3, "lo", 0);
+        text.insert(8, "-", 1);
+        text.insert(9, "", 2);
+        text.assert_runs();
+        assert_eq!(
+            runs(&text),
+            vec![
+                (0..8, 0, "Hellolo ".to_string()),
+                (8..14, 1, "-world".to_string()),
+                (14..15, 0, "!".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn remove_01() {
+        let mut text = AttributedRope::new(Rope::from_str("Hello big world!"), 0u32);
+        text.set_attrs(6..10, 1);
+        text.set_attrs(10..15, 2);
+        text.remove(8..12);
+        text.assert_runs();
+        assert_eq!(
+            runs(&text),
+            vec![
+                (0..6, 0, "Hello ".to_string()),
+                (6..8, 1, "bi".to_string()),
+                (8..11, 2, "rld".to_string()),
+                (11..12, 0, "!".to_string()),
+            ]
+        );
+
+        // Removing a whole run merges its neighbors.
+        text.set_attrs(8..11, 0);
+        text.remove(6..8);
+        text.assert_runs();
+        assert_eq!(runs(&text), vec![(0..10, 0, "Hello rld!".to_string())]);
+    }
+
+    #[test]
+    fn runs_in_01() {
+        let mut text = AttributedRope::new(Rope::from_str("Hello world!"), 0u32);
+        text.set_attrs(6..11, 1);
+
+        let clipped: Vec<_> = text
+            .runs_in(3..8)
+            .map(|(range, attrs, slice)| (range, *attrs, slice.to_string()))
+            .collect();
+        assert_eq!(
+            clipped,
+            vec![(3..6, 0, "lo ".to_string()), (6..8, 1, "wo".to_string())]
+        );
+
+        let reversed: Vec<_> = text.runs_in(3..8).rev().map(|(r, _, _)| r).collect();
+        assert_eq!(reversed, vec![6..8, 3..6]);
+
+        assert_eq!(text.runs_in(6..11).count(), 1);
+        assert_eq!(text.runs_in(6..6).count(), 0);
+        assert_eq!(text.runs_in(12..).count(), 0);
+    }
+
+    #[test]
+    fn edits_01() {
+        // Compare against per-char attributes through a pseudo-random
+        // sequence of edits.
+        let mut text = AttributedRope::new(Rope::from_str("0123456789"), 0u32);
+        let mut model = vec![0u32; 10];
+        let mut rng = fastrand::Rng::with_seed(1);
+        for _ in 0..500 {
+            let a = rng.usize(0..(model.len() + 1));
+            let b = (a + rng.usize(0..7)).min(model.len());
+            let attrs = rng.u32(0..3);
+            match rng.u8(0..3) {
+                0 => {
+                    text.insert(a, &"x".repeat(b - a), attrs);
+                    model.splice(a..a, vec![attrs; b - a]);
+                }
+                1 => {
+                    text.remove(a..b);
+                    model.drain(a..b);
+                }
+                _ => {
+                    text.set_attrs(a..b, attrs);
+                    model[a..b].iter_mut().for_each(|m| *m = attrs);
+                }
+            }
+        }
+        text.assert_runs();
+
+        let mut chars = Vec::new();
+        for (range, attrs, slice) in text.runs() {
+            assert_eq!(range.len(), slice.len_chars());
+            chars.extend(range.map(|_| *attrs));
+        }
+        assert_eq!(chars, model);
+        for (i, attrs) in model.iter().enumerate() {
+            assert_eq!(text.attrs_at(i), attrs);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_02() {
+        let mut text = AttributedRope::new(Rope::from_str("Hello"), 0u32);
+        text.remove(3..6);
+    }
+}
@@ -0,0 +1,637 @@
+//! Loading and saving text in encodings other than utf8.
+//!
+//! A [`Decoder`] decodes text incrementally into a [`RopeBuilder`], and an
+//! [`Encoder`] encodes a rope's [`Chunks`] incrementally into a writer, so
+//! that neither ever needs the whole text in memory in the other encoding.
+//!
+//! For the common case of loading and saving whole files, [`from_reader()`]
+//! detects the encoding from the byte order mark (if any), and reports it
+//! along with whether there was a byte order mark, so that
+//! [`write_to()`] can later save the text the same way:
+//!
+//! ```
+//! use ropey::encoding::{self, Encoding};
+//!
+//! // "Hi!" in utf16 little endian, with a byte order mark.
+//! let data: &[u8] = b"\xFF\xFEH\x00i\x00!\x00";
+//!
+//! let (rope, encoding, has_bom) = encoding::from_reader(data, Encoding::Utf8).unwrap();
+//! assert_eq!(rope, "Hi!");
+//! assert_eq!(encoding, Encoding::Utf16Le);
+//! assert!(has_bom);
+//!
+//! let mut saved = Vec::new();
+//! encoding::write_to(rope.slice(..), &mut saved, encoding, has_bom).unwrap();
+//! assert_eq!(saved, data);
+//! ```
+
+use std::io;
+
+use crate::iter::Chunks;
+use crate::rope::Rope;
+use crate::rope_builder::RopeBuilder;
+use crate::slice::RopeSlice;
+use crate::tree::MAX_BYTES;
+
+/// A text encoding.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// UTF-8.
+    Utf8,
+    /// UTF-16, little endian.
+    Utf16Le,
+    /// UTF-16, big endian.
+    Utf16Be,
+    /// UTF-32, little endian.
+    Utf32Le,
+    /// UTF-32, big endian.
+    Utf32Be,
+    /// ISO-8859-1 (Latin-1), which maps each byte to the char with the same
+    /// value.
+    Latin1,
+    /// Windows-1252, the superset of Latin-1 that uses bytes `0x80`-`0x9F`
+    /// for printable chars such as "€" and "“" instead of control chars.
+    ///
+    /// The five bytes in that range that Windows-1252 doesn't define are
+    /// mapped to the control chars with the same value, as in the WHATWG
+    /// encoding standard, so that any byte sequence round-trips.
+    Windows1252,
+}
+
+impl Encoding {
+    /// Returns the name of the encoding, as registered with IANA.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Utf32Le => "UTF-32LE",
+            Encoding::Utf32Be => "UTF-32BE",
+            Encoding::Latin1 => "ISO-8859-1",
+            Encoding::Windows1252 => "windows-1252",
+        }
+    }
+
+    /// Returns the encoding's byte order mark.
+    ///
+    /// This is empty for Latin-1 and Windows-1252, which don't have one.
+    pub fn bom(&self) -> &'static [u8] {
+        match *self {
+            Encoding::Utf8 => b"\xEF\xBB\xBF",
+            Encoding::Utf16Le => b"\xFF\xFE",
+            Encoding::Utf16Be => b"\xFE\xFF",
+            Encoding::Utf32Le => b"\xFF\xFE\x00\x00",
+            Encoding::Utf32Be => b"\x00\x00\xFE\xFF",
+            Encoding::Latin1 | Encoding::Windows1252 => b"",
+        }
+    }
+}
+
+/// Detects the encoding of text from the byte order mark at its start.
+///
+/// Returns the encoding and the length of the byte order mark in bytes, or
+/// `None` if `bytes` doesn't start with a byte order mark.  To detect utf32
+/// byte order marks `bytes` must have at least four bytes, if available.
+///
+/// Note: the utf32 little endian byte order mark starts with the utf16
+/// little endian one, so utf16 text starting with a null char is detected
+/// as utf32.
+pub fn detect_bom(bytes: &[u8]) -> Option<(Encoding, usize)> {
+    [
+        Encoding::Utf8,
+        Encoding::Utf32Le,
+        Encoding::Utf32Be,
+        Encoding::Utf16Le,
+        Encoding::Utf16Be,
+    ]
+    .iter()
+    .find(|encoding| bytes.starts_with(encoding.bom()))
+    .map(|&encoding| (encoding, encoding.bom().len()))
+}
+
+/// Creates a `Rope` from the output of a reader, decoding it from the
+/// encoding given by its byte order mark, or `default_encoding` if it has
+/// none.
+///
+/// The byte order mark is not included in the `Rope`.  Invalid input is
+/// replaced with U+FFFD (the replacement character), as by [`Decoder`].
+///
+/// Returns the `Rope`, the encoding it was decoded from, and whether the
+/// input had a byte order mark, in that order.
+///
+/// Runs in O(N) time.
+///
+/// # Errors
+///
+/// If the reader returns an error, `from_reader` stops and returns that
+/// error.
+pub fn from_reader<T: io::Read>(
+    mut reader: T,
+    default_encoding: Encoding,
+) -> io::Result<(Rope, Encoding, bool)> {
+    const BUFFER_SIZE: usize = MAX_BYTES * 2;
+    let mut buffer = [0u8; BUFFER_SIZE];
+
+    // Read enough to detect any byte order mark.
+    let mut fill_idx = 0;
+    while fill_idx < 4 {
+        match reader.read(&mut buffer[fill_idx..]) {
+            Ok(0) => break,
+            Ok(read_count) => fill_idx += read_count,
+            Err(e) => return Err(e),
+        }
+    }
+    let (encoding, bom_len) = detect_bom(&buffer[..fill_idx]).unwrap_or((default_encoding, 0));
+
+    let mut builder = RopeBuilder::new();
+    let mut decoder = Decoder::new(encoding);
+    decoder.decode(&buffer[bom_len..fill_idx], &mut builder);
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read_count) => decoder.decode(&buffer[..read_count], &mut builder),
+            Err(e) => return Err(e),
+        }
+    }
+    decoder.finish(&mut builder);
+
+    Ok((builder.finish(), encoding, bom_len > 0))
+}
+
+/// Writes `text` to a writer in the given encoding, preceded by the
+/// encoding's byte order mark if `with_bom` is true.
+///
+/// Runs in O(N) time.
+///
+/// # Errors
+///
+/// - If the writer returns an error, `write_to` stops and returns that
+///   error.
+/// - If `text` contains a char that can't be represented in the encoding,
+///   an IO error with kind `InvalidData` is returned.
+///
+/// Note: some data is likely written even if there is an error.
+pub fn write_to<T: io::Write>(
+    text: RopeSlice<'_>,
+    writer: T,
+    encoding: Encoding,
+    with_bom: bool,
+) -> io::Result<()> {
+    Encoder::new(encoding, with_bom).write_chunks(text.chunks(), writer)
+}
+
+//=============================================================
+
+/// An incremental decoder from an [`Encoding`] into a [`RopeBuilder`].
+///
+/// The input can be split anywhere, even in the middle of a char.  Invalid
+/// input (e.g. unpaired utf16 surrogates) is replaced with U+FFFD (the
+/// replacement character).  For utf8 this is done by
+/// [`RopeBuilder::append_bytes_lossy()`], which also records the
+/// replacements it makes.
+///
+/// The decoder doesn't treat byte order marks specially, so any byte order
+/// mark should be skipped before decoding (see [`detect_bom()`]).
+///
+/// # Example
+/// ```
+/// # use ropey::RopeBuilder;
+/// use ropey::encoding::{Decoder, Encoding};
+///
+/// let mut builder = RopeBuilder::new();
+/// let mut decoder = Decoder::new(Encoding::Windows1252);
+///
+/// decoder.decode(b"\x93Caf\xE9\x94 costs ", &mut builder);
+/// decoder.decode(b"3\x80.", &mut builder);
+/// decoder.finish(&mut builder);
+///
+/// assert_eq!(builder.finish(), "“Café” costs 3€.");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    encoding: Encoding,
+    // The bytes of a code unit split across `decode()` calls.
+    partial: [u8; 4],
+    partial_len: usize,
+    // A utf16 high surrogate waiting for its low surrogate.
+    high_surrogate: Option<u16>,
+    buffer: String,
+}
+
+impl Decoder {
+    /// Creates a decoder for the given encoding.
+    pub fn new(encoding: Encoding) -> Decoder {
+        Decoder {
+            encoding: encoding,
+            partial: [0; 4],
+            partial_len: 0,
+            high_surrogate: None,
+            buffer: String::new(),
+        }
+    }
+
+    /// Decodes `bytes`, appending the decoded text to `builder`.
+    pub fn decode(&mut self, bytes: &[u8], builder: &mut RopeBuilder) {
+        match self.encoding {
+            Encoding::Utf8 => {
+                builder.append_bytes_lossy(bytes);
+                return;
+            }
+            Encoding::Latin1 => self.buffer.extend(bytes.iter().map(|&b| b as char)),
+            Encoding::Windows1252 => self
+                .buffer
+                .extend(bytes.iter().map(|&b| windows_1252_to_char(b))),
+            Encoding::Utf16Le | Encoding::Utf16Be => self.decode_units(bytes, 2),
+            Encoding::Utf32Le | Encoding::Utf32Be => self.decode_units(bytes, 4),
+        }
+
+        builder.append(&self.buffer);
+        self.buffer.clear();
+    }
+
+    /// Finishes decoding, appending a replacement character to `builder`
+    /// if the input ended in the middle of a char.
+    pub fn finish(self, builder: &mut RopeBuilder) {
+        if self.high_surrogate.is_some() {
+            builder.append("\u{FFFD}");
+        }
+        if self.partial_len > 0 {
+            builder.append("\u{FFFD}");
+        }
+    }
+
+    fn decode_units(&mut self, bytes: &[u8], unit_len: usize) {
+        let mut bytes = bytes;
+
+        // Finish off a code unit split across calls first.
+        if self.partial_len > 0 {
+            let len = (unit_len - self.partial_len).min(bytes.len());
+            self.partial[self.partial_len..(self.partial_len + len)].copy_from_slice(&bytes[..len]);
+            self.partial_len += len;
+            bytes = &bytes[len..];
+            if self.partial_len < unit_len {
+                return;
+            }
+            let unit = self.partial;
+            self.partial_len = 0;
+            self.decode_unit(&unit[..unit_len]);
+        }
+
+        let mut units = bytes.chunks_exact(unit_len);
+        for unit in &mut units {
+            self.decode_unit(unit);
+        }
+        let rest = units.remainder();
+        self.partial[..rest.len()].copy_from_slice(rest);
+        self.partial_len = rest.len();
+    }
+
+    #[inline]
+    fn decode_unit(&mut self, unit: &[u8]) {
+        match self.encoding {
+            Encoding::Utf16Le => self.decode_utf16(u16::from_le_bytes([unit[0], unit[1]])),
+            Encoding::Utf16Be => self.decode_utf16(u16::from_be_bytes([unit[0], unit[1]])),
+            Encoding::Utf32Le => {
+                self.decode_utf32(u32::from_le_bytes([unit[0], unit[1], unit[2], unit[3]]))
+            }
+            Encoding::Utf32Be => {
+                self.decode_utf32(u32::from_be_bytes([unit[0], unit[1], unit[2], unit[3]]))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    fn decode_utf16(&mut self, unit: u16) {
+        if let Some(high) = self.high_surrogate.take() {
+            if (0xDC00..0xE000).contains(&unit) {
+                let code = 0x10000 + (((high as u32) - 0xD800) << 10) + ((unit as u32) - 0xDC00);
+                self.buffer.push(char::from_u32(code).unwrap());
+                return;
+            }
+            self.buffer.push('\u{FFFD}');
+        }
+
+        if (0xD800..0xDC00).contains(&unit) {
+            self.high_surrogate = Some(unit);
+        } else {
+            // Lone low surrogates fail the conversion.
+            self.buffer
+                .push(char::from_u32(unit as u32).unwrap_or('\u{FFFD}'));
+        }
+    }
+
+    #[inline]
+    fn decode_utf32(&mut self, unit: u32) {
+        self.buffer.push(char::from_u32(unit).unwrap_or('\u{FFFD}'));
+    }
+}
+
+/// An incremental encoder from utf8 text into an [`Encoding`].
+///
+/// # Example
+/// ```
+/// # use ropey::Rope;
+/// use ropey::encoding::{Encoder, Encoding};
+///
+/// let rope = Rope::from_str("Hi 🐸");
+/// let mut encoder = Encoder::new(Encoding::Utf16Be, false);
+///
+/// let mut out = Vec::new();
+/// encoder.write_chunks(rope.chunks(), &mut out).unwrap();
+///
+/// assert_eq!(out, b"\x00H\x00i\x00 \xD8\x3D\xDC\x38");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Encoder {
+    encoding: Encoding,
+    bom_pending: bool,
+}
+
+impl Encoder {
+    /// Creates an encoder for the given encoding.
+    ///
+    /// If `with_bom` is true, the encoder's output is preceded by the
+    /// encoding's byte order mark.
+    pub fn new(encoding: Encoding, with_bom: bool) -> Encoder {
+        Encoder {
+            encoding: encoding,
+            bom_pending: with_bom,
+        }
+    }
+
+    /// Encodes `text`, appending the encoded bytes to `out`.
+    ///
+    /// # Errors
+    ///
+    /// If `text` contains a char that can't be represented in the encoding,
+    /// an IO error with kind `InvalidData` is returned.  The text before
+    /// that char is still appended to `out`.
+    pub fn encode(&mut self, text: &str, out: &mut Vec<u8>) -> io::Result<()> {
+        if std::mem::take(&mut self.bom_pending) {
+            out.extend_from_slice(self.encoding.bom());
+        }
+
+        match self.encoding {
+            Encoding::Utf8 => out.extend_from_slice(text.as_bytes()),
+            Encoding::Utf16Le => {
+                for unit in text.encode_utf16() {
+                    out.extend_from_slice(&unit.to_le_bytes());
+                }
+            }
+            Encoding::Utf16Be => {
+                for unit in text.encode_utf16() {
+                    out.extend_from_slice(&unit.to_be_bytes());
+                }
+            }
+            Encoding::Utf32Le => {
+                for c in text.chars() {
+                    out.extend_from_slice(&(c as u32).to_le_bytes());
+                }
+            }
+            Encoding::Utf32Be => {
+                for c in text.chars() {
+                    out.extend_from_slice(&(c as u32).to_be_bytes());
+                }
+            }
+            Encoding::Latin1 | Encoding::Windows1252 => {
+                for c in text.chars() {
+                    let byte = if self.encoding == Encoding::Latin1 {
+                        latin_1_from_char(c)
+                    } else {
+                        windows_1252_from_char(c)
+                    };
+                    match byte {
+                        Some(byte) => out.push(byte),
+                        None => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!(
+                                    "char {:?} (U+{:04X}) can't be encoded in {}",
+                                    c,
+                                    c as u32,
+                                    self.encoding.name()
+                                ),
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encodes the text of `chunks`, writing the encoded bytes to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// - If the writer returns an error, `write_chunks` stops and returns
+    ///   that error.
+    /// - If the text contains a char that can't be represented in the
+    ///   encoding, an IO error with kind `InvalidData` is returned.
+    pub fn write_chunks<T: io::Write>(
+        &mut self,
+        chunks: Chunks<'_>,
+        mut writer: T,
+    ) -> io::Result<()> {
+        let mut buffer = Vec::new();
+        self.encode("", &mut buffer)?;
+        for chunk in chunks {
+            self.encode(chunk, &mut buffer)?;
+            if buffer.len() >= MAX_BYTES * 4 {
+                writer.write_all(&buffer)?;
+                buffer.clear();
+            }
+        }
+        writer.write_all(&buffer)
+    }
+}
+
+//=============================================================
+
+/// The chars that Windows-1252 bytes `0x80`-`0x9F` decode to.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+#[inline]
+fn windows_1252_to_char(byte: u8) -> char {
+    if (0x80..0xA0).contains(&byte) {
+        WINDOWS_1252_HIGH[(byte - 0x80) as usize]
+    } else {
+        byte as char
+    }
+}
+
+#[inline]
+fn windows_1252_from_char(c: char) -> Option<u8> {
+    if (c as u32) < 0x80 || ((c as u32) >= 0xA0 && (c as u32) < 0x100) {
+        Some(c as u8)
+    } else {
+        WINDOWS_1252_HIGH
+            .iter()
+            .position(|&high| high == c)
+            .map(|i| 0x80 + i as u8)
+    }
+}
+
+#[inline]
+fn latin_1_from_char(c: char) -> Option<u8> {
+    if (c as u32) < 0x100 {
+        Some(c as u8)
+    } else {
+        None
+    }
+}
+
+//=============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Hello there!  How're you doing?\r\nIt's \
+                        a fine day, isn't it?\r\nAren't you glad \
+                        we're alive?\r\nこんにちは、みんなさん！🐸";
+
+    fn decode_in_pieces(bytes: &[u8], encoding: Encoding, piece_len: usize) -> Rope {
+        let mut builder = RopeBuilder::new();
+        let mut decoder = Decoder::new(encoding);
+        for piece in bytes.chunks(piece_len) {
+            decoder.decode(piece, &mut builder);
+        }
+        decoder.finish(&mut builder);
+        builder.finish()
+    }
+
+    fn encode(text: &str, encoding: Encoding, with_bom: bool) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_to(Rope::from_str(text).slice(..), &mut out, encoding, with_bom).unwrap();
+        out
+    }
+
+    #[test]
+    fn detect_bom_01() {
+        assert_eq!(detect_bom(b"\xEF\xBB\xBFHi"), Some((Encoding::Utf8, 3)));
+        assert_eq!(detect_bom(b"\xFF\xFEH\x00"), Some((Encoding::Utf16Le, 2)));
+        assert_eq!(detect_bom(b"\xFE\xFF\x00H"), Some((Encoding::Utf16Be, 2)));
+        assert_eq!(
+            detect_bom(b"\xFF\xFE\x00\x00"),
+            Some((Encoding::Utf32Le, 4))
+        );
+        assert_eq!(
+            detect_bom(b"\x00\x00\xFE\xFF"),
+            Some((Encoding::Utf32Be, 4))
+        );
+        assert_eq!(detect_bom(b"Hi"), None);
+        assert_eq!(detect_bom(b"\xEF\xBB"), None);
+        assert_eq!(detect_bom(b""), None);
+    }
+
+    #[test]
+    fn round_trip_01() {
+        for &encoding in &[
+            Encoding::Utf8,
+            Encoding::Utf16Le,
+            Encoding::Utf16Be,
+            Encoding::Utf32Le,
+            Encoding::Utf32Be,
+        ] {
+            for &with_bom in &[false, true] {
+                let bytes = encode(TEXT, encoding, with_bom);
+                assert_eq!(bytes.starts_with(encoding.bom()), with_bom);
+
+                // The byte order mark overrides the default encoding.
+                let default_encoding = if with_bom { Encoding::Latin1 } else { encoding };
+                let (r, detected, has_bom) = from_reader(&bytes[..], default_encoding).unwrap();
+                assert_eq!(r, TEXT);
+                assert_eq!(detected, encoding);
+                assert_eq!(has_bom, with_bom);
+
+                let unmarked = &bytes[(encoding.bom().len() * with_bom as usize)..];
+                for piece_len in 1..6 {
+                    assert_eq!(decode_in_pieces(unmarked, encoding, piece_len), TEXT);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn latin_1_01() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let text: String = (0..=255u8).map(|b| b as char).collect();
+
+        assert_eq!(decode_in_pieces(&bytes, Encoding::Latin1, 7), text.as_str());
+        assert_eq!(encode(&text, Encoding::Latin1, true), bytes);
+
+        let mut out = Vec::new();
+        let err = Encoder::new(Encoding::Latin1, false)
+            .encode("ab€c", &mut out)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(out, b"ab");
+    }
+
+    #[test]
+    fn windows_1252_01() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let r = decode_in_pieces(&bytes, Encoding::Windows1252, 7);
+
+        assert_eq!(r.len_chars(), 256);
+        assert_eq!(r.char(0x80), '€');
+        assert_eq!(r.char(0x93), '“');
+        assert_eq!(r.char(0x81), '\u{81}');
+        assert_eq!(r.char(0xE9), 'é');
+        assert_eq!(encode(&r.to_string(), Encoding::Windows1252, false), bytes);
+
+        let mut out = Vec::new();
+        assert!(Encoder::new(Encoding::Windows1252, false)
+            .encode("\u{80}", &mut out)
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_01() {
+        // Lone surrogates, a swapped pair, and a truncated pair at the end.
+        let units: &[u16] = &[0x61, 0xDC00, 0x62, 0xD800, 0x63, 0xDC00, 0xD800, 0xD83D];
+        let bytes: Vec<u8> = units.iter().flat_map(|u| u.to_le_bytes()).collect();
+        for piece_len in 1..4 {
+            assert_eq!(
+                decode_in_pieces(&bytes, Encoding::Utf16Le, piece_len),
+                "a\u{FFFD}b\u{FFFD}c\u{FFFD}\u{FFFD}\u{FFFD}"
+            );
+        }
+
+        // An odd trailing byte.
+        assert_eq!(
+            decode_in_pieces(b"\x00a\x00", Encoding::Utf16Be, 1),
+            "a\u{FFFD}"
+        );
+
+        // Out of range and surrogate code points, and a truncated unit.
+        assert_eq!(
+            decode_in_pieces(
+                b"\x00\x00\x11\x00\x00\xD8\x00\x00a\x00\x00\x00\x00",
+                Encoding::Utf32Le,
+                3
+            ),
+            "\u{FFFD}\u{FFFD}a\u{FFFD}"
+        );
+    }
+
+    #[test]
+    fn write_to_01() {
+        let mut out = Vec::new();
+        write_to(Rope::new().slice(..), &mut out, Encoding::Utf16Be, true).unwrap();
+        assert_eq!(out, b"\xFE\xFF");
+
+        let r = Rope::from_str(TEXT);
+        let mut out = Vec::new();
+        write_to(r.slice(3..40), &mut out, Encoding::Utf32Be, false).unwrap();
+        assert_eq!(out.len(), 37 * 4);
+    }
+}
@@ -64,16 +64,25 @@
 //! the direction of the iterator in-place, without changing its position in
 //! the text.
 
+use std::collections::VecDeque;
+use std::ops::Range;
 use std::str;
 use std::sync::Arc;
 
+use crate::line_ending::LineEnding;
+use crate::search::{fold_case, Finder, Kmp};
 use crate::slice::{RSEnum, RopeSlice};
 use crate::str_utils::{
-    byte_to_line_idx, char_to_byte_idx, count_chars, count_utf16_surrogates, ends_with_line_break,
-    last_line_start_byte_idx, line_to_byte_idx, trim_line_break,
+    byte_to_line_idx, char_to_byte_idx, count_chars, count_line_breaks, count_utf16_surrogates,
+    ends_with_line_break, last_line_start_byte_idx, line_to_byte_idx, trim_line_break,
 };
 use crate::tree::{Count, Node, TextInfo};
 
+#[cfg(feature = "graphemes")]
+use std::borrow::Cow;
+#[cfg(feature = "graphemes")]
+use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
+
 //==========================================================
 
 /// An iterator over a `Rope`'s bytes.
@@ -541,6 +550,114 @@ impl<'a> ExactSizeIterator for Chars<'a> {}
 
 //==========================================================
 
+/// An iterator over a `Rope`'s chars, along with their positions.
+///
+/// Each char is yielded as a `(char_idx, byte_idx, char)` tuple, where the
+/// indices are those of the start of the char, relative to the start of the
+/// `Rope`/`RopeSlice` the iterator was created from.
+#[derive(Debug, Clone)]
+pub struct CharIndices<'a> {
+    chars: Chars<'a>,
+    char_idx: usize,
+    byte_idx: usize,
+    is_reversed: bool,
+}
+
+impl<'a> CharIndices<'a> {
+    /// Wraps a `Chars` iterator positioned at the given char/byte index.
+    pub(crate) fn new(chars: Chars<'a>, char_idx: usize, byte_idx: usize) -> CharIndices<'a> {
+        CharIndices {
+            chars: chars,
+            char_idx: char_idx,
+            byte_idx: byte_idx,
+            is_reversed: false,
+        }
+    }
+
+    /// Reverses the direction of the iterator in-place.
+    ///
+    /// In other words, swaps the behavior of [`prev()`](CharIndices::prev())
+    /// and [`next()`](CharIndices::next()).
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.chars.reverse();
+        self.is_reversed = !self.is_reversed;
+    }
+
+    /// Same as `reverse()`, but returns itself.
+    ///
+    /// This is useful when chaining iterator methods:
+    ///
+    /// ```rust
+    /// # use ropey::Rope;
+    /// # let rope = Rope::from_str("Hello there\n world!\n");
+    /// // Print the rope's chars and their indices in reverse.
+    /// for (char_idx, byte_idx, ch) in rope.char_indices_at(rope.len_chars()).reversed() {
+    ///     println!("{} {} {}", char_idx, byte_idx, ch);
+    /// #   assert_eq!(ch, rope.char(char_idx));
+    /// }
+    #[inline]
+    #[must_use]
+    pub fn reversed(mut self) -> CharIndices<'a> {
+        self.reverse();
+        self
+    }
+
+    /// Advances the iterator backwards and returns the previous value.
+    ///
+    /// Runs in amortized O(1) time and worst-case O(log N) time.
+    #[inline(always)]
+    pub fn prev(&mut self) -> Option<(usize, usize, char)> {
+        let ch = self.chars.prev()?;
+        if !self.is_reversed {
+            Some(self.step_back(ch))
+        } else {
+            Some(self.step_forward(ch))
+        }
+    }
+
+    #[inline(always)]
+    fn step_forward(&mut self, ch: char) -> (usize, usize, char) {
+        let out = (self.char_idx, self.byte_idx, ch);
+        self.char_idx += 1;
+        self.byte_idx += ch.len_utf8();
+        out
+    }
+
+    #[inline(always)]
+    fn step_back(&mut self, ch: char) -> (usize, usize, char) {
+        self.char_idx -= 1;
+        self.byte_idx -= ch.len_utf8();
+        (self.char_idx, self.byte_idx, ch)
+    }
+}
+
+impl<'a> Iterator for CharIndices<'a> {
+    type Item = (usize, usize, char);
+
+    /// Advances the iterator forward and returns the next value.
+    ///
+    /// Runs in amortized O(1) time and worst-case O(log N) time.
+    #[inline(always)]
+    fn next(&mut self) -> Option<(usize, usize, char)> {
+        let ch = self.chars.next()?;
+        if !self.is_reversed {
+            Some(self.step_forward(ch))
+        } else {
+            Some(self.step_back(ch))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for CharIndices<'a> {}
+
+//==========================================================
+
 /// An iterator over a `Rope`'s lines.
 ///
 /// The returned lines include the line break at the end, if any.
@@ -1249,6 +1366,241 @@ impl ExactSizeIterator for Lines<'_> {}
 
 //==========================================================
 
+/// An iterator over a `Rope`'s lines, without their line breaks.
+///
+/// This is the same as [`Lines`], except that the line break at the end of
+/// each line (if any) is left off of the yielded line.
+#[derive(Debug, Clone)]
+pub struct LinesWithoutEndings<'a> {
+    lines: Lines<'a>,
+}
+
+impl<'a> LinesWithoutEndings<'a> {
+    pub(crate) fn new(lines: Lines<'a>) -> LinesWithoutEndings<'a> {
+        LinesWithoutEndings { lines: lines }
+    }
+
+    /// Reverses the direction of the iterator in-place.
+    ///
+    /// In other words, swaps the behavior of
+    /// [`prev()`](LinesWithoutEndings::prev()) and
+    /// [`next()`](LinesWithoutEndings::next()).
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.lines.reverse();
+    }
+
+    /// Same as `reverse()`, but returns itself.
+    ///
+    /// This is useful when chaining iterator methods:
+    ///
+    /// ```rust
+    /// # use ropey::Rope;
+    /// # let rope = Rope::from_str("Hello there\n world!\n");
+    /// // Print the rope's lines in reverse, without their line breaks.
+    /// for line in rope.lines_without_endings_at(rope.len_lines()).reversed() {
+    ///     println!("{}", line);
+    /// #   assert!(!line.chars().any(|c| c == '\n'));
+    /// }
+    #[inline]
+    #[must_use]
+    pub fn reversed(mut self) -> LinesWithoutEndings<'a> {
+        self.reverse();
+        self
+    }
+
+    /// Advances the iterator backwards and returns the previous value.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn prev(&mut self) -> Option<RopeSlice<'a>> {
+        self.lines.prev().map(|line| split_line_ending(line).0)
+    }
+}
+
+impl<'a> Iterator for LinesWithoutEndings<'a> {
+    type Item = RopeSlice<'a>;
+
+    /// Advances the iterator forward and returns the next value.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    fn next(&mut self) -> Option<RopeSlice<'a>> {
+        self.lines.next().map(|line| split_line_ending(line).0)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+impl ExactSizeIterator for LinesWithoutEndings<'_> {}
+
+//==========================================================
+
+/// A line of a `Rope`, as yielded by the [`LineInfos`] iterator.
+#[derive(Debug, Clone)]
+pub struct LineInfo<'a> {
+    /// The text of the line, without its line break.
+    pub content: RopeSlice<'a>,
+    /// The line break at the end of the line, or `None` for the last line
+    /// (which never has one).
+    pub ending: Option<LineEnding>,
+    /// The char range of the whole line, including its line break.
+    pub char_range: Range<usize>,
+    /// The byte range of the whole line, including its line break.
+    pub byte_range: Range<usize>,
+}
+
+/// An iterator over a `Rope`'s lines, along with their line breaks and
+/// positions.
+///
+/// Each line is yielded as a [`LineInfo`], which splits the line into its
+/// content and the kind of line break it ends with, and gives the char and
+/// byte range the line occupies relative to the start of the
+/// `Rope`/`RopeSlice` the iterator was created from.
+#[derive(Debug, Clone)]
+pub struct LineInfos<'a> {
+    lines: Lines<'a>,
+    char_idx: usize,
+    byte_idx: usize,
+    is_reversed: bool,
+}
+
+impl<'a> LineInfos<'a> {
+    /// Wraps a `Lines` iterator positioned at the line starting at the given
+    /// char/byte index.
+    pub(crate) fn new(lines: Lines<'a>, char_idx: usize, byte_idx: usize) -> LineInfos<'a> {
+        LineInfos {
+            lines: lines,
+            char_idx: char_idx,
+            byte_idx: byte_idx,
+            is_reversed: false,
+        }
+    }
+
+    /// Reverses the direction of the iterator in-place.
+    ///
+    /// In other words, swaps the behavior of [`prev()`](LineInfos::prev())
+    /// and [`next()`](LineInfos::next()).
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.lines.reverse();
+        self.is_reversed = !self.is_reversed;
+    }
+
+    /// Same as `reverse()`, but returns itself.
+    ///
+    /// This is useful when chaining iterator methods:
+    ///
+    /// ```rust
+    /// # use ropey::Rope;
+    /// # let rope = Rope::from_str("Hello there\n world!\n");
+    /// // Print the rope's lines in reverse, along with their char ranges.
+    /// for info in rope.line_infos_at(rope.len_lines()).reversed() {
+    ///     println!("{:?} {}", info.char_range, info.content);
+    /// #   assert_eq!(rope.slice(info.char_range.clone()).len_bytes(), info.byte_range.len());
+    /// }
+    #[inline]
+    #[must_use]
+    pub fn reversed(mut self) -> LineInfos<'a> {
+        self.reverse();
+        self
+    }
+
+    /// Advances the iterator backwards and returns the previous value.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn prev(&mut self) -> Option<LineInfo<'a>> {
+        let line = self.lines.prev()?;
+        if !self.is_reversed {
+            Some(self.step_back(line))
+        } else {
+            Some(self.step_forward(line))
+        }
+    }
+
+    #[inline]
+    fn step_forward(&mut self, line: RopeSlice<'a>) -> LineInfo<'a> {
+        let char_start = self.char_idx;
+        let byte_start = self.byte_idx;
+        self.char_idx += line.len_chars();
+        self.byte_idx += line.len_bytes();
+        LineInfo::new(line, char_start..self.char_idx, byte_start..self.byte_idx)
+    }
+
+    #[inline]
+    fn step_back(&mut self, line: RopeSlice<'a>) -> LineInfo<'a> {
+        let char_end = self.char_idx;
+        let byte_end = self.byte_idx;
+        self.char_idx -= line.len_chars();
+        self.byte_idx -= line.len_bytes();
+        LineInfo::new(line, self.char_idx..char_end, self.byte_idx..byte_end)
+    }
+}
+
+impl<'a> Iterator for LineInfos<'a> {
+    type Item = LineInfo<'a>;
+
+    /// Advances the iterator forward and returns the next value.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    fn next(&mut self) -> Option<LineInfo<'a>> {
+        let line = self.lines.next()?;
+        if !self.is_reversed {
+            Some(self.step_forward(line))
+        } else {
+            Some(self.step_back(line))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+impl ExactSizeIterator for LineInfos<'_> {}
+
+impl<'a> LineInfo<'a> {
+    fn new(
+        line: RopeSlice<'a>,
+        char_range: Range<usize>,
+        byte_range: Range<usize>,
+    ) -> LineInfo<'a> {
+        let (content, ending) = split_line_ending(line);
+        LineInfo {
+            content: content,
+            ending: ending,
+            char_range: char_range,
+            byte_range: byte_range,
+        }
+    }
+}
+
+/// Splits a line into its content and the line break it ends with, if any.
+fn split_line_ending(line: RopeSlice<'_>) -> (RopeSlice<'_>, Option<LineEnding>) {
+    if line.len_bytes() == 0 {
+        return (line, None);
+    }
+
+    // The whole line break is always within the last chunk, since CRLF pairs
+    // are never split across chunks.
+    let (tail, ..) = line.chunk_at_byte(line.len_bytes() - 1);
+    match LineEnding::at_end_of(tail) {
+        Some(ending) => (
+            line.byte_slice(..(line.len_bytes() - ending.len_bytes())),
+            Some(ending),
+        ),
+        None => (line, None),
+    }
+}
+
+//==========================================================
+
 /// An iterator over a `Rope`'s contiguous `str` chunks.
 ///
 /// Internally, each `Rope` stores text as a segemented collection of utf8
@@ -1714,70 +2066,1099 @@ impl<'a> Iterator for Chunks<'a> {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    #![allow(clippy::while_let_on_iterator)]
-    use super::*;
-    use crate::Rope;
+//==========================================================
 
-    const TEXT: &str = "\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
-                        isn't it?  Aren't you glad we're alive?\r\n\
-                        こんにちは！元気ですか？日はいいですね。\
-                        私たちが生きだって嬉しいではないか？\r\n\
-                        Hello there!  How're you doing?  It's a fine day, \
+/// An iterator over a `Rope`'s contiguous `str` chunks, along with their
+/// positions.
+///
+/// Each chunk is yielded as a `(chunk, byte_idx, char_idx, line_idx)`
+/// tuple, where the indices are the byte and char index of the start of
+/// the chunk and the index of the line it starts on, relative to the start
+/// of the `Rope`/`RopeSlice` the iterator was created from.
+///
+/// The same guarantees about the yielded chunks apply as for
+/// [`Chunks`].
+#[derive(Debug, Clone)]
+pub struct ChunkIndices<'a> {
+    chunks: Chunks<'a>,
+    byte_idx: usize,
+    char_idx: usize,
+    line_idx: usize,
+    is_reversed: bool,
+}
+
+impl<'a> ChunkIndices<'a> {
+    /// Wraps a `Chunks` iterator, given the byte/char/line index of the start
+    /// of the chunk its `next()` would yield.
+    pub(crate) fn new(
+        chunks: Chunks<'a>,
+        byte_idx: usize,
+        char_idx: usize,
+        line_idx: usize,
+    ) -> ChunkIndices<'a> {
+        ChunkIndices {
+            chunks: chunks,
+            byte_idx: byte_idx,
+            char_idx: char_idx,
+            line_idx: line_idx,
+            is_reversed: false,
+        }
+    }
+
+    /// Reverses the direction of the iterator in-place.
+    ///
+    /// In other words, swaps the behavior of [`prev()`](ChunkIndices::prev())
+    /// and [`next()`](ChunkIndices::next()).
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.chunks.reverse();
+        self.is_reversed = !self.is_reversed;
+    }
+
+    /// Same as `reverse()`, but returns itself.
+    ///
+    /// This is useful when chaining iterator methods:
+    ///
+    /// ```rust
+    /// # use ropey::Rope;
+    /// # let rope = Rope::from_str("Hello there\n world!\n");
+    /// // Print the rope's chunks and their positions in reverse.
+    /// for (chunk, byte_idx, char_idx, line_idx) in
+    ///     rope.chunk_indices_at_byte(rope.len_bytes()).reversed()
+    /// {
+    ///     println!("{} {} {} {}", byte_idx, char_idx, line_idx, chunk);
+    /// #   assert_eq!(rope.byte_slice(byte_idx..(byte_idx + chunk.len())), chunk);
+    /// }
+    #[inline]
+    #[must_use]
+    pub fn reversed(mut self) -> ChunkIndices<'a> {
+        self.reverse();
+        self
+    }
+
+    /// Advances the iterator backwards and returns the previous value.
+    ///
+    /// Runs in amortized O(1) time and worst-case O(log N) time.
+    #[inline(always)]
+    pub fn prev(&mut self) -> Option<(&'a str, usize, usize, usize)> {
+        let chunk = self.chunks.prev()?;
+        if !self.is_reversed {
+            Some(self.step_back(chunk))
+        } else {
+            Some(self.step_forward(chunk))
+        }
+    }
+
+    #[inline]
+    fn step_forward(&mut self, chunk: &'a str) -> (&'a str, usize, usize, usize) {
+        let out = (chunk, self.byte_idx, self.char_idx, self.line_idx);
+        self.byte_idx += chunk.len();
+        self.char_idx += count_chars(chunk);
+        self.line_idx += count_line_breaks(chunk);
+        out
+    }
+
+    #[inline]
+    fn step_back(&mut self, chunk: &'a str) -> (&'a str, usize, usize, usize) {
+        self.byte_idx -= chunk.len();
+        self.char_idx -= count_chars(chunk);
+        self.line_idx -= count_line_breaks(chunk);
+        (chunk, self.byte_idx, self.char_idx, self.line_idx)
+    }
+}
+
+impl<'a> Iterator for ChunkIndices<'a> {
+    type Item = (&'a str, usize, usize, usize);
+
+    /// Advances the iterator forward and returns the next value.
+    ///
+    /// Runs in amortized O(1) time and worst-case O(log N) time.
+    #[inline(always)]
+    fn next(&mut self) -> Option<(&'a str, usize, usize, usize)> {
+        let chunk = self.chunks.next()?;
+        if !self.is_reversed {
+            Some(self.step_forward(chunk))
+        } else {
+            Some(self.step_back(chunk))
+        }
+    }
+}
+
+//==========================================================
+
+/// An iterator over the non-overlapping matches of a substring in a `Rope`.
+///
+/// Each match is yielded as the char range it occupies.  Depending on how
+/// the iterator was created, it either searches forward from the start of
+/// the text (like `str::match_indices()`) or backward from the end (like
+/// `str::rmatch_indices()`).
+///
+/// The search runs directly over the `Rope`'s chunks, and finds matches that
+/// span chunk boundaries without copying the text.
+///
+/// Unlike Ropey's other iterators, this is a one-way iterator: it can't be
+/// reversed or moved backward.
+#[derive(Debug, Clone)]
+pub struct Matches<'a> {
+    chunks: Chunks<'a>,
+    chunk: &'a str,
+    byte_idx: usize, // Search position within `chunk`.
+    char_idx: usize, // Char index of the search position.
+    len_chars: usize,
+    finder: Finder,
+    state: usize,
+    pattern_char_len: usize,
+    is_reversed: bool,
+}
+
+impl<'a> Matches<'a> {
+    /// Creates an iterator over the matches of `pattern` in `slice`, with the
+    /// search starting at `char_idx` and moving forward, or backward if
+    /// `reverse` is true.
+    pub(crate) fn new(
+        slice: &RopeSlice<'a>,
+        pattern: &str,
+        char_idx: usize,
+        reverse: bool,
+    ) -> Matches<'a> {
+        let finder = if reverse {
+            Finder::new_rev(pattern)
+        } else {
+            Finder::new(pattern)
+        };
+        Matches::with_finder(slice, finder, count_chars(pattern), char_idx, reverse)
+    }
+
+    /// Like `new()`, but with a pre-built `Finder` for the pattern, which
+    /// must match the direction of the search.
+    pub(crate) fn with_finder(
+        slice: &RopeSlice<'a>,
+        finder: Finder,
+        pattern_char_len: usize,
+        char_idx: usize,
+        reverse: bool,
+    ) -> Matches<'a> {
+        let byte_idx = slice.char_to_byte(char_idx);
+        let (mut chunks, chunk_byte_idx, _, _) = slice.chunks_at_byte(byte_idx);
+
+        let (chunk, byte_idx) = if !reverse {
+            (chunks.next().unwrap_or(""), byte_idx - chunk_byte_idx)
+        } else {
+            // Position the chunks iterator just before the chunk we start
+            // searching backward from, and then point it backward.
+            let chunk = match chunks.next() {
+                Some(chunk) if chunk_byte_idx < byte_idx => {
+                    chunks.prev();
+                    (chunk, byte_idx - chunk_byte_idx)
+                }
+                Some(_) => {
+                    chunks.prev();
+                    ("", 0)
+                }
+                None => ("", 0),
+            };
+            chunks.reverse();
+            chunk
+        };
+
+        Matches {
+            chunks: chunks,
+            chunk: chunk,
+            byte_idx: byte_idx,
+            char_idx: char_idx,
+            len_chars: slice.len_chars(),
+            finder: finder,
+            state: 0,
+            pattern_char_len: pattern_char_len,
+            is_reversed: reverse,
+        }
+    }
+
+    fn next_impl(&mut self) -> Option<Range<usize>> {
+        loop {
+            let haystack = &self.chunk.as_bytes()[self.byte_idx..];
+            if let Some(end) = self.finder.find_in(&mut self.state, haystack) {
+                let end = self.byte_idx + end;
+                self.char_idx += count_chars(&self.chunk[self.byte_idx..end]);
+                self.byte_idx = end;
+                return Some((self.char_idx - self.pattern_char_len)..self.char_idx);
+            }
+
+            self.char_idx += count_chars(&self.chunk[self.byte_idx..]);
+            self.byte_idx = self.chunk.len();
+            self.chunk = self.chunks.next()?;
+            self.byte_idx = 0;
+        }
+    }
+
+    fn prev_impl(&mut self) -> Option<Range<usize>> {
+        loop {
+            let haystack = &self.chunk.as_bytes()[..self.byte_idx];
+            if let Some(start) = self.finder.rfind_in(&mut self.state, haystack) {
+                self.char_idx -= count_chars(&self.chunk[start..self.byte_idx]);
+                self.byte_idx = start;
+                return Some(self.char_idx..(self.char_idx + self.pattern_char_len));
+            }
+
+            self.char_idx -= count_chars(&self.chunk[..self.byte_idx]);
+            self.byte_idx = 0;
+            self.chunk = self.chunks.next()?;
+            self.byte_idx = self.chunk.len();
+        }
+    }
+
+    /// Matches of an empty pattern: one at every char boundary.
+    fn next_empty(&mut self) -> Option<Range<usize>> {
+        let idx = self.char_idx;
+        if !self.is_reversed {
+            if idx > self.len_chars {
+                return None;
+            }
+            self.char_idx += 1;
+        } else {
+            if self.state > 0 {
+                return None;
+            }
+            if idx == 0 {
+                // Marks the iterator as finished.
+                self.state = 1;
+            } else {
+                self.char_idx -= 1;
+            }
+        }
+        Some(idx..idx)
+    }
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = Range<usize>;
+
+    /// Advances the iterator and returns the char range of the next match.
+    ///
+    /// Runs in time linear in the length of the text searched to find the
+    /// match.
+    #[inline]
+    fn next(&mut self) -> Option<Range<usize>> {
+        if self.finder.len() == 0 {
+            self.next_empty()
+        } else if !self.is_reversed {
+            self.next_impl()
+        } else {
+            self.prev_impl()
+        }
+    }
+}
+
+//==========================================================
+
+/// An iterator over the non-overlapping case-insensitive matches of a
+/// substring in a `Rope`.
+///
+/// The text and the pattern are compared after full Unicode case folding,
+/// so a match can have a different length than the pattern: `"STRASSE"`
+/// matches all six chars of `"straße"`, for example.  Matches always cover
+/// whole chars of the text, and are yielded as their char ranges in the
+/// original text.
+///
+/// Like [`Matches`], this searches either forward or backward depending on
+/// how it was created, and is a one-way iterator.
+#[derive(Debug, Clone)]
+pub struct MatchesIgnoreCase<'a> {
+    chars: Chars<'a>,
+    char_idx: usize, // Char index of the search position.
+    len_chars: usize,
+    finder: Kmp<char>,
+    state: usize,
+    folded: Vec<char>, // Case folding of the char being searched.
+    folded_idx: usize, // Number of case-folded chars searched so far.
+    // The `(folded_idx, char_idx)` pairs of the char boundaries that a match
+    // could still start at, oldest first.
+    boundaries: VecDeque<(usize, usize)>,
+    is_reversed: bool,
+}
+
+impl<'a> MatchesIgnoreCase<'a> {
+    /// Creates an iterator over the case-insensitive matches of `pattern`
+    /// in `slice`, with the search starting at `char_idx` and moving
+    /// forward, or backward if `reverse` is true.
+    pub(crate) fn new(
+        slice: &RopeSlice<'a>,
+        pattern: &str,
+        char_idx: usize,
+        reverse: bool,
+    ) -> MatchesIgnoreCase<'a> {
+        let mut needle = Vec::new();
+        for c in pattern.chars() {
+            fold_case(c, &mut needle);
+        }
+        let mut chars = slice.chars_at(char_idx);
+        if reverse {
+            needle.reverse();
+            chars.reverse();
+        }
+
+        MatchesIgnoreCase {
+            chars: chars,
+            char_idx: char_idx,
+            len_chars: slice.len_chars(),
+            finder: Kmp::from_vec(needle),
+            state: 0,
+            folded: Vec::new(),
+            folded_idx: 0,
+            boundaries: VecDeque::new(),
+            is_reversed: reverse,
+        }
+    }
+
+    fn next_impl(&mut self) -> Option<Range<usize>> {
+        let needle_len = self.finder.len();
+        loop {
+            let c = self.chars.next()?;
+
+            // Forget boundaries too far back for any further match to start
+            // at, and record the one just before `c`.
+            while let Some(&(idx, _)) = self.boundaries.front() {
+                if idx + needle_len > self.folded_idx {
+                    break;
+                }
+                self.boundaries.pop_front();
+            }
+            self.boundaries.push_back((self.folded_idx, self.char_idx));
+
+            if !self.is_reversed {
+                self.char_idx += 1;
+            } else {
+                self.char_idx -= 1;
+            }
+            self.folded.clear();
+            fold_case(c, &mut self.folded);
+            if self.is_reversed {
+                self.folded.reverse();
+            }
+
+            for i in 0..self.folded.len() {
+                self.folded_idx += 1;
+                if !self.finder.step(&mut self.state, self.folded[i]) {
+                    continue;
+                }
+
+                // Only accept matches that cover whole chars of the text.
+                let start = self
+                    .boundaries
+                    .binary_search_by_key(&(self.folded_idx - needle_len), |b| b.0)
+                    .ok()
+                    .filter(|_| i + 1 == self.folded.len())
+                    .map(|b| self.boundaries[b].1);
+                match start {
+                    Some(start) => {
+                        self.boundaries.clear();
+                        return Some(if !self.is_reversed {
+                            start..self.char_idx
+                        } else {
+                            self.char_idx..start
+                        });
+                    }
+                    None => self.state = self.finder.overlap_state(),
+                }
+            }
+        }
+    }
+
+    /// Matches of an empty pattern: one at every char boundary.
+    fn next_empty(&mut self) -> Option<Range<usize>> {
+        let idx = self.char_idx;
+        if !self.is_reversed {
+            if idx > self.len_chars {
+                return None;
+            }
+            self.char_idx += 1;
+        } else {
+            if self.state > 0 {
+                return None;
+            }
+            if idx == 0 {
+                // Marks the iterator as finished.
+                self.state = 1;
+            } else {
+                self.char_idx -= 1;
+            }
+        }
+        Some(idx..idx)
+    }
+}
+
+impl<'a> Iterator for MatchesIgnoreCase<'a> {
+    type Item = Range<usize>;
+
+    /// Advances the iterator and returns the char range of the next match.
+    ///
+    /// Runs in time linear in the length of the text searched to find the
+    /// match.
+    #[inline]
+    fn next(&mut self) -> Option<Range<usize>> {
+        if self.finder.len() == 0 {
+            self.next_empty()
+        } else {
+            self.next_impl()
+        }
+    }
+}
+
+//==========================================================
+
+/// A pattern that a `Rope` can be split on.
+///
+/// This is implemented for `char`, `&str`, and `FnMut(char) -> bool`
+/// predicates, mirroring the patterns accepted by `str::split()`.
+pub trait Pattern {
+    #[doc(hidden)]
+    type Searcher: Searcher;
+
+    #[doc(hidden)]
+    fn into_searcher(self) -> Self::Searcher;
+}
+
+/// NOT PART OF THE PUBLIC API (hidden from docs for a reason!).
+///
+/// Finds the occurrences of a `Pattern` in a `RopeSlice`.
+#[doc(hidden)]
+pub trait Searcher {
+    /// Returns the char range of the first occurrence that starts at or
+    /// after `char_idx`.
+    fn find_in(&mut self, text: &RopeSlice, char_idx: usize) -> Option<Range<usize>>;
+
+    /// Returns the char range of the last occurrence that ends at or before
+    /// `char_idx`.
+    fn rfind_in(&mut self, text: &RopeSlice, char_idx: usize) -> Option<Range<usize>>;
+}
+
+impl Pattern for char {
+    type Searcher = CharSearcher;
+
+    #[inline]
+    fn into_searcher(self) -> CharSearcher {
+        CharSearcher(self)
+    }
+}
+
+impl<'b> Pattern for &'b str {
+    type Searcher = StrSearcher;
+
+    #[inline]
+    fn into_searcher(self) -> StrSearcher {
+        StrSearcher {
+            finder: Finder::new(self),
+            finder_rev: Finder::new_rev(self),
+            char_len: count_chars(self),
+        }
+    }
+}
+
+impl<F: FnMut(char) -> bool> Pattern for F {
+    type Searcher = CharPredicateSearcher<F>;
+
+    #[inline]
+    fn into_searcher(self) -> CharPredicateSearcher<F> {
+        CharPredicateSearcher(self)
+    }
+}
+
+/// NOT PART OF THE PUBLIC API (hidden from docs for a reason!).
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct CharSearcher(char);
+
+impl Searcher for CharSearcher {
+    #[inline]
+    fn find_in(&mut self, text: &RopeSlice, char_idx: usize) -> Option<Range<usize>> {
+        let c = self.0;
+        find_char_in(text, char_idx, |c2| c2 == c)
+    }
+
+    #[inline]
+    fn rfind_in(&mut self, text: &RopeSlice, char_idx: usize) -> Option<Range<usize>> {
+        let c = self.0;
+        rfind_char_in(text, char_idx, |c2| c2 == c)
+    }
+}
+
+/// NOT PART OF THE PUBLIC API (hidden from docs for a reason!).
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct StrSearcher {
+    finder: Finder,
+    finder_rev: Finder,
+    char_len: usize,
+}
+
+impl Searcher for StrSearcher {
+    #[inline]
+    fn find_in(&mut self, text: &RopeSlice, char_idx: usize) -> Option<Range<usize>> {
+        Matches::with_finder(text, self.finder.clone(), self.char_len, char_idx, false).next()
+    }
+
+    #[inline]
+    fn rfind_in(&mut self, text: &RopeSlice, char_idx: usize) -> Option<Range<usize>> {
+        Matches::with_finder(text, self.finder_rev.clone(), self.char_len, char_idx, true).next()
+    }
+}
+
+/// NOT PART OF THE PUBLIC API (hidden from docs for a reason!).
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct CharPredicateSearcher<F>(F);
+
+impl<F> std::fmt::Debug for CharPredicateSearcher<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CharPredicateSearcher")
+    }
+}
+
+impl<F: FnMut(char) -> bool> Searcher for CharPredicateSearcher<F> {
+    #[inline]
+    fn find_in(&mut self, text: &RopeSlice, char_idx: usize) -> Option<Range<usize>> {
+        find_char_in(text, char_idx, &mut self.0)
+    }
+
+    #[inline]
+    fn rfind_in(&mut self, text: &RopeSlice, char_idx: usize) -> Option<Range<usize>> {
+        rfind_char_in(text, char_idx, &mut self.0)
+    }
+}
+
+fn find_char_in<F>(text: &RopeSlice, char_idx: usize, mut pred: F) -> Option<Range<usize>>
+where
+    F: FnMut(char) -> bool,
+{
+    let i = char_idx + text.chars_at(char_idx).position(&mut pred)?;
+    Some(i..(i + 1))
+}
+
+fn rfind_char_in<F>(text: &RopeSlice, char_idx: usize, mut pred: F) -> Option<Range<usize>>
+where
+    F: FnMut(char) -> bool,
+{
+    let i = char_idx - text.chars_at(char_idx).reversed().position(&mut pred)? - 1;
+    Some(i..(i + 1))
+}
+
+//==========================================================
+
+/// An iterator over the pieces of a `Rope` separated by a pattern.
+///
+/// Each piece is yielded as a `RopeSlice`, without copying or allocating.
+/// This is created by `split()` and its variants, which mirror the methods
+/// of the same names on `str`.
+///
+/// Like Ropey's other iterators, this is a cursor that can move both
+/// forward and backward.  For `&str` patterns that can overlap themselves
+/// (like `"aa"`), note that searching backward can find different
+/// occurrences than searching forward, just as `str::rsplit()` can differ
+/// from `str::split()`.
+pub struct Split<'a, P: Pattern> {
+    text: RopeSlice<'a>,
+    searcher: P::Searcher,
+    // The delimiter just before the cursor, or `None` if the cursor is
+    // before the first piece.  When the cursor is at the end, this is the
+    // delimiter just before the last piece.
+    delim: Option<Range<usize>>,
+    at_end: bool,
+    // Whether `delim` is yet to be found by searching backward from the
+    // end, for iterators created at the end.
+    delim_unknown: bool,
+    // The number of delimiters that can still be crossed moving forward,
+    // for `splitn()`.
+    delims_left: Option<usize>,
+    skip_empty: bool,
+    skip_empty_last: bool,
+    is_empty: bool,
+    is_reversed: bool,
+}
+
+impl<'a, P: Pattern> Split<'a, P> {
+    /// Creates an iterator over the pieces of `slice` separated by
+    /// `pattern`, positioned before the first piece.
+    pub(crate) fn new(slice: &RopeSlice<'a>, pattern: P) -> Split<'a, P> {
+        Split {
+            text: *slice,
+            searcher: pattern.into_searcher(),
+            delim: None,
+            at_end: false,
+            delim_unknown: false,
+            delims_left: None,
+            skip_empty: false,
+            skip_empty_last: false,
+            is_empty: false,
+            is_reversed: false,
+        }
+    }
+
+    /// Like `new()`, but positioned after the last piece.
+    pub(crate) fn new_at_end(slice: &RopeSlice<'a>, pattern: P) -> Split<'a, P> {
+        let mut split = Split::new(slice, pattern);
+        split.at_end = true;
+        split.delim_unknown = true;
+        split
+    }
+
+    /// Limits the iterator to at most `n` pieces, the last of which holds
+    /// the rest of the text.
+    pub(crate) fn limit(mut self, n: usize) -> Split<'a, P> {
+        self.delims_left = Some(n.saturating_sub(1));
+        self.is_empty = n == 0;
+        self
+    }
+
+    /// Makes the iterator skip the last piece if it's empty.
+    pub(crate) fn terminator(mut self) -> Split<'a, P> {
+        self.skip_empty_last = true;
+        self
+    }
+
+    /// Makes the iterator skip all empty pieces.
+    pub(crate) fn skip_empty(mut self) -> Split<'a, P> {
+        self.skip_empty = true;
+        self
+    }
+
+    /// Advances the iterator backwards and returns the previous value.
+    ///
+    /// Runs in time linear in the length of the text searched to find the
+    /// previous delimiter.
+    #[inline(always)]
+    pub fn prev(&mut self) -> Option<RopeSlice<'a>> {
+        if !self.is_reversed {
+            self.prev_impl()
+        } else {
+            self.next_impl()
+        }
+    }
+
+    /// Reverses the direction of the iterator in-place.
+    ///
+    /// In other words, swaps the behavior of [`prev()`](Split::prev())
+    /// and [`next()`](Split::next()).
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.is_reversed = !self.is_reversed;
+    }
+
+    /// Same as `reverse()`, but returns itself.
+    ///
+    /// This is useful when chaining iterator methods:
+    ///
+    /// ```rust
+    /// # use ropey::Rope;
+    /// # let rope = Rope::from_str("a,b,c");
+    /// // Skip over the rope's first three comma-separated fields, and then
+    /// // walk back over them.
+    /// let mut fields = rope.split(',');
+    /// fields.nth(2);
+    /// for (i, field) in fields.reversed().enumerate() {
+    ///     println!("{} {}", i, field);
+    /// #   assert_eq!(field, ["c", "b", "a"][i]);
+    /// }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn reversed(mut self) -> Split<'a, P> {
+        self.reverse();
+        self
+    }
+
+    fn next_impl(&mut self) -> Option<RopeSlice<'a>> {
+        let len = self.text.len_chars();
+        loop {
+            if self.at_end || self.is_empty {
+                return None;
+            }
+
+            let start = self.delim.as_ref().map_or(0, |d| d.end);
+            let next_delim = if self.delims_left == Some(0) {
+                None
+            } else {
+                // An empty delimiter can't be found again in the same place.
+                let from = match self.delim {
+                    Some(ref d) if d.is_empty() => d.end + 1,
+                    _ => start,
+                };
+                if from <= len {
+                    self.searcher.find_in(&self.text, from)
+                } else {
+                    None
+                }
+            };
+            let end = match next_delim {
+                Some(d) => {
+                    let end = d.start;
+                    self.delim = Some(d);
+                    self.delims_left = self.delims_left.map(|n| n - 1);
+                    end
+                }
+                None => {
+                    self.at_end = true;
+                    len
+                }
+            };
+
+            if start == end && (self.skip_empty || (self.at_end && self.skip_empty_last)) {
+                continue;
+            }
+            return Some(self.text.slice(start..end));
+        }
+    }
+
+    fn prev_impl(&mut self) -> Option<RopeSlice<'a>> {
+        let len = self.text.len_chars();
+        loop {
+            if self.is_empty {
+                return None;
+            }
+
+            let (start, end) = if self.at_end {
+                if self.delim_unknown {
+                    self.delim = self.searcher.rfind_in(&self.text, len);
+                    self.delim_unknown = false;
+                }
+                self.at_end = false;
+                let start = self.delim.as_ref().map_or(0, |d| d.end);
+                if start == len && self.skip_empty_last {
+                    continue;
+                }
+                (start, len)
+            } else {
+                let delim = self.delim.take()?;
+                self.delim = if delim.is_empty() {
+                    // An empty delimiter can't be found again in the same
+                    // place.
+                    if delim.start == 0 {
+                        None
+                    } else {
+                        self.searcher.rfind_in(&self.text, delim.start - 1)
+                    }
+                } else {
+                    self.searcher.rfind_in(&self.text, delim.start)
+                };
+                self.delims_left = self.delims_left.map(|n| n + 1);
+                (self.delim.as_ref().map_or(0, |d| d.end), delim.start)
+            };
+
+            if start == end && self.skip_empty {
+                continue;
+            }
+            return Some(self.text.slice(start..end));
+        }
+    }
+}
+
+impl<'a, P: Pattern> Iterator for Split<'a, P> {
+    type Item = RopeSlice<'a>;
+
+    /// Advances the iterator forward and returns the next value.
+    ///
+    /// Runs in time linear in the length of the text searched to find the
+    /// next delimiter.
+    #[inline(always)]
+    fn next(&mut self) -> Option<RopeSlice<'a>> {
+        if !self.is_reversed {
+            self.next_impl()
+        } else {
+            self.prev_impl()
+        }
+    }
+}
+
+impl<'a, P: Pattern> Clone for Split<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Split {
+            text: self.text,
+            searcher: self.searcher.clone(),
+            delim: self.delim.clone(),
+            at_end: self.at_end,
+            delim_unknown: self.delim_unknown,
+            delims_left: self.delims_left,
+            skip_empty: self.skip_empty,
+            skip_empty_last: self.skip_empty_last,
+            is_empty: self.is_empty,
+            is_reversed: self.is_reversed,
+        }
+    }
+}
+
+impl<'a, P: Pattern> std::fmt::Debug for Split<'a, P>
+where
+    P::Searcher: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Split")
+            .field("text", &self.text)
+            .field("searcher", &self.searcher)
+            .field("delim", &self.delim)
+            .field("at_end", &self.at_end)
+            .field("delim_unknown", &self.delim_unknown)
+            .field("delims_left", &self.delims_left)
+            .field("skip_empty", &self.skip_empty)
+            .field("skip_empty_last", &self.skip_empty_last)
+            .field("is_empty", &self.is_empty)
+            .field("is_reversed", &self.is_reversed)
+            .finish()
+    }
+}
+
+//==========================================================
+
+/// An iterator over a `Rope`'s extended grapheme clusters.
+///
+/// Each grapheme is yielded as a `RopeSlice`, since a grapheme can span
+/// chunk boundaries.
+///
+/// Unlike Ropey's other iterators, this is a standard double-ended
+/// iterator: `next()` and `next_back()` consume graphemes from the front
+/// and back respectively, meeting in the middle.
+///
+/// Only available with the `graphemes` feature.
+#[cfg(feature = "graphemes")]
+#[derive(Debug, Clone)]
+pub struct Graphemes<'a> {
+    text: RopeSlice<'a>,
+    front: GraphemeStepper<'a>,
+    back: GraphemeStepper<'a>,
+}
+
+#[cfg(feature = "graphemes")]
+impl<'a> Graphemes<'a> {
+    pub(crate) fn new(slice: &RopeSlice<'a>) -> Graphemes<'a> {
+        let len = slice.len_bytes();
+        Graphemes {
+            text: *slice,
+            front: GraphemeStepper::new(slice, 0),
+            back: GraphemeStepper::new(slice, len),
+        }
+    }
+}
+
+#[cfg(feature = "graphemes")]
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = RopeSlice<'a>;
+
+    /// Advances the iterator forward and returns the next grapheme.
+    ///
+    /// Runs in time proportional to the grapheme's length, plus O(log N)
+    /// time for each chunk boundary the grapheme crosses.
+    #[inline]
+    fn next(&mut self) -> Option<RopeSlice<'a>> {
+        let start = self.front.byte_idx;
+        if start >= self.back.byte_idx {
+            return None;
+        }
+        let end = self.front.next_boundary(&self.text);
+        Some(self.text.byte_slice(start..end))
+    }
+}
+
+#[cfg(feature = "graphemes")]
+impl<'a> DoubleEndedIterator for Graphemes<'a> {
+    /// Advances the iterator backward and returns the previous grapheme.
+    ///
+    /// Runs in time proportional to the grapheme's length, plus O(log N)
+    /// time for each chunk boundary the grapheme crosses.
+    #[inline]
+    fn next_back(&mut self) -> Option<RopeSlice<'a>> {
+        let end = self.back.byte_idx;
+        if end <= self.front.byte_idx {
+            return None;
+        }
+        let start = self.back.prev_boundary(&self.text);
+        Some(self.text.byte_slice(start..end))
+    }
+}
+
+/// A position in a `RopeSlice` along with the chunk it's in, for stepping
+/// across grapheme boundaries.
+///
+/// Each step runs a fresh `GraphemeCursor` over the current chunk, feeding
+/// it neighboring chunks as it asks for them.  Only pre-context requests
+/// fall back to copying text, into a contiguous buffer (see `query()`).
+#[cfg(feature = "graphemes")]
+#[derive(Debug, Clone)]
+pub(crate) struct GraphemeStepper<'a> {
+    chunk: &'a str,
+    chunk_byte_idx: usize,
+    byte_idx: usize,
+}
+
+#[cfg(feature = "graphemes")]
+impl<'a> GraphemeStepper<'a> {
+    /// Creates a stepper positioned at `byte_idx`, which must be a char
+    /// boundary within `slice`.
+    pub(crate) fn new(slice: &RopeSlice<'a>, byte_idx: usize) -> GraphemeStepper<'a> {
+        let (chunk, chunk_byte_idx, _, _) = slice.chunk_at_byte(byte_idx);
+        GraphemeStepper {
+            chunk: chunk,
+            chunk_byte_idx: chunk_byte_idx,
+            byte_idx: byte_idx,
+        }
+    }
+
+    /// Moves to the next grapheme boundary and returns its byte index, or
+    /// stays put and returns the current index if already at the end.
+    pub(crate) fn next_boundary(&mut self, slice: &RopeSlice<'a>) -> usize {
+        if let Some(n) = self.query(slice, |c, chunk, start| c.next_boundary(chunk, start)) {
+            self.byte_idx = n;
+            // Keep the chunk cache on the chunk that follows the boundary.
+            if n >= self.chunk_byte_idx + self.chunk.len() && n < slice.len_bytes() {
+                let (chunk, chunk_byte_idx, _, _) = slice.chunk_at_byte(n);
+                self.chunk = chunk;
+                self.chunk_byte_idx = chunk_byte_idx;
+            }
+        }
+        self.byte_idx
+    }
+
+    /// Moves to the previous grapheme boundary and returns its byte index,
+    /// or stays put and returns the current index if already at the start.
+    pub(crate) fn prev_boundary(&mut self, slice: &RopeSlice<'a>) -> usize {
+        if let Some(n) = self.query(slice, |c, chunk, start| c.prev_boundary(chunk, start)) {
+            self.byte_idx = n;
+            // Keep the chunk cache on the chunk that precedes the boundary.
+            if n <= self.chunk_byte_idx && n > 0 {
+                let (chunk, chunk_byte_idx, _, _) = slice.chunk_at_byte(n - 1);
+                self.chunk = chunk;
+                self.chunk_byte_idx = chunk_byte_idx;
+            }
+        }
+        self.byte_idx
+    }
+
+    /// Returns whether the current position is a grapheme boundary.
+    pub(crate) fn is_boundary(&self, slice: &RopeSlice<'a>) -> bool {
+        self.query(slice, |c, chunk, start| c.is_boundary(chunk, start))
+    }
+
+    /// Runs `f` with a cursor at the current position, starting with the
+    /// current chunk and moving to the neighboring chunks as the cursor asks
+    /// for them.
+    ///
+    /// Pre-context is the exception: `GraphemeCursor` mishandles pre-context
+    /// given to it with `provide_context()` in emoji zwj sequences (it takes
+    /// the last char of the context to be the zwj).  So when it asks for
+    /// some, the cursor is restarted on a contiguous copy of the text that
+    /// reaches back further, doubling the number of chunks reached back each
+    /// time so that the copying stays linear.  This only happens when a
+    /// chunk boundary falls inside the text a boundary decision depends on.
+    fn query<T, F>(&self, slice: &RopeSlice<'a>, f: F) -> T
+    where
+        F: Fn(&mut GraphemeCursor, &str, usize) -> std::result::Result<T, GraphemeIncomplete>,
+    {
+        let len = slice.len_bytes();
+        let mut cursor = GraphemeCursor::new(self.byte_idx, len, true);
+        let mut chunk = Cow::Borrowed(self.chunk);
+        let mut chunk_byte_idx = self.chunk_byte_idx;
+        let mut reach_back = 1;
+        loop {
+            match f(&mut cursor, &chunk, chunk_byte_idx) {
+                Ok(out) => return out,
+                Err(GraphemeIncomplete::PrevChunk) => {
+                    let (prev_chunk, prev_byte_idx, _, _) = slice.chunk_at_byte(chunk_byte_idx - 1);
+                    chunk = Cow::Borrowed(prev_chunk);
+                    chunk_byte_idx = prev_byte_idx;
+                }
+                Err(GraphemeIncomplete::NextChunk) => {
+                    chunk_byte_idx += chunk.len();
+                    chunk = Cow::Borrowed(slice.chunk_at_byte(chunk_byte_idx).0);
+                }
+                Err(GraphemeIncomplete::PreContext(n)) => {
+                    let mut start = n;
+                    for _ in 0..reach_back {
+                        if start == 0 {
+                            break;
+                        }
+                        start = slice.chunk_at_byte(start - 1).1;
+                    }
+                    reach_back *= 2;
+
+                    // The window must still contain the current position.
+                    let start = start.min(self.chunk_byte_idx);
+                    let end =
+                        (chunk_byte_idx + chunk.len()).max(self.chunk_byte_idx + self.chunk.len());
+                    chunk = Cow::Owned(String::from(slice.byte_slice(start..end)));
+                    chunk_byte_idx = start;
+                    cursor = GraphemeCursor::new(self.byte_idx, len, true);
+                }
+                Err(GraphemeIncomplete::InvalidOffset) => unreachable!(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::while_let_on_iterator)]
+    use super::*;
+    use crate::str_utils::byte_to_char_idx;
+    use crate::Rope;
+
+    const TEXT: &str = "\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
+                        isn't it?  Aren't you glad we're alive?\r\n\
+                        こんにちは！元気ですか？日はいいですね。\
+                        私たちが生きだって嬉しいではないか？\r\n\
+                        Hello there!  How're you doing?  It's a fine day, \
                         isn't it?  Aren't you glad we're alive?\r\n\
                         こんにちは！元気ですか？日はいいですね。\
                         私たちが生きだって嬉しいではないか？\r\n\
@@ -3064,164 +4445,427 @@ mod tests {
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_at_char_02() {
+    fn chunks_at_char_02() {
+        let r = Rope::from_str(TEXT);
+        let s = r.slice(34..301);
+
+        for i in 0..s.len_chars() {
+            let (chunk, b, c, l) = s.chunk_at_char(i);
+            let (mut chunks, bs, cs, ls) = s.chunks_at_char(i);
+
+            assert_eq!(b, bs);
+            assert_eq!(c, cs);
+            assert_eq!(l, ls);
+            assert_eq!(Some(chunk), chunks.next());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_at_char_03() {
+        let r = Rope::from_str(TEXT);
+        let s = r.slice(34..301);
+
+        let (mut chunks, _, _, _) = s.chunks_at_char(s.len_chars());
+        assert_eq!(chunks.next(), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_at_char_04() {
+        let r = Rope::from_str(TEXT);
+        let s = r.slice(34..34);
+
+        let (mut chunks, _, _, _) = s.chunks_at_char(0);
+        assert_eq!(chunks.next(), None);
+
+        let (mut chunks, _, _, _) = s.chunks_at_char(0);
+        assert_eq!(chunks.prev(), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_at_line_break_01() {
+        let r = Rope::from_str(TEXT);
+
+        for i in 0..r.len_lines() {
+            let (chunk, b, c, l) = r.chunk_at_line_break(i);
+            let (mut chunks, bs, cs, ls) = r.chunks_at_line_break(i);
+
+            assert_eq!(b, bs);
+            assert_eq!(c, cs);
+            assert_eq!(l, ls);
+            assert_eq!(Some(chunk), chunks.next());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_at_line_break_02() {
+        let r = Rope::from_str(TEXT);
+        let s = r.slice(34..301);
+
+        for i in 0..s.len_lines() {
+            let (chunk, b, c, l) = s.chunk_at_line_break(i);
+            let (mut chunks, bs, cs, ls) = s.chunks_at_line_break(i);
+
+            assert_eq!(Some(chunk), chunks.next());
+            assert_eq!(b, bs);
+            assert_eq!(c, cs);
+            assert_eq!(l, ls);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_at_line_break_03() {
+        let r = Rope::from_str(TEXT);
+        let s = r.slice(34..301);
+
+        let (mut chunks, _, _, _) = s.chunks_at_line_break(s.len_lines());
+        assert_eq!(chunks.next(), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_at_line_break_04() {
+        let r = Rope::from_str(TEXT);
+        let s = r.slice(34..34);
+
+        let (mut chunks, _, _, _) = s.chunks_at_line_break(0);
+        assert_eq!(chunks.next(), None);
+
+        let (mut chunks, _, _, _) = s.chunks_at_line_break(0);
+        assert_eq!(chunks.prev(), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_reverse_01() {
+        let r = Rope::from_str(TEXT);
+        let mut itr = r.chunks();
+        let mut stack = Vec::new();
+
+        for _ in 0..8 {
+            stack.push(itr.next().unwrap());
+        }
+        itr.reverse();
+        for _ in 0..8 {
+            assert_eq!(stack.pop().unwrap(), itr.next().unwrap());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_reverse_02() {
+        let r = Rope::from_str(TEXT);
+        let mut itr = r.chunks_at_char(r.len_chars() / 3).0;
+        let mut stack = Vec::new();
+
+        for _ in 0..8 {
+            stack.push(itr.next().unwrap());
+        }
+        itr.reverse();
+        for _ in 0..8 {
+            assert_eq!(stack.pop().unwrap(), itr.next().unwrap());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_reverse_03() {
+        let r = Rope::from_str(TEXT);
+        let mut itr = r.chunks_at_char(r.len_chars() / 3).0;
+        let mut stack = Vec::new();
+
+        itr.reverse();
+        for _ in 0..8 {
+            stack.push(itr.next().unwrap());
+        }
+        itr.reverse();
+        for _ in 0..8 {
+            assert_eq!(stack.pop().unwrap(), itr.next().unwrap());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunks_reverse_04() {
+        let mut itr = Chunks::from_str("a\n", false);
+
+        assert_eq!(Some("a\n"), itr.next());
+        assert_eq!(None, itr.next());
+        itr.reverse();
+        assert_eq!(Some("a\n"), itr.next());
+        assert_eq!(None, itr.next());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn bytes_sliced_01() {
+        let r = Rope::from_str(TEXT);
+
+        let s_start = 34;
+        let s_end = 301;
+        let s_start_byte = r.char_to_byte(s_start);
+        let s_end_byte = r.char_to_byte(s_end);
+
+        let s1 = r.slice(s_start..s_end);
+        let s2 = &TEXT[s_start_byte..s_end_byte];
+
+        let mut s1_bytes = s1.bytes();
+        let mut s2_bytes = s2.bytes();
+
+        assert_eq!(s1, s2);
+        assert_eq!(s1.byte(0), s2.as_bytes()[0]);
+
+        assert_eq!(s1.len_bytes(), s2.len());
+        assert_eq!(s1_bytes.len(), s2.len());
+
+        for _ in 0..(s2.len() + 1) {
+            assert_eq!(s1_bytes.next(), s2_bytes.next());
+        }
+
+        assert_eq!(s1_bytes.len(), 0);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn bytes_sliced_reverse_01() {
+        let r = Rope::from_str(TEXT);
+
+        let s_start = 34;
+        let s_end = 301;
+        let s = r.slice(s_start..s_end);
+
+        let mut itr = s.bytes();
+        let mut stack = Vec::new();
+        for _ in 0..32 {
+            stack.push(itr.next().unwrap());
+        }
+        itr.reverse();
+        for _ in 0..32 {
+            assert_eq!(stack.pop(), itr.next());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn bytes_at_sliced_01() {
+        let r = Rope::from_str(TEXT);
+
+        let s_start = 34;
+        let s_end = 301;
+        let s_start_byte = r.char_to_byte(s_start);
+        let s_end_byte = r.char_to_byte(s_end);
+
+        let s1 = r.slice(s_start..s_end);
+        let s2 = &TEXT[s_start_byte..s_end_byte];
+
+        let mut bytes_1 = s2.bytes();
+        for i in 0..(s1.len_bytes() + 1) {
+            let mut bytes_2 = s1.bytes_at(i);
+            assert_eq!(bytes_1.next(), bytes_2.next());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn bytes_at_sliced_02() {
+        let r = Rope::from_str(TEXT);
+        let s = r.slice(34..301);
+        let mut bytes = s.bytes_at(s.len_bytes());
+        assert_eq!(bytes.next(), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn bytes_at_sliced_03() {
+        let r = Rope::from_str(TEXT);
+
+        let s_start = 34;
+        let s_end = 301;
+        let s_start_byte = r.char_to_byte(s_start);
+        let s_end_byte = r.char_to_byte(s_end);
+
+        let s1 = r.slice(s_start..s_end);
+        let s2 = &TEXT[s_start_byte..s_end_byte];
+
+        let mut bytes_1 = s1.bytes_at(s1.len_bytes());
+        let mut bytes_2 = s2.bytes();
+        while let Some(b) = bytes_2.next_back() {
+            assert_eq!(bytes_1.prev(), Some(b));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn bytes_at_sliced_reverse_01() {
         let r = Rope::from_str(TEXT);
-        let s = r.slice(34..301);
 
-        for i in 0..s.len_chars() {
-            let (chunk, b, c, l) = s.chunk_at_char(i);
-            let (mut chunks, bs, cs, ls) = s.chunks_at_char(i);
+        let s_start = 34;
+        let s_end = 301;
+        let s = r.slice(s_start..s_end);
 
-            assert_eq!(b, bs);
-            assert_eq!(c, cs);
-            assert_eq!(l, ls);
-            assert_eq!(Some(chunk), chunks.next());
+        let mut itr = s.bytes_at(s.len_bytes() / 3);
+        let mut stack = Vec::new();
+        for _ in 0..32 {
+            stack.push(itr.next().unwrap());
+        }
+        itr.reverse();
+        for _ in 0..32 {
+            assert_eq!(stack.pop(), itr.next());
         }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_at_char_03() {
+    fn chars_sliced_01() {
         let r = Rope::from_str(TEXT);
-        let s = r.slice(34..301);
-
-        let (mut chunks, _, _, _) = s.chunks_at_char(s.len_chars());
-        assert_eq!(chunks.next(), None);
-    }
 
-    #[test]
-    #[cfg_attr(miri, ignore)]
-    fn chunks_at_char_04() {
-        let r = Rope::from_str(TEXT);
-        let s = r.slice(34..34);
+        let s_start = 34;
+        let s_end = 301;
+        let s_start_byte = r.char_to_byte(s_start);
+        let s_end_byte = r.char_to_byte(s_end);
 
-        let (mut chunks, _, _, _) = s.chunks_at_char(0);
-        assert_eq!(chunks.next(), None);
+        let s1 = r.slice(s_start..s_end);
+        let s2 = &TEXT[s_start_byte..s_end_byte];
 
-        let (mut chunks, _, _, _) = s.chunks_at_char(0);
-        assert_eq!(chunks.prev(), None);
+        for (cr, ct) in s1.chars().zip(s2.chars()) {
+            assert_eq!(cr, ct);
+        }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_at_line_break_01() {
+    fn chars_sliced_reverse_01() {
         let r = Rope::from_str(TEXT);
 
-        for i in 0..r.len_lines() {
-            let (chunk, b, c, l) = r.chunk_at_line_break(i);
-            let (mut chunks, bs, cs, ls) = r.chunks_at_line_break(i);
+        let s_start = 34;
+        let s_end = 301;
+        let s = r.slice(s_start..s_end);
 
-            assert_eq!(b, bs);
-            assert_eq!(c, cs);
-            assert_eq!(l, ls);
-            assert_eq!(Some(chunk), chunks.next());
+        let mut itr = s.chars();
+        let mut stack = Vec::new();
+        for _ in 0..32 {
+            stack.push(itr.next().unwrap());
+        }
+        itr.reverse();
+        for _ in 0..32 {
+            assert_eq!(stack.pop(), itr.next());
         }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_at_line_break_02() {
+    fn chars_at_sliced_01() {
         let r = Rope::from_str(TEXT);
-        let s = r.slice(34..301);
 
-        for i in 0..s.len_lines() {
-            let (chunk, b, c, l) = s.chunk_at_line_break(i);
-            let (mut chunks, bs, cs, ls) = s.chunks_at_line_break(i);
+        let s_start = 34;
+        let s_end = 301;
+        let s_start_byte = r.char_to_byte(s_start);
+        let s_end_byte = r.char_to_byte(s_end);
 
-            assert_eq!(Some(chunk), chunks.next());
-            assert_eq!(b, bs);
-            assert_eq!(c, cs);
-            assert_eq!(l, ls);
+        let s1 = r.slice(s_start..s_end);
+        let s2 = &TEXT[s_start_byte..s_end_byte];
+
+        let mut chars_1 = s2.chars();
+        for i in 0..(s1.len_chars() + 1) {
+            let mut chars_2 = s1.chars_at(i);
+            assert_eq!(chars_1.next(), chars_2.next());
         }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_at_line_break_03() {
+    fn chars_at_sliced_02() {
         let r = Rope::from_str(TEXT);
         let s = r.slice(34..301);
-
-        let (mut chunks, _, _, _) = s.chunks_at_line_break(s.len_lines());
-        assert_eq!(chunks.next(), None);
+        let mut chars = s.chars_at(s.len_chars());
+        assert_eq!(chars.next(), None);
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_at_line_break_04() {
+    fn chars_at_sliced_03() {
         let r = Rope::from_str(TEXT);
-        let s = r.slice(34..34);
 
-        let (mut chunks, _, _, _) = s.chunks_at_line_break(0);
-        assert_eq!(chunks.next(), None);
+        let s_start = 34;
+        let s_end = 301;
+        let s_start_char = r.char_to_byte(s_start);
+        let s_end_char = r.char_to_byte(s_end);
 
-        let (mut chunks, _, _, _) = s.chunks_at_line_break(0);
-        assert_eq!(chunks.prev(), None);
+        let s1 = r.slice(s_start..s_end);
+        let s2 = &TEXT[s_start_char..s_end_char];
+
+        let mut chars_1 = s1.chars_at(s1.len_chars());
+        let mut chars_2 = s2.chars();
+        while let Some(c) = chars_2.next_back() {
+            assert_eq!(chars_1.prev(), Some(c));
+        }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_reverse_01() {
+    fn chars_at_sliced_reverse_01() {
         let r = Rope::from_str(TEXT);
-        let mut itr = r.chunks();
-        let mut stack = Vec::new();
 
-        for _ in 0..8 {
+        let s_start = 34;
+        let s_end = 301;
+        let s = r.slice(s_start..s_end);
+
+        let mut itr = s.chars_at(s.len_chars() / 3);
+        let mut stack = Vec::new();
+        for _ in 0..32 {
             stack.push(itr.next().unwrap());
         }
         itr.reverse();
-        for _ in 0..8 {
-            assert_eq!(stack.pop().unwrap(), itr.next().unwrap());
+        for _ in 0..32 {
+            assert_eq!(stack.pop(), itr.next());
         }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_reverse_02() {
+    fn lines_sliced_01() {
         let r = Rope::from_str(TEXT);
-        let mut itr = r.chunks_at_char(r.len_chars() / 3).0;
-        let mut stack = Vec::new();
 
-        for _ in 0..8 {
-            stack.push(itr.next().unwrap());
-        }
-        itr.reverse();
-        for _ in 0..8 {
-            assert_eq!(stack.pop().unwrap(), itr.next().unwrap());
+        let s_start = 34;
+        let s_end = 301;
+        let s_start_byte = r.char_to_byte(s_start);
+        let s_end_byte = r.char_to_byte(s_end);
+
+        let s1 = r.slice(s_start..s_end);
+        let s2 = &TEXT[s_start_byte..s_end_byte];
+
+        for (liner, linet) in s1.lines().zip(s2.lines()) {
+            assert_eq!(liner.to_string().trim_end(), linet);
         }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_reverse_03() {
+    fn lines_sliced_reverse_01() {
         let r = Rope::from_str(TEXT);
-        let mut itr = r.chunks_at_char(r.len_chars() / 3).0;
-        let mut stack = Vec::new();
 
-        itr.reverse();
-        for _ in 0..8 {
+        let s_start = 34;
+        let s_end = 301;
+        let s = r.slice(s_start..s_end);
+
+        let mut itr = s.lines();
+        let mut stack = Vec::new();
+        for _ in 0..4 {
             stack.push(itr.next().unwrap());
         }
         itr.reverse();
-        for _ in 0..8 {
+        for _ in 0..4 {
             assert_eq!(stack.pop().unwrap(), itr.next().unwrap());
         }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_reverse_04() {
-        let mut itr = Chunks::from_str("a\n", false);
-
-        assert_eq!(Some("a\n"), itr.next());
-        assert_eq!(None, itr.next());
-        itr.reverse();
-        assert_eq!(Some("a\n"), itr.next());
-        assert_eq!(None, itr.next());
-    }
-
-    #[test]
-    #[cfg_attr(miri, ignore)]
-    fn bytes_sliced_01() {
+    fn chunks_sliced_01() {
         let r = Rope::from_str(TEXT);
 
         let s_start = 34;
@@ -3232,37 +4876,93 @@ mod tests {
         let s1 = r.slice(s_start..s_end);
         let s2 = &TEXT[s_start_byte..s_end_byte];
 
-        let mut s1_bytes = s1.bytes();
-        let mut s2_bytes = s2.bytes();
-
-        assert_eq!(s1, s2);
-        assert_eq!(s1.byte(0), s2.as_bytes()[0]);
-
-        assert_eq!(s1.len_bytes(), s2.len());
-        assert_eq!(s1_bytes.len(), s2.len());
-
-        for _ in 0..(s2.len() + 1) {
-            assert_eq!(s1_bytes.next(), s2_bytes.next());
+        let mut idx = 0;
+        for chunk in s1.chunks() {
+            assert_eq!(chunk, &s2[idx..(idx + chunk.len())]);
+            idx += chunk.len();
         }
 
-        assert_eq!(s1_bytes.len(), 0);
+        assert_eq!(idx, s2.len());
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn bytes_sliced_reverse_01() {
+    fn chunks_sliced_reverse_01() {
         let r = Rope::from_str(TEXT);
 
         let s_start = 34;
         let s_end = 301;
         let s = r.slice(s_start..s_end);
 
-        let mut itr = s.bytes();
+        let mut itr = s.chunks();
+        let mut stack = Vec::new();
+        for _ in 0..8 {
+            stack.push(itr.next().unwrap());
+        }
+        itr.reverse();
+        for _ in 0..8 {
+            assert_eq!(stack.pop(), itr.next());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn char_indices_01() {
+        let r = Rope::from_str(TEXT);
+        let expected: Vec<_> = TEXT
+            .char_indices()
+            .enumerate()
+            .map(|(i, (b, c))| (i, b, c))
+            .collect();
+
+        assert_eq!(r.char_indices().len(), expected.len());
+        assert_eq!(r.char_indices().collect::<Vec<_>>(), expected);
+        assert_eq!(
+            r.char_indices_at(r.len_chars())
+                .reversed()
+                .collect::<Vec<_>>(),
+            expected.iter().rev().cloned().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn char_indices_at_01() {
+        let r = Rope::from_str(TEXT);
+        let expected: Vec<_> = TEXT
+            .char_indices()
+            .enumerate()
+            .map(|(i, (b, c))| (i, b, c))
+            .collect();
+
+        for i in 0..=r.len_chars() {
+            let mut itr = r.char_indices_at(i);
+            if i < r.len_chars() {
+                assert_eq!(itr.next(), Some(expected[i]));
+                assert_eq!(itr.prev(), Some(expected[i]));
+            } else {
+                assert_eq!(itr.next(), None);
+            }
+            if i > 0 {
+                assert_eq!(itr.prev(), Some(expected[i - 1]));
+            } else {
+                assert_eq!(itr.prev(), None);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn char_indices_reverse_01() {
+        let r = Rope::from_str(TEXT);
+        let mut itr = r.char_indices_at(r.len_chars() / 3);
         let mut stack = Vec::new();
+
         for _ in 0..32 {
             stack.push(itr.next().unwrap());
         }
         itr.reverse();
+        assert_eq!(itr.len(), r.len_chars() / 3 + 32);
         for _ in 0..32 {
             assert_eq!(stack.pop(), itr.next());
         }
@@ -3270,7 +4970,7 @@ mod tests {
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn bytes_at_sliced_01() {
+    fn char_indices_sliced_01() {
         let r = Rope::from_str(TEXT);
 
         let s_start = 34;
@@ -3281,254 +4981,694 @@ mod tests {
         let s1 = r.slice(s_start..s_end);
         let s2 = &TEXT[s_start_byte..s_end_byte];
 
-        let mut bytes_1 = s2.bytes();
-        for i in 0..(s1.len_bytes() + 1) {
-            let mut bytes_2 = s1.bytes_at(i);
-            assert_eq!(bytes_1.next(), bytes_2.next());
+        let expected: Vec<_> = s2
+            .char_indices()
+            .enumerate()
+            .map(|(i, (b, c))| (i, b, c))
+            .collect();
+        assert_eq!(s1.char_indices().collect::<Vec<_>>(), expected);
+        assert_eq!(s1.char_indices_at(10).next(), Some(expected[10]));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunk_indices_01() {
+        let r = Rope::from_str(TEXT);
+
+        let mut idx = 0;
+        for (chunk, byte_idx, char_idx, line_idx) in r.chunk_indices() {
+            assert_eq!(byte_idx, idx);
+            assert_eq!(chunk, &TEXT[idx..(idx + chunk.len())]);
+            assert_eq!(char_idx, r.byte_to_char(byte_idx));
+            assert_eq!(line_idx, r.byte_to_line(byte_idx));
+            idx += chunk.len();
+        }
+        assert_eq!(idx, TEXT.len());
+
+        let forward: Vec<_> = r.chunk_indices().collect();
+        let mut backward: Vec<_> = r.chunk_indices_at_byte(r.len_bytes()).reversed().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn chunk_indices_at_01() {
+        let r = Rope::from_str(TEXT);
+
+        for i in 0..=r.len_bytes() {
+            let (mut chunks, byte_idx, char_idx, line_idx) = r.chunks_at_byte(i);
+            assert_eq!(
+                r.chunk_indices_at_byte(i).next(),
+                chunks.next().map(|c| (c, byte_idx, char_idx, line_idx))
+            );
+        }
+        for i in 0..=r.len_chars() {
+            let (mut chunks, byte_idx, char_idx, line_idx) = r.chunks_at_char(i);
+            assert_eq!(
+                r.chunk_indices_at_char(i).next(),
+                chunks.next().map(|c| (c, byte_idx, char_idx, line_idx))
+            );
+        }
+        for i in 0..=r.len_lines() {
+            let (mut chunks, byte_idx, char_idx, line_idx) = r.chunks_at_line_break(i);
+            assert_eq!(
+                r.chunk_indices_at_line_break(i).next(),
+                chunks.next().map(|c| (c, byte_idx, char_idx, line_idx))
+            );
         }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn bytes_at_sliced_02() {
+    fn chunk_indices_reverse_01() {
         let r = Rope::from_str(TEXT);
-        let s = r.slice(34..301);
-        let mut bytes = s.bytes_at(s.len_bytes());
-        assert_eq!(bytes.next(), None);
+        let mut itr = r.chunk_indices_at_char(r.len_chars() / 3);
+        let mut stack = Vec::new();
+
+        for _ in 0..8 {
+            stack.push(itr.next().unwrap());
+        }
+        itr.reverse();
+        for _ in 0..8 {
+            assert_eq!(stack.pop(), itr.next());
+        }
+        for (chunk, byte_idx, char_idx, line_idx) in itr {
+            assert_eq!(r.byte_slice(byte_idx..(byte_idx + chunk.len())), chunk);
+            assert_eq!(char_idx, r.byte_to_char(byte_idx));
+            assert_eq!(line_idx, r.byte_to_line(byte_idx));
+        }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn bytes_at_sliced_03() {
+    fn chunk_indices_sliced_01() {
         let r = Rope::from_str(TEXT);
 
         let s_start = 34;
         let s_end = 301;
-        let s_start_byte = r.char_to_byte(s_start);
-        let s_end_byte = r.char_to_byte(s_end);
-
-        let s1 = r.slice(s_start..s_end);
-        let s2 = &TEXT[s_start_byte..s_end_byte];
+        let s = r.slice(s_start..s_end);
 
-        let mut bytes_1 = s1.bytes_at(s1.len_bytes());
-        let mut bytes_2 = s2.bytes();
-        while let Some(b) = bytes_2.next_back() {
-            assert_eq!(bytes_1.prev(), Some(b));
+        let mut idx = 0;
+        for (chunk, byte_idx, char_idx, line_idx) in s.chunk_indices() {
+            assert_eq!(byte_idx, idx);
+            assert_eq!(s.byte_slice(idx..(idx + chunk.len())), chunk);
+            assert_eq!(char_idx, s.byte_to_char(byte_idx));
+            assert_eq!(line_idx, s.byte_to_line(byte_idx));
+            idx += chunk.len();
         }
+        assert_eq!(idx, s.len_bytes());
+
+        let (mut chunks, byte_idx, char_idx, line_idx) = s.chunks_at_char(100);
+        assert_eq!(
+            s.chunk_indices_at_char(100).next(),
+            chunks.next().map(|c| (c, byte_idx, char_idx, line_idx))
+        );
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn bytes_at_sliced_reverse_01() {
+    fn lines_without_endings_01() {
         let r = Rope::from_str(TEXT);
+        assert_eq!(r.lines_without_endings().len(), r.len_lines());
+        for (line, trimmed) in r.lines().zip(r.lines_without_endings()) {
+            // `trim_line_break()` leaves the CR of a CRLF without `cr_lines`.
+            let line = line.to_string();
+            let expected = line.strip_suffix("\r\n").unwrap_or(trim_line_break(&line));
+            assert_eq!(expected, trimmed);
+        }
 
-        let s_start = 34;
-        let s_end = 301;
-        let s = r.slice(s_start..s_end);
-
-        let mut itr = s.bytes_at(s.len_bytes() / 3);
+        let mut itr = r.lines_without_endings_at(r.len_lines() / 3);
         let mut stack = Vec::new();
-        for _ in 0..32 {
+        for _ in 0..8 {
             stack.push(itr.next().unwrap());
         }
         itr.reverse();
-        for _ in 0..32 {
+        for _ in 0..8 {
             assert_eq!(stack.pop(), itr.next());
         }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chars_sliced_01() {
-        let r = Rope::from_str(TEXT);
-
-        let s_start = 34;
-        let s_end = 301;
-        let s_start_byte = r.char_to_byte(s_start);
-        let s_end_byte = r.char_to_byte(s_end);
-
-        let s1 = r.slice(s_start..s_end);
-        let s2 = &TEXT[s_start_byte..s_end_byte];
+    fn line_infos_01() {
+        let r = Rope::from_str("Hi\nthere\r\n\r\nworld\u{2028}!");
+        let infos: Vec<_> = r.line_infos().collect();
+        let summary: Vec<_> = infos
+            .iter()
+            .map(|info| (info.content.to_string(), info.ending))
+            .collect();
+
+        // CRLF pairs are always a single line break.
+        assert_eq!(
+            summary[..3],
+            [
+                ("Hi".to_string(), Some(LineEnding::Lf)),
+                ("there".to_string(), Some(LineEnding::Crlf)),
+                ("".to_string(), Some(LineEnding::Crlf)),
+            ]
+        );
+        assert_eq!(infos[1].char_range, 3..10);
 
-        for (cr, ct) in s1.chars().zip(s2.chars()) {
-            assert_eq!(cr, ct);
+        #[cfg(feature = "unicode_lines")]
+        {
+            assert_eq!(
+                summary[3..],
+                [
+                    ("world".to_string(), Some(LineEnding::Ls)),
+                    ("!".to_string(), None),
+                ]
+            );
+            assert_eq!(infos[3].byte_range, 12..20);
+            assert_eq!(infos[4].char_range, 18..19);
+        }
+        #[cfg(not(feature = "unicode_lines"))]
+        {
+            assert_eq!(summary[3..], [("world\u{2028}!".to_string(), None)]);
+            assert_eq!(infos[3].byte_range, 12..21);
         }
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chars_sliced_reverse_01() {
+    fn line_infos_02() {
         let r = Rope::from_str(TEXT);
+        assert_eq!(r.line_infos().len(), r.len_lines());
 
-        let s_start = 34;
-        let s_end = 301;
-        let s = r.slice(s_start..s_end);
+        for (i, info) in r.line_infos().enumerate() {
+            let line = r.line(i);
+            assert_eq!(info.char_range.start, r.line_to_char(i));
+            assert_eq!(info.byte_range.start, r.line_to_byte(i));
+            assert_eq!(r.slice(info.char_range.clone()), line);
+            assert_eq!(r.byte_slice(info.byte_range.clone()), line);
+            let ending = info.ending.map_or("", |e| e.as_str());
+            assert_eq!(line, format!("{}{}", info.content, ending));
+        }
 
-        let mut itr = s.chars();
+        let mut itr = r.line_infos_at(r.len_lines() / 3);
         let mut stack = Vec::new();
-        for _ in 0..32 {
+        for _ in 0..8 {
             stack.push(itr.next().unwrap());
         }
         itr.reverse();
-        for _ in 0..32 {
-            assert_eq!(stack.pop(), itr.next());
+        for _ in 0..8 {
+            let a = stack.pop().unwrap();
+            let b = itr.next().unwrap();
+            assert_eq!(a.content, b.content);
+            assert_eq!(a.ending, b.ending);
+            assert_eq!(a.char_range, b.char_range);
+            assert_eq!(a.byte_range, b.byte_range);
         }
+        assert_eq!(itr.len(), r.len_lines() / 3);
+        assert!(r.get_line_infos_at(r.len_lines() + 1).is_none());
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chars_at_sliced_01() {
+    fn line_infos_sliced_01() {
         let r = Rope::from_str(TEXT);
 
         let s_start = 34;
         let s_end = 301;
-        let s_start_byte = r.char_to_byte(s_start);
-        let s_end_byte = r.char_to_byte(s_end);
-
-        let s1 = r.slice(s_start..s_end);
-        let s2 = &TEXT[s_start_byte..s_end_byte];
+        let s = r.slice(s_start..s_end);
 
-        let mut chars_1 = s2.chars();
-        for i in 0..(s1.len_chars() + 1) {
-            let mut chars_2 = s1.chars_at(i);
-            assert_eq!(chars_1.next(), chars_2.next());
+        for (i, info) in s.line_infos().enumerate() {
+            let line = s.line(i);
+            assert_eq!(s.slice(info.char_range.clone()), line);
+            assert_eq!(s.byte_slice(info.byte_range.clone()), line);
+            assert_eq!(info.content, s.lines_without_endings().nth(i).unwrap());
         }
+        assert!(s.line_infos_at(s.len_lines()).next().is_none());
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chars_at_sliced_02() {
-        let r = Rope::from_str(TEXT);
-        let s = r.slice(34..301);
-        let mut chars = s.chars_at(s.len_chars());
-        assert_eq!(chars.next(), None);
+    fn empty_iter() {
+        let rope = Rope::from_str("");
+        let r: Vec<_> = rope.lines().collect();
+        assert_eq!(&[""], &*r)
+    }
+
+    fn char_ranges(text: &str, byte_ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+        byte_ranges
+            .into_iter()
+            .map(|r| byte_to_char_idx(text, r.start)..byte_to_char_idx(text, r.end))
+            .collect()
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chars_at_sliced_03() {
+    fn matches_01() {
         let r = Rope::from_str(TEXT);
+        for pattern in &["\r\n", "e", "you", "です", "ない", "day, isn't"] {
+            let expected = char_ranges(
+                TEXT,
+                TEXT.match_indices(pattern)
+                    .map(|(i, m)| i..(i + m.len()))
+                    .collect(),
+            );
+            assert!(!expected.is_empty());
+            assert_eq!(r.matches(pattern).collect::<Vec<_>>(), expected);
+        }
+    }
 
-        let s_start = 34;
-        let s_end = 301;
-        let s_start_char = r.char_to_byte(s_start);
-        let s_end_char = r.char_to_byte(s_end);
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_02() {
+        let r = Rope::from_str(TEXT);
+        for pattern in &["\r\n", "e", "you", "です", "ない", "day, isn't"] {
+            let expected = char_ranges(
+                TEXT,
+                TEXT.rmatch_indices(pattern)
+                    .map(|(i, m)| i..(i + m.len()))
+                    .collect(),
+            );
+            assert_eq!(r.rmatches(pattern).collect::<Vec<_>>(), expected);
+        }
+    }
 
-        let s1 = r.slice(s_start..s_end);
-        let s2 = &TEXT[s_start_char..s_end_char];
+    #[test]
+    fn matches_03() {
+        let r = Rope::from_str("aaaaa");
+        assert_eq!(r.matches("aa").collect::<Vec<_>>(), vec![0..2, 2..4]);
+        assert_eq!(r.rmatches("aa").collect::<Vec<_>>(), vec![3..5, 1..3]);
+        assert_eq!(r.matches("b").next(), None);
+        assert_eq!(r.matches("aaaaaa").next(), None);
+    }
 
-        let mut chars_1 = s1.chars_at(s1.len_chars());
-        let mut chars_2 = s2.chars();
-        while let Some(c) = chars_2.next_back() {
-            assert_eq!(chars_1.prev(), Some(c));
-        }
+    #[test]
+    fn matches_04() {
+        // Empty pattern.
+        let r = Rope::from_str("a😀b");
+        assert_eq!(
+            r.matches("").collect::<Vec<_>>(),
+            vec![0..0, 1..1, 2..2, 3..3]
+        );
+        assert_eq!(
+            r.rmatches("").collect::<Vec<_>>(),
+            vec![3..3, 2..2, 1..1, 0..0]
+        );
+        assert_eq!(Rope::from_str("").matches("").count(), 1);
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chars_at_sliced_reverse_01() {
+    fn matches_sliced_01() {
         let r = Rope::from_str(TEXT);
-
         let s_start = 34;
-        let s_end = 301;
+        let s_end = 90;
         let s = r.slice(s_start..s_end);
+        let text = &TEXT[s_start..s_end];
 
-        let mut itr = s.chars_at(s.len_chars() / 3);
-        let mut stack = Vec::new();
-        for _ in 0..32 {
-            stack.push(itr.next().unwrap());
-        }
-        itr.reverse();
-        for _ in 0..32 {
-            assert_eq!(stack.pop(), itr.next());
-        }
+        let expected = char_ranges(
+            text,
+            text.match_indices("e").map(|(i, _)| i..(i + 1)).collect(),
+        );
+        assert_eq!(s.matches("e").collect::<Vec<_>>(), expected);
+
+        let mut expected = expected;
+        expected.reverse();
+        assert_eq!(s.rmatches("e").collect::<Vec<_>>(), expected);
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn lines_sliced_01() {
+    fn matches_ignore_case_01() {
         let r = Rope::from_str(TEXT);
+        let upper = TEXT.to_uppercase();
+        for pattern in &["\r\n", "E", "You", "です", "DAY, ISN'T"] {
+            let expected = char_ranges(
+                &upper,
+                upper
+                    .match_indices(&pattern.to_uppercase())
+                    .map(|(i, m)| i..(i + m.len()))
+                    .collect(),
+            );
+            assert!(!expected.is_empty());
+            assert_eq!(r.matches_ignore_case(pattern).collect::<Vec<_>>(), expected);
 
-        let s_start = 34;
-        let s_end = 301;
-        let s_start_byte = r.char_to_byte(s_start);
-        let s_end_byte = r.char_to_byte(s_end);
+            let mut expected = expected;
+            expected.reverse();
+            assert_eq!(
+                r.rmatches_ignore_case(pattern).collect::<Vec<_>>(),
+                expected
+            );
+        }
+    }
 
-        let s1 = r.slice(s_start..s_end);
-        let s2 = &TEXT[s_start_byte..s_end_byte];
+    #[test]
+    fn matches_ignore_case_02() {
+        // Case folding that changes the length of the text.
+        let r = Rope::from_str("Die Straße, die STRASSE, die straẞe.");
+        assert_eq!(
+            r.matches_ignore_case("strasse").collect::<Vec<_>>(),
+            vec![4..10, 16..23, 29..35]
+        );
+        assert_eq!(
+            r.rmatches_ignore_case("STRAßE").collect::<Vec<_>>(),
+            vec![29..35, 16..23, 4..10]
+        );
+        assert_eq!(
+            r.matches_ignore_case("ss").collect::<Vec<_>>(),
+            vec![8..9, 20..22, 33..34]
+        );
+    }
 
-        for (liner, linet) in s1.lines().zip(s2.lines()) {
-            assert_eq!(liner.to_string().trim_end(), linet);
-        }
+    #[test]
+    fn matches_ignore_case_03() {
+        // Matches must cover whole chars.
+        let r = Rope::from_str("aßb ßs");
+        assert_eq!(r.matches_ignore_case("s").collect::<Vec<_>>(), vec![5..6]);
+        assert_eq!(r.matches_ignore_case("sb").next(), None);
+        assert_eq!(r.matches_ignore_case("ßs").collect::<Vec<_>>(), vec![4..6]);
+        assert_eq!(r.matches_ignore_case("sss").collect::<Vec<_>>(), vec![4..6]);
+        assert_eq!(
+            r.rmatches_ignore_case("SSS").collect::<Vec<_>>(),
+            vec![4..6]
+        );
+
+        // A rejected partial-char match mustn't hide a later one.
+        let r = Rope::from_str("ßsss");
+        assert_eq!(
+            r.matches_ignore_case("ss").collect::<Vec<_>>(),
+            vec![0..1, 1..3]
+        );
+        assert_eq!(
+            r.rmatches_ignore_case("ss").collect::<Vec<_>>(),
+            vec![2..4, 0..1]
+        );
+    }
+
+    #[test]
+    fn matches_ignore_case_04() {
+        // Empty pattern.
+        let r = Rope::from_str("aß");
+        assert_eq!(
+            r.matches_ignore_case("").collect::<Vec<_>>(),
+            vec![0..0, 1..1, 2..2]
+        );
+        assert_eq!(
+            r.rmatches_ignore_case("").collect::<Vec<_>>(),
+            vec![2..2, 1..1, 0..0]
+        );
+    }
+
+    fn split_strs<'a, P: Pattern>(split: Split<'a, P>) -> Vec<String> {
+        split.map(|s| s.to_string()).collect()
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn lines_sliced_reverse_01() {
+    fn split_01() {
         let r = Rope::from_str(TEXT);
+        for pattern in &["\r\n", "e", "you", "です", "", "xyz"] {
+            let expected: Vec<_> = TEXT.split(pattern).collect();
+            assert_eq!(split_strs(r.split(*pattern)), expected);
 
-        let s_start = 34;
-        let s_end = 301;
-        let s = r.slice(s_start..s_end);
-
-        let mut itr = s.lines();
-        let mut stack = Vec::new();
-        for _ in 0..4 {
-            stack.push(itr.next().unwrap());
-        }
-        itr.reverse();
-        for _ in 0..4 {
-            assert_eq!(stack.pop().unwrap(), itr.next().unwrap());
+            let expected: Vec<_> = TEXT.rsplit(pattern).collect();
+            assert_eq!(split_strs(r.rsplit(*pattern)), expected);
         }
+
+        let expected: Vec<_> = TEXT.split(' ').collect();
+        assert_eq!(split_strs(r.split(' ')), expected);
+        let expected: Vec<_> = TEXT.rsplit(char::is_whitespace).collect();
+        assert_eq!(split_strs(r.rsplit(char::is_whitespace)), expected);
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_sliced_01() {
+    fn split_02() {
+        // Moving back and forth.
         let r = Rope::from_str(TEXT);
+        for pattern in &["\r\n", " ", ""] {
+            let pieces: Vec<_> = TEXT.split(pattern).collect();
+            let mut split = r.split(*pattern);
+            assert_eq!(split.prev(), None);
+            for piece in &pieces {
+                assert_eq!(split.next().unwrap(), *piece);
+            }
+            assert_eq!(split.next(), None);
+            for piece in pieces.iter().rev() {
+                assert_eq!(split.prev().unwrap(), *piece);
+            }
+            assert_eq!(split.prev(), None);
+            assert_eq!(split.next().unwrap(), pieces[0]);
+            assert_eq!(split.reversed().next().unwrap(), pieces[0]);
+        }
+    }
 
-        let s_start = 34;
-        let s_end = 301;
-        let s_start_byte = r.char_to_byte(s_start);
-        let s_end_byte = r.char_to_byte(s_end);
+    #[test]
+    fn split_03() {
+        let r = Rope::from_str("a,b,,c,");
+        assert_eq!(split_strs(r.split(',')), ["a", "b", "", "c", ""]);
+        assert_eq!(split_strs(r.split_terminator(',')), ["a", "b", "", "c"]);
+        assert_eq!(
+            split_strs(r.split_terminator(',').reversed()),
+            Vec::<String>::new()
+        );
+        assert_eq!(split_strs(r.splitn(3, ',')), ["a", "b", ",c,"]);
+        assert_eq!(split_strs(r.splitn(1, ',')), ["a,b,,c,"]);
+        assert_eq!(split_strs(r.splitn(0, ',')), Vec::<String>::new());
+        assert_eq!(split_strs(r.slice(..0).split(',')), [""]);
+        assert_eq!(
+            split_strs(r.slice(..0).split_terminator(',')),
+            Vec::<String>::new()
+        );
 
-        let s1 = r.slice(s_start..s_end);
-        let s2 = &TEXT[s_start_byte..s_end_byte];
+        let mut split = r.split_terminator(',');
+        assert_eq!(split.by_ref().count(), 4);
+        assert_eq!(split.prev().unwrap(), "c");
 
-        let mut idx = 0;
-        for chunk in s1.chunks() {
-            assert_eq!(chunk, &s2[idx..(idx + chunk.len())]);
-            idx += chunk.len();
-        }
+        let mut split = r.splitn(3, ',');
+        assert_eq!(split.by_ref().count(), 3);
+        assert_eq!(split.prev().unwrap(), ",c,");
+        assert_eq!(split.prev().unwrap(), "b");
+        assert_eq!(split.next().unwrap(), "b");
+        assert_eq!(split.next().unwrap(), ",c,");
+        assert_eq!(split.next(), None);
+    }
 
-        assert_eq!(idx, s2.len());
+    #[test]
+    fn split_whitespace_01() {
+        let text = "  Hello\tthere!\r\n  How're\u{3000}you?  ";
+        let r = Rope::from_str(text);
+        let expected: Vec<_> = text.split_whitespace().collect();
+        assert_eq!(split_strs(r.split_whitespace()), expected);
+
+        let mut split = r.split_whitespace();
+        assert_eq!(split.by_ref().count(), 4);
+        let mut expected = expected;
+        expected.reverse();
+        assert_eq!(split_strs(split.reversed()), expected);
+        assert_eq!(Rope::from_str(" \n ").split_whitespace().next(), None);
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
-    fn chunks_sliced_reverse_01() {
+    fn split_sliced_01() {
         let r = Rope::from_str(TEXT);
-
         let s_start = 34;
-        let s_end = 301;
+        let s_end = 90;
         let s = r.slice(s_start..s_end);
+        let text = &TEXT[s_start..s_end];
 
-        let mut itr = s.chunks();
-        let mut stack = Vec::new();
-        for _ in 0..8 {
-            stack.push(itr.next().unwrap());
+        let expected: Vec<_> = text.split("e").collect();
+        assert_eq!(split_strs(s.split("e")), expected);
+        let expected: Vec<_> = text.rsplit('e').collect();
+        assert_eq!(split_strs(s.rsplit('e')), expected);
+    }
+
+    #[cfg(feature = "graphemes")]
+    const GRAPHEME_TEXT: &str = "He\u{303}\u{303}\u{303}\u{303}\u{303}\u{303}llo\r\n\
+                                 🇺🇸🇯🇵 wo\u{308}\u{308}\u{308}\u{308}rld!";
+
+    #[test]
+    #[cfg(feature = "graphemes")]
+    #[cfg_attr(miri, ignore)]
+    fn graphemes_01() {
+        let r = Rope::from_str(GRAPHEME_TEXT);
+        let expected: Vec<String> = {
+            use unicode_segmentation::UnicodeSegmentation;
+            GRAPHEME_TEXT.graphemes(true).map(String::from).collect()
+        };
+
+        // Forward.
+        let graphemes: Vec<String> = r.graphemes().map(String::from).collect();
+        assert_eq!(graphemes, expected);
+
+        // Backward.
+        let mut graphemes: Vec<String> = r.graphemes().rev().map(String::from).collect();
+        graphemes.reverse();
+        assert_eq!(graphemes, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "graphemes")]
+    #[cfg_attr(miri, ignore)]
+    fn graphemes_02() {
+        // Alternating ends, meeting in the middle.
+        let r = Rope::from_str(GRAPHEME_TEXT);
+        let mut itr = r.graphemes();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        while let Some(g) = itr.next() {
+            front.push(String::from(g));
+            match itr.next_back() {
+                Some(g) => back.push(String::from(g)),
+                None => break,
+            }
         }
-        itr.reverse();
-        for _ in 0..8 {
-            assert_eq!(stack.pop(), itr.next());
+        assert_eq!(itr.next(), None);
+        assert_eq!(itr.next_back(), None);
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front.concat(), GRAPHEME_TEXT);
+        assert_eq!(front[1], "e\u{303}\u{303}\u{303}\u{303}\u{303}\u{303}");
+    }
+
+    #[test]
+    #[cfg(feature = "graphemes")]
+    #[cfg_attr(miri, ignore)]
+    fn graphemes_sliced_01() {
+        let r = Rope::from_str(GRAPHEME_TEXT);
+        // Slicing through the middle of a cluster makes the pieces
+        // separate graphemes.
+        let s = r.slice(3..11);
+        let graphemes: Vec<String> = s.graphemes().map(String::from).collect();
+        assert_eq!(
+            graphemes,
+            vec!["\u{303}\u{303}\u{303}\u{303}\u{303}", "l", "l", "o"]
+        );
+        assert_eq!(r.slice(0..0).graphemes().next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "graphemes")]
+    #[cfg_attr(miri, ignore)]
+    fn graphemes_chunk_boundaries_01() {
+        use crate::RopeBuilder;
+        use unicode_segmentation::UnicodeSegmentation;
+
+        // Emoji zwj sequences and regional indicators whose boundary
+        // decisions need text from before a chunk boundary.
+        let chunk_sets: [&[&str]; 4] = [
+            &["a\u{1F600}\u{200D}", "\u{200D}\u{1F600}b"],
+            &["a\u{1F600}", "\u{200D}\u{1F600}b"],
+            &["a\u{1F600}", "\u{303}", "\u{303}\u{200D}", "\u{1F600}b"],
+            &["\u{1F1E6}", "\u{1F1E6}", "\u{1F1E6}", "\u{1F1E6}\u{1F1E6}"],
+        ];
+        for chunks in chunk_sets.iter() {
+            let mut b = RopeBuilder::new();
+            for chunk in chunks.iter() {
+                b._append_chunk(chunk);
+            }
+            let r = b._finish_no_fix();
+            let text = chunks.concat();
+            let expected: Vec<&str> = text.graphemes(true).collect();
+
+            let graphemes: Vec<String> = r.graphemes().map(String::from).collect();
+            assert_eq!(graphemes, expected);
+            let mut graphemes: Vec<String> = r.graphemes().rev().map(String::from).collect();
+            graphemes.reverse();
+            assert_eq!(graphemes, expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "graphemes")]
+    fn graphemes_precontext_fallback_01() {
+        use crate::RopeBuilder;
+        use unicode_segmentation::UnicodeSegmentation;
+
+        // Chunk boundaries inside a zwj sequence and a run of regional
+        // indicators, with the text that decides each boundary spread over
+        // several chunks before it, so that `GraphemeStepper` has to fall
+        // back to a contiguous copy reaching back more than once.
+        let chunk_sets: [&[&str]; 2] = [
+            &[
+                "a\u{1F600}",
+                "\u{303}",
+                "\u{303}",
+                "\u{303}",
+                "\u{303}",
+                "\u{200D}",
+                "\u{1F600}b",
+            ],
+            &[
+                "a",
+                "\u{1F1E6}",
+                "\u{1F1E6}",
+                "\u{1F1E6}",
+                "\u{1F1E6}",
+                "\u{1F1E6}",
+                "b",
+            ],
+        ];
+        for chunks in chunk_sets.iter() {
+            let mut b = RopeBuilder::new();
+            for chunk in chunks.iter() {
+                b._append_chunk(chunk);
+            }
+            let r = b._finish_no_fix();
+            let text = chunks.concat();
+            assert_eq!(r.chunks().count(), chunks.len());
+
+            let boundaries: Vec<usize> = text
+                .grapheme_indices(true)
+                .map(|(i, _)| byte_to_char_idx(&text, i))
+                .chain(Some(r.len_chars()))
+                .collect();
+            for char_idx in 0..=r.len_chars() {
+                assert_eq!(
+                    r.is_grapheme_boundary(char_idx),
+                    boundaries.contains(&char_idx)
+                );
+                let next = boundaries.iter().find(|&&i| i > char_idx);
+                let prev = boundaries.iter().rev().find(|&&i| i < char_idx);
+                assert_eq!(
+                    r.next_grapheme_boundary(char_idx),
+                    *next.unwrap_or(&r.len_chars())
+                );
+                assert_eq!(r.prev_grapheme_boundary(char_idx), *prev.unwrap_or(&0));
+            }
         }
     }
 
     #[test]
+    #[cfg(feature = "graphemes")]
     #[cfg_attr(miri, ignore)]
-    fn empty_iter() {
-        let rope = Rope::from_str("");
-        let r: Vec<_> = rope.lines().collect();
-        assert_eq!(&[""], &*r)
+    fn grapheme_boundaries_01() {
+        let r = Rope::from_str(GRAPHEME_TEXT);
+
+        assert!(r.is_grapheme_boundary(0));
+        assert!(r.is_grapheme_boundary(1));
+        assert!(!r.is_grapheme_boundary(2));
+        assert!(!r.is_grapheme_boundary(7));
+        assert!(r.is_grapheme_boundary(8));
+        assert!(!r.is_grapheme_boundary(12)); // Inside CRLF.
+        assert!(r.is_grapheme_boundary(r.len_chars()));
+
+        assert_eq!(r.next_grapheme_boundary(1), 8);
+        assert_eq!(r.next_grapheme_boundary(3), 8);
+        assert_eq!(r.next_grapheme_boundary(11), 13);
+        assert_eq!(r.next_grapheme_boundary(13), 15);
+        assert_eq!(r.prev_grapheme_boundary(8), 1);
+        assert_eq!(r.prev_grapheme_boundary(5), 1);
+        assert_eq!(r.prev_grapheme_boundary(17), 15);
+        assert_eq!(r.prev_grapheme_boundary(0), 0);
+        assert_eq!(r.next_grapheme_boundary(r.len_chars()), r.len_chars());
+
+        let s = r.slice(2..);
+        assert_eq!(s.next_grapheme_boundary(0), 6);
+        assert!(s.is_grapheme_boundary(0));
+        assert!(s.try_is_grapheme_boundary(s.len_chars() + 1).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "graphemes")]
+    #[should_panic]
+    fn grapheme_boundaries_02() {
+        let r = Rope::from_str(GRAPHEME_TEXT);
+        r.next_grapheme_boundary(r.len_chars() + 1);
     }
 }
@@ -15,6 +15,11 @@
 //! - [`RopeBuilder`]: an efficient incremental
 //!   `Rope` builder.
 //!
+//! Additionally, [`marker`] provides markers that track positions in a
+//! `Rope` through edits, [`attributed`] provides a `Rope` with
+//! attributes attached to its text, and [`encoding`] loads and saves text
+//! in encodings other than utf8.
+//!
 //!
 //! # A Basic Example
 //!
@@ -160,6 +165,32 @@
 //! will also get disabled, and performance will suffer.  So be careful
 //! to explicitly re-enable the `simd` feature flag (if desired) when
 //! doing that.
+//!
+//!
+//! # Graphemes
+//!
+//! Ropey's indexing is based on chars, not graphemes.  But with the
+//! `graphemes` feature flag enabled, `Rope` and `RopeSlice` also provide
+//! methods for iterating over extended grapheme clusters and finding
+//! grapheme boundaries, via the `unicode-segmentation` crate.
+//!
+//!
+//! # Language Server Support
+//!
+//! The `lsp` feature flag enables the `lsp` module, which applies
+//! incremental line/column based text changes (as sent by LSP clients) to a
+//! `Rope`.  It doesn't depend on any LSP crate.
+//!
+//!
+//! # Regex Search
+//!
+//! The `regex` feature flag enables the `regex` module, and adds regex
+//! search methods to `Rope` and `RopeSlice` that run directly over the
+//! rope's chunks via the `regex-automata` crate.
+//!
+//! Similarly, the `multi_search` feature flag enables the `multi_search`
+//! module, for searching for many literal patterns at once with an
+//! Aho-Corasick automaton from the `aho-corasick` crate.
 
 // Clippy lints that we intentionally don't follow.
 #![allow(clippy::collapsible_if)]
@@ -183,22 +214,48 @@
 #![allow(clippy::needless_borrow)]
 #![allow(clippy::partialeq_to_none)]
 
+#[cfg(feature = "multi_search")]
+extern crate aho_corasick;
+#[cfg(test)]
+extern crate fastrand;
+extern crate memchr;
+#[cfg(feature = "regex")]
+extern crate regex_automata;
 extern crate smallvec;
 extern crate str_indices;
+#[cfg(feature = "graphemes")]
+extern crate unicode_segmentation;
 
 mod crlf;
+mod line_ending;
+mod position;
+mod reader;
 mod rope;
 mod rope_builder;
+mod search;
 mod slice;
+mod span_tree;
 mod tree;
 
+pub mod attributed;
+pub mod encoding;
 pub mod iter;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+pub mod marker;
+#[cfg(feature = "multi_search")]
+pub mod multi_search;
+#[cfg(feature = "regex")]
+pub mod regex;
 pub mod str_utils;
 
 use std::ops::Bound;
 
+pub use crate::line_ending::{LineEnding, LineEndingStats};
+pub use crate::position::{LineCol, PositionEncoding};
+pub use crate::reader::RopeReader;
 pub use crate::rope::Rope;
-pub use crate::rope_builder::RopeBuilder;
+pub use crate::rope_builder::{RopeBuilder, Utf8Replacement};
 pub use crate::slice::RopeSlice;
 
 /// NOT PART OF THE PUBLIC API (hidden from docs for a reason!)
@@ -298,6 +355,44 @@ pub enum Error {
         Option<usize>, // End.
         usize,         // Rope char length.
     ),
+
+    /// Indicates that two char-index ranges in a batch of edits overlapped.
+    ///
+    /// Contains the [start, end) char indices of the range that overlapped
+    /// a preceding range, in that order.
+    CharRangeOverlap(
+        usize, // Start.
+        usize, // End.
+    ),
+
+    /// Indicates that the destination char index of a move was inside the
+    /// char-index range being moved.
+    ///
+    /// Contains the destination char index and the [start, end) char
+    /// indices of the range being moved, in that order.
+    CharIndexInsideMovedRange(
+        usize, // Destination.
+        usize, // Start.
+        usize, // End.
+    ),
+
+    /// Indicates that the column of a line/column position was past the
+    /// end of its line.  The end of a line is before its line break.
+    ///
+    /// Contains the column attempted and the actual length of the line
+    /// (excluding its line break), both in the units of the position
+    /// encoding, in that order.
+    ColumnIndexOutOfBounds(
+        usize, // Column.
+        usize, // Line length.
+    ),
+
+    /// Indicates that the column of a line/column position was in the
+    /// middle of a char, e.g. between the two code units of a utf16
+    /// surrogate pair.
+    ///
+    /// Contains the column attempted.
+    ColumnIndexNotCharBoundary(usize),
 }
 
 impl std::error::Error for Error {
@@ -378,6 +473,34 @@ impl std::fmt::Debug for Error {
                 write_range(f, start_idx_opt, end_idx_opt)?;
                 write!(f, ", Rope/RopeSlice char length {}", len)
             }
+            Error::CharRangeOverlap(start_idx, end_idx) => {
+                write!(
+                    f,
+                    "Char range {}..{} overlaps another range in the same batch of edits",
+                    start_idx, end_idx
+                )
+            }
+            Error::CharIndexInsideMovedRange(dest_idx, start_idx, end_idx) => {
+                write!(
+                    f,
+                    "Char index {} is inside the char range {}..{} being moved",
+                    dest_idx, start_idx, end_idx
+                )
+            }
+            Error::ColumnIndexOutOfBounds(col, len) => {
+                write!(
+                    f,
+                    "Column index out of bounds: column {}, line length {}",
+                    col, len
+                )
+            }
+            Error::ColumnIndexNotCharBoundary(col) => {
+                write!(
+                    f,
+                    "Column index is not a valid char boundary: column {}",
+                    col
+                )
+            }
         }
     }
 }
@@ -0,0 +1,340 @@
+use crate::str_utils::trim_line_break;
+
+/// A kind of line break.
+///
+/// Which of these Ropey recognizes as line breaks depends on the line break
+/// feature flags (see the [crate-level docs](crate#a-note-about-line-breaks)):
+/// `Lf` and `Crlf` are always recognized, `Cr` only with `cr_lines`, and the
+/// rest only with `unicode_lines`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// Line Feed, `U+000A`.
+    Lf,
+    /// Carriage Return followed by Line Feed, `U+000D U+000A`.
+    Crlf,
+    /// Carriage Return, `U+000D`.
+    Cr,
+    /// Vertical Tab, `U+000B`.
+    Vt,
+    /// Form Feed, `U+000C`.
+    Ff,
+    /// Next Line, `U+0085`.
+    Nel,
+    /// Line Separator, `U+2028`.
+    Ls,
+    /// Paragraph Separator, `U+2029`.
+    Ps,
+}
+
+impl LineEnding {
+    /// All kinds of line break, in declaration order.
+    pub(crate) const ALL: [LineEnding; 8] = [
+        LineEnding::Lf,
+        LineEnding::Crlf,
+        LineEnding::Cr,
+        LineEnding::Vt,
+        LineEnding::Ff,
+        LineEnding::Nel,
+        LineEnding::Ls,
+        LineEnding::Ps,
+    ];
+
+    /// Returns the text of the line break.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            LineEnding::Lf => "\u{000A}",
+            LineEnding::Crlf => "\u{000D}\u{000A}",
+            LineEnding::Cr => "\u{000D}",
+            LineEnding::Vt => "\u{000B}",
+            LineEnding::Ff => "\u{000C}",
+            LineEnding::Nel => "\u{0085}",
+            LineEnding::Ls => "\u{2028}",
+            LineEnding::Ps => "\u{2029}",
+        }
+    }
+
+    /// Returns the length of the line break in bytes.
+    #[inline]
+    pub fn len_bytes(&self) -> usize {
+        self.as_str().len()
+    }
+
+    /// Returns the length of the line break in chars.
+    #[inline]
+    pub fn len_chars(&self) -> usize {
+        if *self == LineEnding::Crlf {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Returns the line break that `text` ends with, if any.
+    ///
+    /// Only line breaks recognized under the enabled feature flags are
+    /// returned, except that a trailing CRLF pair is always returned as a
+    /// unit, like in `next_line_ending()`.
+    pub(crate) fn at_end_of(text: &str) -> Option<LineEnding> {
+        if text.ends_with("\u{000D}\u{000A}") {
+            return Some(LineEnding::Crlf);
+        }
+        match &text[trim_line_break(text).len()..] {
+            "\u{000A}" => Some(LineEnding::Lf),
+            "\u{000D}" => Some(LineEnding::Cr),
+            "\u{000B}" => Some(LineEnding::Vt),
+            "\u{000C}" => Some(LineEnding::Ff),
+            "\u{0085}" => Some(LineEnding::Nel),
+            "\u{2028}" => Some(LineEnding::Ls),
+            "\u{2029}" => Some(LineEnding::Ps),
+            _ => None,
+        }
+    }
+}
+
+/// The number of line breaks of each kind in a text.
+///
+/// Returned by [`Rope::line_ending_stats()`](crate::Rope::line_ending_stats).
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LineEndingStats {
+    /// Number of [`LineEnding::Lf`] line breaks.
+    pub lf: usize,
+    /// Number of [`LineEnding::Crlf`] line breaks.
+    pub crlf: usize,
+    /// Number of [`LineEnding::Cr`] line breaks.
+    pub cr: usize,
+    /// Number of [`LineEnding::Vt`] line breaks.
+    pub vt: usize,
+    /// Number of [`LineEnding::Ff`] line breaks.
+    pub ff: usize,
+    /// Number of [`LineEnding::Nel`] line breaks.
+    pub nel: usize,
+    /// Number of [`LineEnding::Ls`] line breaks.
+    pub ls: usize,
+    /// Number of [`LineEnding::Ps`] line breaks.
+    pub ps: usize,
+}
+
+impl LineEndingStats {
+    /// Returns the number of line breaks of the given kind.
+    #[inline]
+    pub fn count(&self, line_ending: LineEnding) -> usize {
+        match line_ending {
+            LineEnding::Lf => self.lf,
+            LineEnding::Crlf => self.crlf,
+            LineEnding::Cr => self.cr,
+            LineEnding::Vt => self.vt,
+            LineEnding::Ff => self.ff,
+            LineEnding::Nel => self.nel,
+            LineEnding::Ls => self.ls,
+            LineEnding::Ps => self.ps,
+        }
+    }
+
+    /// Returns the total number of line breaks.
+    #[inline]
+    pub fn total(&self) -> usize {
+        LineEnding::ALL.iter().map(|&le| self.count(le)).sum()
+    }
+
+    /// Returns the most frequent kind of line break, or `None` if there
+    /// are no line breaks.
+    ///
+    /// Ties go to the kind declared first in [`LineEnding`].
+    pub fn most_common(&self) -> Option<LineEnding> {
+        let mut best = None;
+        let mut best_count = 0;
+        for &le in LineEnding::ALL.iter() {
+            if self.count(le) > best_count {
+                best = Some(le);
+                best_count = self.count(le);
+            }
+        }
+        best
+    }
+
+    /// Returns whether more than one kind of line break is present.
+    pub fn is_mixed(&self) -> bool {
+        LineEnding::ALL
+            .iter()
+            .filter(|&&le| self.count(le) > 0)
+            .count()
+            > 1
+    }
+
+    /// Adds the line breaks in `text` to the counts.
+    pub(crate) fn add_str(&mut self, text: &str) {
+        let mut i = 0;
+        while let Some((offset, le)) = next_line_ending(&text.as_bytes()[i..]) {
+            match le {
+                LineEnding::Lf => self.lf += 1,
+                LineEnding::Crlf => self.crlf += 1,
+                LineEnding::Cr => self.cr += 1,
+                LineEnding::Vt => self.vt += 1,
+                LineEnding::Ff => self.ff += 1,
+                LineEnding::Nel => self.nel += 1,
+                LineEnding::Ls => self.ls += 1,
+                LineEnding::Ps => self.ps += 1,
+            }
+            i += offset + le.len_bytes();
+        }
+    }
+}
+
+/// Finds the first line break in `text`, returning its byte index and kind.
+///
+/// Only line breaks recognized under the enabled feature flags are found,
+/// except that a CRLF pair is always found as a unit, so that converting
+/// line breaks never splits one.  (Without `cr_lines` its LF is still the
+/// only line break, so line counts agree either way.)
+///
+/// A CRLF pair split across the end of `text` is found as a lone CR.
+/// Ropey never splits CRLF pairs between leaves or chunks, so this only
+/// matters for text that was cut at an arbitrary byte index.
+pub(crate) fn next_line_ending(text: &[u8]) -> Option<(usize, LineEnding)> {
+    for (i, &byte) in text.iter().enumerate() {
+        let le = match byte {
+            0x0A => LineEnding::Lf,
+            0x0D if text.get(i + 1) == Some(&0x0A) => LineEnding::Crlf,
+            #[cfg(feature = "cr_lines")]
+            0x0D => LineEnding::Cr,
+            #[cfg(feature = "unicode_lines")]
+            0x0B => LineEnding::Vt,
+            #[cfg(feature = "unicode_lines")]
+            0x0C => LineEnding::Ff,
+            #[cfg(feature = "unicode_lines")]
+            0xC2 if text.get(i + 1) == Some(&0x85) => LineEnding::Nel,
+            #[cfg(feature = "unicode_lines")]
+            0xE2 if text.get(i + 1) == Some(&0x80) && text.get(i + 2) == Some(&0xA8) => {
+                LineEnding::Ls
+            }
+            #[cfg(feature = "unicode_lines")]
+            0xE2 if text.get(i + 1) == Some(&0x80) && text.get(i + 2) == Some(&0xA9) => {
+                LineEnding::Ps
+            }
+            _ => continue,
+        };
+        return Some((i, le));
+    }
+    None
+}
+
+/// Appends `text` to `out` with all of its line breaks (as found by
+/// `next_line_ending()`) replaced by `line_ending`.
+///
+/// Returns the number of line breaks that were changed.
+pub(crate) fn convert_line_endings(text: &str, line_ending: LineEnding, out: &mut String) -> usize {
+    let mut count = 0;
+    let mut copied = 0; // Byte index of the end of the text copied so far.
+    let mut i = 0;
+    while let Some((offset, le)) = next_line_ending(&text.as_bytes()[i..]) {
+        let start = i + offset;
+        i = start + le.len_bytes();
+        if le != line_ending {
+            out.push_str(&text[copied..start]);
+            out.push_str(line_ending.as_str());
+            copied = i;
+            count += 1;
+        }
+    }
+    out.push_str(&text[copied..]);
+    count
+}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn at_end_of_01() {
+        assert_eq!(LineEnding::at_end_of(""), None);
+        assert_eq!(LineEnding::at_end_of("Hi"), None);
+        assert_eq!(LineEnding::at_end_of("Hi\n"), Some(LineEnding::Lf));
+        assert_eq!(LineEnding::at_end_of("\n\n"), Some(LineEnding::Lf));
+        assert_eq!(LineEnding::at_end_of("Hi\nthere"), None);
+        assert_eq!(LineEnding::at_end_of("Hi\r\n"), Some(LineEnding::Crlf));
+        assert_eq!(LineEnding::at_end_of("\r\n"), Some(LineEnding::Crlf));
+        assert_eq!(LineEnding::at_end_of("\n\r\n"), Some(LineEnding::Crlf));
+
+        #[cfg(feature = "cr_lines")]
+        assert_eq!(LineEnding::at_end_of("Hi\r"), Some(LineEnding::Cr));
+        #[cfg(not(feature = "cr_lines"))]
+        assert_eq!(LineEnding::at_end_of("Hi\r"), None);
+
+        #[cfg(feature = "unicode_lines")]
+        {
+            assert_eq!(LineEnding::at_end_of("Hi\u{000B}"), Some(LineEnding::Vt));
+            assert_eq!(LineEnding::at_end_of("Hi\u{000C}"), Some(LineEnding::Ff));
+            assert_eq!(LineEnding::at_end_of("Hi\u{0085}"), Some(LineEnding::Nel));
+            assert_eq!(LineEnding::at_end_of("Hi\u{2028}"), Some(LineEnding::Ls));
+            assert_eq!(LineEnding::at_end_of("Hi\u{2029}"), Some(LineEnding::Ps));
+        }
+        #[cfg(not(feature = "unicode_lines"))]
+        assert_eq!(LineEnding::at_end_of("Hi\u{2028}"), None);
+    }
+
+    #[test]
+    fn len_01() {
+        for ending in LineEnding::ALL.iter() {
+            assert_eq!(ending.len_bytes(), ending.as_str().len());
+            assert_eq!(ending.len_chars(), ending.as_str().chars().count());
+        }
+    }
+
+    #[test]
+    fn stats_01() {
+        let mut stats = LineEndingStats::default();
+        assert_eq!(stats.total(), 0);
+        assert_eq!(stats.most_common(), None);
+        assert!(!stats.is_mixed());
+
+        stats.add_str("a\nb\r\nc\r\n\n\n");
+        assert_eq!(stats.lf, 3);
+        assert_eq!(stats.crlf, 2);
+        assert_eq!(stats.total(), 5);
+        assert_eq!(stats.most_common(), Some(LineEnding::Lf));
+        assert!(stats.is_mixed());
+
+        stats.add_str("\r\u{2028}\u{85}\r\n");
+        assert_eq!(stats.crlf, 3);
+        #[cfg(feature = "cr_lines")]
+        assert_eq!(stats.cr, 1);
+        #[cfg(feature = "unicode_lines")]
+        {
+            assert_eq!(stats.ls, 1);
+            assert_eq!(stats.nel, 1);
+            assert_eq!(stats.total(), 9);
+        }
+        #[cfg(not(feature = "cr_lines"))]
+        assert_eq!(stats.total(), 6);
+    }
+
+    #[test]
+    fn convert_line_endings_01() {
+        let convert = |text: &str, le: LineEnding| {
+            let mut out = String::new();
+            let count = convert_line_endings(text, le, &mut out);
+            (out, count)
+        };
+        assert_eq!(convert("", LineEnding::Lf), ("".into(), 0));
+        assert_eq!(convert("a\nb\n", LineEnding::Lf), ("a\nb\n".into(), 0));
+        assert_eq!(
+            convert("a\nb\r\nc", LineEnding::Crlf),
+            ("a\r\nb\r\nc".into(), 1)
+        );
+        assert_eq!(convert("\r\n\r\n\n", LineEnding::Lf), ("\n\n\n".into(), 2));
+
+        #[cfg(feature = "cr_lines")]
+        assert_eq!(
+            convert("a\rb\r\r\n", LineEnding::Lf),
+            ("a\nb\n\n".into(), 3)
+        );
+        #[cfg(feature = "unicode_lines")]
+        assert_eq!(
+            convert("a\u{2029}b\u{0B}\u{0C}", LineEnding::Crlf),
+            ("a\r\nb\r\n\r\n".into(), 3)
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Applying incremental, line/column based text changes to a `Rope`.
+//!
+//! Language servers receive edits to open documents as a sequence of
+//! changes, each of which replaces the text between two line/column
+//! positions (or the whole document).  This module applies such changes to
+//! a `Rope`, without depending on any particular LSP crate:
+//!
+//! ```
+//! # use ropey::Rope;
+//! use ropey::lsp::{apply_changes, TextChange};
+//! use ropey::{LineCol, PositionEncoding};
+//!
+//! let mut rope = Rope::from_str("Hello world!\nHow are you?");
+//! apply_changes(
+//!     &mut rope,
+//!     &[
+//!         TextChange::new(LineCol::new(0, 6)..LineCol::new(0, 11), "there"),
+//!         TextChange::new(LineCol::new(1, 4)..LineCol::new(1, 7), "is it"),
+//!     ],
+//!     PositionEncoding::Utf16,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(rope, "Hello there!\nHow is it you?");
+//! ```
+//!
+//! Lines are counted the way the LSP specification counts them: only LF,
+//! CRLF, and CR are line breaks, regardless of Ropey's line break feature
+//! flags.  So, for example, a `U+2028` Line Separator does not start a new
+//! line here even with `unicode_lines` enabled.  Because Ropey's line index
+//! can't be used for this, finding a position takes time proportional to
+//! how far into the document it is.
+//!
+//! Requires the `lsp` feature flag.
+
+use std::ops::Range;
+
+use crate::position::{LineCol, PositionEncoding};
+use crate::rope::Rope;
+use crate::{Error, Result};
+
+/// A single change to a document's text.
+///
+/// Corresponds to the LSP `TextDocumentContentChangeEvent` type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextChange {
+    /// The range of text to replace, or `None` to replace the entire
+    /// document.
+    ///
+    /// The range is expressed in terms of the document's text *before* the
+    /// change is applied.
+    pub range: Option<Range<LineCol>>,
+    /// The replacement text.
+    pub text: String,
+}
+
+impl TextChange {
+    /// Creates a change that replaces the text in `range`.
+    #[inline]
+    pub fn new(range: Range<LineCol>, text: &str) -> TextChange {
+        TextChange {
+            range: Some(range),
+            text: text.into(),
+        }
+    }
+
+    /// Creates a change that replaces the entire document.
+    #[inline]
+    pub fn full(text: &str) -> TextChange {
+        TextChange {
+            range: None,
+            text: text.into(),
+        }
+    }
+}
+
+/// Applies a single change to `rope`.
+///
+/// Columns in the change's range are interpreted according to `encoding`.
+///
+/// On failure `rope` is left unmodified.
+///
+/// # Errors
+///
+/// - If either end of the range is not a valid position in `rope`.  This is
+///   checked like in [`Rope::line_col_to_char()`], except that lines are
+///   counted as described in the [module docs](self).
+/// - If the start of the range is after its end.
+pub fn apply_change(
+    rope: &mut Rope,
+    change: &TextChange,
+    encoding: PositionEncoding,
+) -> Result<()> {
+    match change.range {
+        None => {
+            *rope = Rope::from_str(&change.text);
+            Ok(())
+        }
+        Some(ref range) => {
+            let start = position_to_char(rope, range.start, encoding)?;
+            let end = position_to_char(rope, range.end, encoding)?;
+            if start > end {
+                return Err(Error::CharRangeInvalid(start, end));
+            }
+            rope.try_replace(start..end, &change.text)
+        }
+    }
+}
+
+/// Applies a sequence of changes to `rope`, in order.
+///
+/// Each change's range refers to the text as left by the changes before it,
+/// as in the LSP `didChange` notification.  Columns are interpreted
+/// according to `encoding`.
+///
+/// The changes are applied atomically: if any of them is invalid, an error
+/// is returned and `rope` is left unmodified.
+///
+/// # Errors
+///
+/// Returns the error of the first invalid change.  See
+/// [`apply_change()`] for the conditions.
+pub fn apply_changes<'a, I>(rope: &mut Rope, changes: I, encoding: PositionEncoding) -> Result<()>
+where
+    I: IntoIterator<Item = &'a TextChange>,
+{
+    // Cloning is cheap, and lets us discard partially applied changes.
+    let mut new_rope = rope.clone();
+    for change in changes {
+        apply_change(&mut new_rope, change, encoding)?;
+    }
+    *rope = new_rope;
+    Ok(())
+}
+
+/// Returns the char index of `pos`, with lines counted as in LSP.
+fn position_to_char(rope: &Rope, pos: LineCol, encoding: PositionEncoding) -> Result<usize> {
+    let line_start = lsp_line_to_byte(rope, pos.line)?;
+    let line = rope.byte_slice(line_start..lsp_line_content_end(rope, line_start));
+    let line_char_idx = rope.byte_to_char(line_start);
+
+    let line_len = match encoding {
+        PositionEncoding::Utf8 => line.len_bytes(),
+        PositionEncoding::Utf16 => line.len_utf16_cu(),
+        PositionEncoding::Utf32 => line.len_chars(),
+    };
+    if pos.col > line_len {
+        return Err(Error::ColumnIndexOutOfBounds(pos.col, line_len));
+    }
+
+    let char_idx = match encoding {
+        PositionEncoding::Utf8 => {
+            let char_idx = line.byte_to_char(pos.col);
+            if line.char_to_byte(char_idx) != pos.col {
+                return Err(Error::ColumnIndexNotCharBoundary(pos.col));
+            }
+            char_idx
+        }
+        PositionEncoding::Utf16 => {
+            let char_idx = line.utf16_cu_to_char(pos.col);
+            if line.char_to_utf16_cu(char_idx) != pos.col {
+                return Err(Error::ColumnIndexNotCharBoundary(pos.col));
+            }
+            char_idx
+        }
+        PositionEncoding::Utf32 => pos.col,
+    };
+    Ok(line_char_idx + char_idx)
+}
+
+/// Returns the byte index of the start of the given LSP line.
+fn lsp_line_to_byte(rope: &Rope, line_idx: usize) -> Result<usize> {
+    if line_idx == 0 {
+        return Ok(0);
+    }
+
+    let mut line_count = 0;
+    let mut byte_idx = 0;
+    for chunk in rope.chunks() {
+        let bytes = chunk.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\n' => {}
+                // CRLF pairs are never split across chunks.
+                b'\r' if bytes.get(i + 1) == Some(&b'\n') => i += 1,
+                b'\r' => {}
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            }
+            i += 1;
+            line_count += 1;
+            if line_count == line_idx {
+                return Ok(byte_idx + i);
+            }
+        }
+        byte_idx += bytes.len();
+    }
+
+    Err(Error::LineIndexOutOfBounds(line_idx, line_count + 1))
+}
+
+/// Returns the byte index of the end of the contents of the LSP line
+/// starting at `line_start`, i.e. the start of its line break, if any.
+fn lsp_line_content_end(rope: &Rope, line_start: usize) -> usize {
+    let (chunks, chunk_byte_idx, ..) = rope.chunks_at_byte(line_start);
+    let mut byte_idx = chunk_byte_idx;
+    let mut skip = line_start - chunk_byte_idx;
+    for chunk in chunks {
+        if let Some(i) = chunk.as_bytes()[skip..]
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+        {
+            return byte_idx + skip + i;
+        }
+        byte_idx += chunk.len();
+        skip = 0;
+    }
+    rope.len_bytes()
+}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lc(line: usize, col: usize) -> LineCol {
+        LineCol::new(line, col)
+    }
+
+    #[test]
+    fn apply_change_01() {
+        let mut r = Rope::from_str("a🐸b\nc");
+        let change = TextChange::new(lc(0, 1)..lc(0, 3), "frog");
+
+        apply_change(&mut r, &change, PositionEncoding::Utf16).unwrap();
+        assert_eq!(r, "afrogb\nc");
+    }
+
+    #[test]
+    fn apply_change_02() {
+        let mut r = Rope::from_str("a🐸b\nc");
+        let change = TextChange::new(lc(0, 1)..lc(0, 5), "");
+
+        apply_change(&mut r, &change, PositionEncoding::Utf8).unwrap();
+        assert_eq!(r, "ab\nc");
+    }
+
+    #[test]
+    fn apply_change_03() {
+        let mut r = Rope::from_str("Hello\r\nworld");
+
+        apply_change(&mut r, &TextChange::full("Bye"), PositionEncoding::Utf32).unwrap();
+        assert_eq!(r, "Bye");
+    }
+
+    #[test]
+    fn apply_change_04() {
+        let mut r = Rope::from_str("a🐸b\nc");
+
+        // Mid-surrogate.
+        assert!(matches!(
+            apply_change(
+                &mut r,
+                &TextChange::new(lc(0, 2)..lc(0, 3), ""),
+                PositionEncoding::Utf16
+            ),
+            Err(Error::ColumnIndexNotCharBoundary(2))
+        ));
+        // Reversed range.
+        assert!(matches!(
+            apply_change(
+                &mut r,
+                &TextChange::new(lc(1, 0)..lc(0, 1), ""),
+                PositionEncoding::Utf32
+            ),
+            Err(Error::CharRangeInvalid(4, 1))
+        ));
+        // Stale line.
+        assert!(matches!(
+            apply_change(
+                &mut r,
+                &TextChange::new(lc(2, 0)..lc(2, 0), "x"),
+                PositionEncoding::Utf32
+            ),
+            Err(Error::LineIndexOutOfBounds(2, 2))
+        ));
+        assert_eq!(r, "a🐸b\nc");
+    }
+
+    #[test]
+    fn apply_change_05() {
+        // Only LF, CRLF, and CR are line breaks, whatever the feature flags.
+        let mut r = Rope::from_str("one\u{2028}two\rthree\r\nfour\u{0B}five\nsix");
+        let changes = [
+            TextChange::new(lc(0, 4)..lc(0, 7), "2"),
+            TextChange::new(lc(2, 5)..lc(3, 0), ""),
+        ];
+
+        apply_changes(&mut r, &changes, PositionEncoding::Utf16).unwrap();
+        assert_eq!(r, "one\u{2028}2\rthree\r\nfour\u{0B}six");
+
+        let change = TextChange::new(lc(3, 0)..lc(3, 0), "x");
+        assert!(matches!(
+            apply_change(&mut r, &change, PositionEncoding::Utf16),
+            Err(Error::LineIndexOutOfBounds(3, 3))
+        ));
+        let change = TextChange::new(lc(1, 6)..lc(1, 6), "x");
+        assert!(matches!(
+            apply_change(&mut r, &change, PositionEncoding::Utf16),
+            Err(Error::ColumnIndexOutOfBounds(6, 5))
+        ));
+        let change = TextChange::new(lc(0, 4)..lc(0, 4), "x");
+        assert!(matches!(
+            apply_change(&mut r, &change, PositionEncoding::Utf8),
+            Err(Error::ColumnIndexNotCharBoundary(4))
+        ));
+    }
+
+    #[test]
+    fn apply_change_06() {
+        let text: String = (0..100).map(|i| format!("{}\u{2028}{}\r", i, i)).collect();
+        let mut r = Rope::from_str(&text);
+        let change = TextChange::new(lc(57, 0)..lc(57, 2), "x");
+
+        apply_change(&mut r, &change, PositionEncoding::Utf32).unwrap();
+        assert_eq!(r, text.replace("\r57\u{2028}", "\rx\u{2028}").as_str());
+    }
+
+    #[test]
+    fn apply_changes_01() {
+        let mut r = Rope::from_str("one\ntwo\nthree");
+        let changes = [
+            TextChange::new(lc(1, 0)..lc(2, 0), ""),
+            // Refers to the text after the first change.
+            TextChange::new(lc(1, 0)..lc(1, 5), "3"),
+            TextChange::new(lc(0, 3)..lc(0, 3), "\n2"),
+        ];
+
+        apply_changes(&mut r, &changes, PositionEncoding::Utf16).unwrap();
+        assert_eq!(r, "one\n2\n3");
+    }
+
+    #[test]
+    fn apply_changes_02() {
+        let mut r = Rope::from_str("one\ntwo");
+        let changes = [
+            TextChange::new(lc(0, 0)..lc(0, 3), "1"),
+            // Stale: the first line is now only one column long.
+            TextChange::new(lc(0, 3)..lc(1, 0), ""),
+        ];
+
+        assert!(matches!(
+            apply_changes(&mut r, &changes, PositionEncoding::Utf8),
+            Err(Error::ColumnIndexOutOfBounds(3, 1))
+        ));
+        assert_eq!(r, "one\ntwo");
+    }
+}
@@ -0,0 +1,694 @@
+//! Markers that track char positions through edits.
+//!
+//! A [`MarkerSet`] holds a set of positions (e.g. cursors, bookmarks, or
+//! diagnostics) in a `Rope`'s text, and keeps them up to date as the text
+//! is edited through the `MarkerSet`'s editing methods, which perform the
+//! edit on the `Rope` and update the markers together:
+//!
+//! ```
+//! # use ropey::Rope;
+//! use ropey::marker::{Gravity, MarkerSet};
+//!
+//! let mut rope = Rope::from_str("Hello world!");
+//! let mut markers = MarkerSet::new();
+//! let m = markers.add(6, Gravity::Left);
+//!
+//! markers.insert(&mut rope, 0, "Well, ");
+//! assert_eq!(markers.position(m), Some(12));
+//! assert_eq!(rope.slice(12..), "world!");
+//! ```
+//!
+//! # Keeping markers in sync
+//!
+//! **A `MarkerSet` is not attached to a `Rope`, and doesn't see edits made
+//! to it directly.**  Its markers are only updated by edits made through
+//! its own [`insert()`](MarkerSet::insert), [`remove()`](MarkerSet::remove),
+//! [`split_off()`](MarkerSet::split_off), and
+//! [`append()`](MarkerSet::append) methods, or reported to it with
+//! [`adjust_for_insert()`](MarkerSet::adjust_for_insert) and
+//! [`adjust_for_remove()`](MarkerSet::adjust_for_remove).  Any other edit
+//! to the rope (e.g. calling `Rope::insert()` directly) silently leaves the
+//! markers at stale positions, so every edit to a rope with markers must go
+//! through one of those methods.
+//!
+//! # Gravity
+//!
+//! A marker's [`Gravity`] determines what happens to it when text is
+//! inserted exactly at its position.  A marker with left gravity sticks to
+//! the char on its left and stays put, whereas a marker with right gravity
+//! sticks to the char on its right and moves to the end of the inserted
+//! text.  Gravity likewise determines which side a marker ends up on when
+//! a `MarkerSet` is split at its position.
+//!
+//! # Performance
+//!
+//! Markers are kept in position order in a balanced tree, each storing its
+//! distance from the marker before it, so that an edit only has to adjust
+//! the first marker after it.  Adding, deleting, and looking up a marker,
+//! finding the markers within a char range, and adjusting for an insertion
+//! all take O(log M) time, where M is the number of markers in the set.
+//! Adjusting for a removal takes O(K log M) time on top of that, where K is
+//! the number of markers collapsed by the removal.
+
+use std::collections::HashMap;
+use std::ops::{Range, RangeBounds};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::rope::Rope;
+use crate::span_tree::{Span, SpanId, SpanIter, SpanTree};
+use crate::{end_bound_to_num, start_bound_to_num};
+
+/// Source of marker ids.  Ids are unique across all `MarkerSet`s, so that
+/// sets can be split and appended without ids colliding.
+static NEXT_MARKER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// An identifier for a marker in a [`MarkerSet`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MarkerId(usize);
+
+/// Which way a marker leans when text is inserted at its position.
+///
+/// See the [module documentation](self) for details.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Gravity {
+    /// Stays before text inserted at the marker's position.
+    Left,
+    /// Moves after text inserted at the marker's position.
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Marker {
+    /// Distance in chars from the previous marker, or from the start of the
+    /// text for the first marker.
+    gap: usize,
+    gravity: Gravity,
+    id: MarkerId,
+}
+
+impl Span for Marker {
+    #[inline(always)]
+    fn span_len(&self) -> usize {
+        self.gap
+    }
+}
+
+/// A set of char positions that are kept up to date through edits.
+///
+/// The markers are only updated by edits made through the set's own
+/// methods: editing the `Rope` directly leaves them stale.  See the
+/// [module documentation](self) for details.
+#[derive(Debug, Clone, Default)]
+pub struct MarkerSet {
+    /// Sorted by position, and markers at the same position are ordered
+    /// left-gravity first, which keeps the set sorted when text is inserted
+    /// at that position.
+    markers: SpanTree<Marker>,
+    /// Maps each marker's id to its node in `markers`.
+    slots: HashMap<MarkerId, SpanId>,
+}
+
+impl MarkerSet {
+    //-----------------------------------------------------------------------
+    // Constructors and markers
+
+    /// Creates an empty `MarkerSet`.
+    #[inline]
+    pub fn new() -> Self {
+        MarkerSet {
+            markers: SpanTree::new(),
+            slots: HashMap::new(),
+        }
+    }
+
+    /// Total number of markers in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.markers.count()
+    }
+
+    /// Returns whether the set contains no markers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a marker at `char_idx` with the given gravity, and returns its id.
+    ///
+    /// The `MarkerSet` doesn't know the length of the text it tracks, so
+    /// it's up to the caller to make sure `char_idx` is in bounds.
+    ///
+    /// Runs in O(log M) time.
+    pub fn add(&mut self, char_idx: usize, gravity: Gravity) -> MarkerId {
+        let id = MarkerId(NEXT_MARKER_ID.fetch_add(1, Ordering::Relaxed));
+        self.insert_marker(char_idx, gravity, id);
+        id
+    }
+
+    /// Removes the marker `id` from the set, and returns its position.
+    ///
+    /// Returns `None` if the marker isn't in the set.
+    ///
+    /// Runs in O(log M) time.
+    pub fn delete(&mut self, id: MarkerId) -> Option<usize> {
+        let slot = self.slots.remove(&id)?;
+        let char_idx = self.position_of(slot);
+        let rank = self.markers.rank(slot);
+        let marker = self.markers.remove_range(rank..(rank + 1))[0];
+        // The next marker takes over the deleted marker's distance.
+        if let Some(next) = self.markers.get(rank) {
+            self.markers.update_item(next, |m| m.gap += marker.gap);
+        }
+        Some(char_idx)
+    }
+
+    /// Returns the current char position of the marker `id`.
+    ///
+    /// Returns `None` if the marker isn't in the set.
+    ///
+    /// Runs in O(log M) time.
+    #[inline]
+    pub fn position(&self, id: MarkerId) -> Option<usize> {
+        self.slots.get(&id).map(|&slot| self.position_of(slot))
+    }
+
+    /// Returns the gravity of the marker `id`.
+    ///
+    /// Returns `None` if the marker isn't in the set.
+    ///
+    /// Runs in O(1) time.
+    #[inline]
+    pub fn gravity(&self, id: MarkerId) -> Option<Gravity> {
+        self.slots
+            .get(&id)
+            .map(|&slot| self.markers.item(slot).gravity)
+    }
+
+    /// Returns whether the marker `id` is in the set.
+    #[inline]
+    pub fn contains(&self, id: MarkerId) -> bool {
+        self.slots.contains_key(&id)
+    }
+
+    /// Creates an iterator over all markers in the set, in position order.
+    ///
+    /// Yields `(id, char_idx)` pairs.
+    #[inline]
+    pub fn iter(&self) -> Markers<'_> {
+        self.markers_at(0..self.len())
+    }
+
+    /// Creates an iterator over the markers positioned within `char_range`,
+    /// in position order.
+    ///
+    /// Yields `(id, char_idx)` pairs.  Uses range syntax, e.g. `2..7`,
+    /// `2..=7`, or `..`.
+    ///
+    /// Runs in O(log M) time.
+    pub fn in_range<R>(&self, char_range: R) -> Markers<'_>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = start_bound_to_num(char_range.start_bound()).unwrap_or(0);
+        let end = end_bound_to_num(char_range.end_bound());
+
+        let start_i = self.markers.partition_point(|s, m| s + m.gap < start);
+        let end_i = match end {
+            Some(end) => self.markers.partition_point(|s, m| s + m.gap < end),
+            None => self.len(),
+        };
+
+        self.markers_at(start_i..end_i.max(start_i))
+    }
+
+    //-----------------------------------------------------------------------
+    // Edit methods
+
+    /// Inserts `text` at char index `char_idx` in `rope`, and updates the
+    /// markers to match.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    pub fn insert(&mut self, rope: &mut Rope, char_idx: usize, text: &str) {
+        let len_before = rope.len_chars();
+        rope.insert(char_idx, text);
+        self.adjust_for_insert(char_idx, rope.len_chars() - len_before);
+    }
+
+    /// Removes the text in the given char index range from `rope`, and
+    /// updates the markers to match.
+    ///
+    /// Markers within the removed range are moved to its start.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. `end > len_chars()`).
+    pub fn remove<R>(&mut self, rope: &mut Rope, char_range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = start_bound_to_num(char_range.start_bound()).unwrap_or(0);
+        let end = end_bound_to_num(char_range.end_bound()).unwrap_or_else(|| rope.len_chars());
+        rope.remove(start..end);
+        self.adjust_for_remove(start..end);
+    }
+
+    /// Splits `rope` at `char_idx`, and the markers along with it.
+    ///
+    /// On return, `rope` and `self` contain `[0, char_idx)`, and the returned
+    /// `Rope` and `MarkerSet` contain `[char_idx, len_chars)`, with the
+    /// returned markers' positions relative to the start of the returned
+    /// `Rope`.  Markers exactly at `char_idx` stay in `self` if they have
+    /// left gravity, and go to the returned set if they have right gravity.
+    ///
+    /// Runs in O(log N + K log M) time, where K is the number of markers
+    /// moved to the returned set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    pub fn split_off(&mut self, rope: &mut Rope, char_idx: usize) -> (Rope, MarkerSet) {
+        let other_rope = rope.split_off(char_idx);
+        let other_markers = self.split_off_markers(char_idx);
+        (other_rope, other_markers)
+    }
+
+    /// Appends `other_rope` to the end of `rope`, and `other` to the end of
+    /// `self`.
+    ///
+    /// The markers of `other` are shifted to stay attached to the text of
+    /// `other_rope`.
+    ///
+    /// Runs in O(log N + K log M) time, where K is the number of markers in
+    /// `other`.
+    pub fn append(&mut self, rope: &mut Rope, other_rope: Rope, other: MarkerSet) {
+        let offset = rope.len_chars();
+        rope.append(other_rope);
+        self.append_markers(other, offset);
+    }
+
+    /// Updates the markers for an insertion of `len_chars` chars at
+    /// `char_idx` that was done by other means.
+    ///
+    /// Runs in O(log M) time.
+    pub fn adjust_for_insert(&mut self, char_idx: usize, len_chars: usize) {
+        if len_chars == 0 {
+            return;
+        }
+        // Only the first marker after the insertion needs to move: the
+        // markers after it are positioned relative to it.
+        let i = self.rank_after(char_idx, Gravity::Left);
+        if let Some(next) = self.markers.get(i) {
+            self.markers.update_item(next, |m| m.gap += len_chars);
+        }
+    }
+
+    /// Updates the markers for a removal of `char_range` that was done by
+    /// other means.
+    ///
+    /// Markers within the removed range are moved to its start.
+    ///
+    /// Runs in O((K + 1) log M) time, where K is the number of markers
+    /// within the removed range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is unbounded at the end, or if its start is
+    /// greater than its end.
+    pub fn adjust_for_remove<R>(&mut self, char_range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = start_bound_to_num(char_range.start_bound()).unwrap_or(0);
+        let end = end_bound_to_num(char_range.end_bound())
+            .expect("adjust_for_remove(): the range must have an end.");
+        assert!(
+            start <= end,
+            "adjust_for_remove(): invalid range {}..{}",
+            start,
+            end
+        );
+        if start == end {
+            return;
+        }
+
+        // Take out the markers that end up at `start`, including those at
+        // `end`, since they may need reordering by gravity.
+        let i = self.markers.partition_point(|s, m| s + m.gap <= start);
+        let j = self.markers.partition_point(|s, m| s + m.gap <= end);
+        let collapsed = self.markers.remove_range(i..j);
+
+        // Close up the removed text in the distance to the first marker
+        // after it, which also takes over the taken out markers' distances.
+        if let Some(next) = self.markers.get(i) {
+            let taken: usize = collapsed.iter().map(|m| m.gap).sum();
+            self.markers
+                .update_item(next, |m| m.gap = m.gap + taken - (end - start));
+        }
+
+        // Put them back at `start`, in gravity order.
+        for m in collapsed {
+            self.insert_marker(start, m.gravity, m.id);
+        }
+    }
+
+    //-----------------------------------------------------------------------
+    // Internal utilities
+
+    #[inline]
+    fn position_of(&self, slot: SpanId) -> usize {
+        self.markers.offset(slot) + self.markers.item(slot).gap
+    }
+
+    /// Returns the number of markers that sort before or with a marker at
+    /// `char_idx` with the given gravity.
+    #[inline]
+    fn rank_after(&self, char_idx: usize, gravity: Gravity) -> usize {
+        self.markers
+            .partition_point(|s, m| (s + m.gap, m.gravity) <= (char_idx, gravity))
+    }
+
+    /// Inserts a marker after all the markers at or before its position with
+    /// the same or lesser gravity.
+    fn insert_marker(&mut self, char_idx: usize, gravity: Gravity, id: MarkerId) {
+        let i = self.rank_after(char_idx, gravity);
+        let gap = char_idx - self.markers.offset_of_rank(i);
+        if let Some(next) = self.markers.get(i) {
+            self.markers.update_item(next, |m| m.gap -= gap);
+        }
+        let slot = self.markers.insert(
+            i,
+            Marker {
+                gap: gap,
+                gravity: gravity,
+                id: id,
+            },
+        );
+        self.slots.insert(id, slot);
+    }
+
+    /// Takes the markers at `ranks` out of the set, and returns them in
+    /// order along with their positions.
+    fn take_markers(&mut self, ranks: Range<usize>) -> Vec<(usize, Marker)> {
+        let mut char_idx = self.markers.offset_of_rank(ranks.start);
+        let markers = self.markers.remove_range(ranks);
+        markers
+            .into_iter()
+            .map(|m| {
+                self.slots.remove(&m.id);
+                char_idx += m.gap;
+                (char_idx, m)
+            })
+            .collect()
+    }
+
+    fn split_off_markers(&mut self, char_idx: usize) -> MarkerSet {
+        let i = self.rank_after(char_idx, Gravity::Left);
+        let mut other = MarkerSet::new();
+        for (idx, m) in self.take_markers(i..self.len()) {
+            other.insert_marker(idx - char_idx, m.gravity, m.id);
+        }
+        other
+    }
+
+    fn append_markers(&mut self, mut other: MarkerSet, offset: usize) {
+        // Inserting them one by one puts `other`'s left-gravity markers at
+        // its start before `self`'s right-gravity markers at its end.
+        let len = other.len();
+        for (idx, m) in other.take_markers(0..len) {
+            self.insert_marker(idx + offset, m.gravity, m.id);
+        }
+    }
+
+    fn markers_at(&self, ranks: Range<usize>) -> Markers<'_> {
+        Markers {
+            front: self.markers.offset_of_rank(ranks.start),
+            back: self.markers.offset_of_rank(ranks.end),
+            iter: self.markers.iter_range(ranks),
+        }
+    }
+}
+
+//==============================================================
+
+/// An iterator over the markers of a [`MarkerSet`].
+///
+/// Yields `(id, char_idx)` pairs in position order.
+#[derive(Debug, Clone)]
+pub struct Markers<'a> {
+    iter: SpanIter<'a, Marker>,
+    front: usize, // Position of the marker before the next one from the front.
+    back: usize,  // Position of the next marker from the back.
+}
+
+impl<'a> Iterator for Markers<'a> {
+    type Item = (MarkerId, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<(MarkerId, usize)> {
+        let m = self.iter.next()?;
+        self.front += m.gap;
+        Some((m.id, self.front))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Markers<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(MarkerId, usize)> {
+        let m = self.iter.next_back()?;
+        let char_idx = self.back;
+        self.back -= m.gap;
+        Some((m.id, char_idx))
+    }
+}
+
+impl<'a> ExactSizeIterator for Markers<'a> {}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(set: &MarkerSet) -> Vec<usize> {
+        set.iter().map(|(_, idx)| idx).collect()
+    }
+
+    fn assert_sorted(set: &MarkerSet) {
+        let mut prev = None;
+        for (rank, (id, char_idx)) in set.iter().enumerate() {
+            let gravity = set.gravity(id).unwrap();
+            assert!(prev <= Some((char_idx, gravity)));
+            assert_eq!(set.slots[&id], set.markers.get(rank).unwrap());
+            assert_eq!(set.position(id), Some(char_idx));
+            prev = Some((char_idx, gravity));
+        }
+        assert_eq!(set.slots.len(), set.len());
+    }
+
+    #[test]
+    fn add_delete_01() {
+        let mut set = MarkerSet::new();
+        let a = set.add(5, Gravity::Left);
+        let b = set.add(2, Gravity::Right);
+        let c = set.add(8, Gravity::Left);
+
+        assert_eq!(set.len(), 3);
+        assert_eq!(positions(&set), vec![2, 5, 8]);
+        assert_eq!(set.delete(a), Some(5));
+        assert_eq!(set.delete(a), None);
+        assert_eq!(set.position(a), None);
+        assert_eq!(set.position(b), Some(2));
+        assert_eq!(set.position(c), Some(8));
+        assert_eq!(set.gravity(b), Some(Gravity::Right));
+        assert_sorted(&set);
+    }
+
+    #[test]
+    fn insert_01() {
+        let mut r = Rope::from_str("Hello world!");
+        let mut set = MarkerSet::new();
+        let before = set.add(2, Gravity::Right);
+        let left = set.add(6, Gravity::Left);
+        let right = set.add(6, Gravity::Right);
+        let after = set.add(11, Gravity::Left);
+
+        set.insert(&mut r, 6, "big ");
+
+        assert_eq!(r, "Hello big world!");
+        assert_eq!(set.position(before), Some(2));
+        assert_eq!(set.position(left), Some(6));
+        assert_eq!(set.position(right), Some(10));
+        assert_eq!(set.position(after), Some(15));
+        assert_sorted(&set);
+    }
+
+    #[test]
+    fn remove_01() {
+        let mut r = Rope::from_str("Hello big world!");
+        let mut set = MarkerSet::new();
+        let a = set.add(6, Gravity::Right);
+        let b = set.add(8, Gravity::Left);
+        let c = set.add(10, Gravity::Left);
+        let d = set.add(13, Gravity::Right);
+
+        set.remove(&mut r, 6..10);
+
+        assert_eq!(r, "Hello world!");
+        assert_eq!(set.position(a), Some(6));
+        assert_eq!(set.position(b), Some(6));
+        assert_eq!(set.position(c), Some(6));
+        assert_eq!(set.position(d), Some(9));
+        assert_sorted(&set);
+
+        // The collapsed markers keep their gravity.
+        set.insert(&mut r, 6, "-");
+        assert_eq!(set.position(a), Some(7));
+        assert_eq!(set.position(b), Some(6));
+        assert_eq!(set.position(c), Some(6));
+        assert_sorted(&set);
+    }
+
+    #[test]
+    fn split_off_append_01() {
+        let mut r = Rope::from_str("Hello world!");
+        let mut set = MarkerSet::new();
+        let a = set.add(3, Gravity::Left);
+        let b = set.add(6, Gravity::Left);
+        let c = set.add(6, Gravity::Right);
+        let d = set.add(9, Gravity::Right);
+
+        let (r2, set2) = set.split_off(&mut r, 6);
+
+        assert_eq!(r, "Hello ");
+        assert_eq!(r2, "world!");
+        assert_eq!(positions(&set), vec![3, 6]);
+        assert_eq!(set2.position(c), Some(0));
+        assert_eq!(set2.position(d), Some(3));
+        assert!(!set.contains(c));
+        assert_sorted(&set);
+        assert_sorted(&set2);
+
+        set.append(&mut r, r2, set2);
+
+        assert_eq!(r, "Hello world!");
+        assert_eq!(set.position(a), Some(3));
+        assert_eq!(set.position(b), Some(6));
+        assert_eq!(set.position(c), Some(6));
+        assert_eq!(set.position(d), Some(9));
+        assert_sorted(&set);
+    }
+
+    #[test]
+    fn append_02() {
+        // Right-gravity markers at the end of `self` must sort after
+        // left-gravity markers at the start of `other`.
+        let mut r1 = Rope::from_str("abc");
+        let r2 = Rope::from_str("def");
+        let mut set1 = MarkerSet::new();
+        let mut set2 = MarkerSet::new();
+        let a = set1.add(3, Gravity::Right);
+        let b = set2.add(0, Gravity::Left);
+
+        set1.append(&mut r1, r2, set2);
+
+        assert_eq!(set1.position(a), Some(3));
+        assert_eq!(set1.position(b), Some(3));
+        assert_eq!(
+            set1.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            vec![b, a]
+        );
+        assert_sorted(&set1);
+    }
+
+    #[test]
+    fn in_range_01() {
+        let mut set = MarkerSet::new();
+        for i in 0..10 {
+            set.add(i * 2, Gravity::Left);
+        }
+
+        assert_eq!(
+            set.in_range(3..9).map(|(_, i)| i).collect::<Vec<_>>(),
+            vec![4, 6, 8]
+        );
+        assert_eq!(
+            set.in_range(4..=8).map(|(_, i)| i).collect::<Vec<_>>(),
+            vec![4, 6, 8]
+        );
+        assert_eq!(set.in_range(16..).len(), 2);
+        assert_eq!(set.in_range(..).len(), 10);
+        assert_eq!(set.in_range(5..5).len(), 0);
+        #[allow(clippy::reversed_empty_ranges)]
+        let backwards = 7..3;
+        assert_eq!(set.in_range(backwards).len(), 0);
+        assert_eq!(set.in_range(3..9).next_back().map(|(_, i)| i), Some(8));
+    }
+
+    #[test]
+    fn edits_01() {
+        // Compare against plain positions through a pseudo-random sequence
+        // of edits.
+        let mut set = MarkerSet::new();
+        let mut model: Vec<(MarkerId, Gravity, usize)> = Vec::new();
+        let mut len = 100;
+        let mut rng = fastrand::Rng::with_seed(1);
+        for step in 0..1000 {
+            let a = rng.usize(0..(len + 1));
+            let b = (a + rng.usize(0..9)).min(len);
+            match step % 4 {
+                0 => {
+                    let gravity = if rng.bool() {
+                        Gravity::Left
+                    } else {
+                        Gravity::Right
+                    };
+                    model.push((set.add(a, gravity), gravity, a));
+                }
+                1 => {
+                    set.adjust_for_insert(a, b - a);
+                    for m in &mut model {
+                        if m.2 > a || (m.2 == a && m.1 == Gravity::Right) {
+                            m.2 += b - a;
+                        }
+                    }
+                    len += b - a;
+                }
+                2 => {
+                    set.adjust_for_remove(a..b);
+                    for m in &mut model {
+                        m.2 = if m.2 >= b { m.2 - (b - a) } else { m.2.min(a) };
+                    }
+                    len -= b - a;
+                }
+                _ => {
+                    if !model.is_empty() {
+                        let (id, _, char_idx) = model.swap_remove(rng.usize(0..model.len()));
+                        assert_eq!(set.delete(id), Some(char_idx));
+                    }
+                }
+            }
+        }
+        assert_sorted(&set);
+        assert_eq!(set.len(), model.len());
+        for &(id, gravity, char_idx) in &model {
+            assert_eq!(set.position(id), Some(char_idx));
+            assert_eq!(set.gravity(id), Some(gravity));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_02() {
+        let mut r = Rope::from_str("Hello");
+        let mut set = MarkerSet::new();
+        set.insert(&mut r, 6, "!");
+    }
+}
@@ -0,0 +1,405 @@
+//! Searching for many literal patterns at once.
+//!
+//! This runs an Aho-Corasick automaton from the `aho-corasick` crate over a
+//! `Rope`'s chunks a byte at a time, so matches that span chunk boundaries
+//! are found without copying the text:
+//!
+//! ```
+//! # use ropey::Rope;
+//! use ropey::multi_search::{MatchKind, DFA};
+//!
+//! let rope = Rope::from_str("// TODO: fix this.\n// FIXME: and this.");
+//! let markers = DFA::builder()
+//!     .match_kind(MatchKind::LeftmostLongest)
+//!     .build(["TODO", "FIXME", "FIX"])
+//!     .unwrap();
+//!
+//! let found: Vec<_> = rope
+//!     .multi_find_iter(&markers)
+//!     .map(|m| (m.pattern().as_usize(), m.char_range()))
+//!     .collect();
+//! assert_eq!(found, [(0, 3..7), (1, 22..27)]);
+//! ```
+//!
+//! Any of the `aho-corasick` crate's automata can be used: its `DFA` and
+//! NFAs are re-exported here.  The automaton's [`MatchKind`] determines
+//! which matches a non-overlapping search reports, and overlapping searches
+//! require [`MatchKind::Standard`].
+//!
+//! Requires the `multi_search` feature flag.
+
+use std::ops::Range;
+
+use aho_corasick::automaton::StateID;
+use aho_corasick::Anchored;
+
+use crate::iter::Chunks;
+use crate::slice::RopeSlice;
+
+pub use aho_corasick::automaton::Automaton;
+pub use aho_corasick::dfa::DFA;
+pub use aho_corasick::nfa;
+pub use aho_corasick::{MatchKind, PatternID};
+
+/// A match of one of an automaton's patterns in a `Rope` or `RopeSlice`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MultiMatch {
+    pattern: PatternID,
+    byte_range: Range<usize>,
+    char_range: Range<usize>,
+}
+
+impl MultiMatch {
+    fn new<A: Automaton>(
+        text: &RopeSlice,
+        aut: &A,
+        sid: StateID,
+        index: usize,
+        end: usize,
+    ) -> Self {
+        let pattern = aut.match_pattern(sid, index);
+        let start = end - aut.pattern_len(pattern);
+        MultiMatch {
+            pattern: pattern,
+            byte_range: start..end,
+            char_range: text.byte_to_char(start)..text.byte_to_char(end),
+        }
+    }
+
+    /// The ID of the pattern that matched.
+    #[inline]
+    pub fn pattern(&self) -> PatternID {
+        self.pattern
+    }
+
+    /// The byte range of the match.
+    #[inline]
+    pub fn byte_range(&self) -> Range<usize> {
+        self.byte_range.clone()
+    }
+
+    /// The char range of the match.
+    ///
+    /// If a pattern isn't valid utf8, its matches may not fall on char
+    /// boundaries.  Their char range then includes the chars that the
+    /// match only partially covers.
+    #[inline]
+    pub fn char_range(&self) -> Range<usize> {
+        self.char_range.clone()
+    }
+}
+
+//==============================================================
+
+/// An iterator over the non-overlapping matches of an Aho-Corasick
+/// automaton's patterns in a `Rope`.
+///
+/// Which matches are reported is determined by the automaton's
+/// [`MatchKind`].
+#[derive(Debug, Clone)]
+pub struct MultiMatches<'a, 'r, A: Automaton> {
+    text: RopeSlice<'a>,
+    aut: &'r A,
+    stream: ByteStream<'a>,
+    last_end: Option<usize>,
+}
+
+impl<'a, 'r, A: Automaton> MultiMatches<'a, 'r, A> {
+    pub(crate) fn new(slice: &RopeSlice<'a>, aut: &'r A) -> MultiMatches<'a, 'r, A> {
+        MultiMatches {
+            text: *slice,
+            aut: aut,
+            stream: ByteStream::new(slice, 0),
+            last_end: None,
+        }
+    }
+
+    /// Finds the next match, starting from the current stream position.
+    fn find(&mut self) -> Option<MultiMatch> {
+        let aut = self.aut;
+        let earliest = aut.match_kind() == MatchKind::Standard;
+        let mut sid = start_state(aut);
+        let mut mat = None;
+        if aut.is_match(sid) {
+            mat = Some((sid, self.stream.byte_idx));
+        }
+
+        if mat.is_none() || !earliest {
+            while let Some(byte) = self.stream.next() {
+                sid = aut.next_state(Anchored::No, sid, byte);
+                if aut.is_special(sid) {
+                    if aut.is_dead(sid) {
+                        break;
+                    } else if aut.is_match(sid) {
+                        mat = Some((sid, self.stream.byte_idx));
+                        if earliest {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let (sid, end) = mat?;
+        // Leftmost searches may have looked past the end of the match.
+        if self.stream.byte_idx != end {
+            self.stream = ByteStream::new(&self.text, end);
+        }
+        Some(MultiMatch::new(&self.text, aut, sid, 0, end))
+    }
+}
+
+impl<'a, 'r, A: Automaton> Iterator for MultiMatches<'a, 'r, A> {
+    type Item = MultiMatch;
+
+    /// Advances the iterator forward and returns the next match.
+    ///
+    /// Runs in time linear in the length of the text searched to find the
+    /// match.
+    fn next(&mut self) -> Option<MultiMatch> {
+        loop {
+            let m = self.find()?;
+            if m.byte_range.is_empty() && Some(m.byte_range.end) == self.last_end {
+                // Don't report an empty match right where the previous match
+                // ended, but move on to the next char and try again.
+                let char_idx = self.text.byte_to_char(m.byte_range.end);
+                if char_idx == self.text.len_chars() {
+                    self.stream = ByteStream::new(&self.text, self.text.len_bytes());
+                    return None;
+                }
+                self.stream = ByteStream::new(&self.text, self.text.char_to_byte(char_idx + 1));
+                continue;
+            }
+            self.last_end = Some(m.byte_range.end);
+            return Some(m);
+        }
+    }
+}
+
+//==============================================================
+
+/// An iterator over all matches of an Aho-Corasick automaton's patterns in a
+/// `Rope`, including overlapping ones.
+///
+/// Matches are reported in order of their end position.
+#[derive(Debug, Clone)]
+pub struct OverlappingMultiMatches<'a, 'r, A: Automaton> {
+    text: RopeSlice<'a>,
+    aut: &'r A,
+    stream: ByteStream<'a>,
+    sid: StateID,
+    /// The index of the next match to report from the current state.
+    match_idx: usize,
+}
+
+impl<'a, 'r, A: Automaton> OverlappingMultiMatches<'a, 'r, A> {
+    pub(crate) fn new(slice: &RopeSlice<'a>, aut: &'r A) -> OverlappingMultiMatches<'a, 'r, A> {
+        assert!(
+            aut.match_kind() == MatchKind::Standard,
+            "Overlapping searches require an automaton with MatchKind::Standard."
+        );
+        OverlappingMultiMatches {
+            text: *slice,
+            aut: aut,
+            stream: ByteStream::new(slice, 0),
+            sid: start_state(aut),
+            match_idx: 0,
+        }
+    }
+}
+
+impl<'a, 'r, A: Automaton> Iterator for OverlappingMultiMatches<'a, 'r, A> {
+    type Item = MultiMatch;
+
+    /// Advances the iterator forward and returns the next match.
+    ///
+    /// Runs in time linear in the length of the text searched to find the
+    /// match.
+    fn next(&mut self) -> Option<MultiMatch> {
+        let aut = self.aut;
+        loop {
+            if aut.is_match(self.sid) && self.match_idx < aut.match_len(self.sid) {
+                self.match_idx += 1;
+                return Some(MultiMatch::new(
+                    &self.text,
+                    aut,
+                    self.sid,
+                    self.match_idx - 1,
+                    self.stream.byte_idx,
+                ));
+            }
+
+            let byte = self.stream.next()?;
+            self.sid = aut.next_state(Anchored::No, self.sid, byte);
+            self.match_idx = 0;
+        }
+    }
+}
+
+//==============================================================
+
+/// Streams the bytes of a `RopeSlice`, a chunk at a time.
+#[derive(Debug, Clone)]
+struct ByteStream<'a> {
+    chunks: Chunks<'a>,
+    chunk: &'a [u8],
+    chunk_idx: usize, // Position within `chunk`.
+    byte_idx: usize,  // Position within the slice.
+}
+
+impl<'a> ByteStream<'a> {
+    fn new(slice: &RopeSlice<'a>, byte_idx: usize) -> ByteStream<'a> {
+        let (mut chunks, chunk_byte_idx, _, _) = slice.chunks_at_byte(byte_idx);
+        let (chunk, chunk_idx) = match chunks.next() {
+            Some(chunk) => (chunk.as_bytes(), byte_idx - chunk_byte_idx),
+            None => (&[][..], 0),
+        };
+        ByteStream {
+            chunks: chunks,
+            chunk: chunk,
+            chunk_idx: chunk_idx,
+            byte_idx: byte_idx,
+        }
+    }
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<u8> {
+        while self.chunk_idx >= self.chunk.len() {
+            self.chunk = self.chunks.next()?.as_bytes();
+            self.chunk_idx = 0;
+        }
+        let byte = self.chunk[self.chunk_idx];
+        self.chunk_idx += 1;
+        self.byte_idx += 1;
+        Some(byte)
+    }
+}
+
+fn start_state<A: Automaton>(aut: &A) -> StateID {
+    // Unanchored searches are supported by all of aho-corasick's automata
+    // unless they're explicitly built otherwise.
+    aut.start_state(Anchored::No)
+        .expect("automaton doesn't support unanchored searches")
+}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rope;
+    use aho_corasick::AhoCorasick;
+
+    // 124 bytes, 100 chars, 4 lines
+    const TEXT: &str = "Hello there!  How're you doing?\nIt's \
+                        a fine day, isn't it?\nAren't you glad \
+                        we're alive?\nこんにちは、みんなさん！";
+
+    const PATTERNS: &[&str] = &[
+        "e",
+        "he",
+        "there",
+        "the",
+        "you",
+        "?\nA",
+        "んな",
+        "みんなさ",
+        "n't",
+    ];
+
+    fn expected(kind: MatchKind, overlapping: bool) -> Vec<(usize, Range<usize>)> {
+        let ac = AhoCorasick::builder()
+            .match_kind(kind)
+            .build(PATTERNS)
+            .unwrap();
+        let matches: Vec<_> = if overlapping {
+            ac.find_overlapping_iter(TEXT).collect()
+        } else {
+            ac.find_iter(TEXT).collect()
+        };
+        matches
+            .into_iter()
+            .map(|m| (m.pattern().as_usize(), m.range()))
+            .collect()
+    }
+
+    fn found<I: Iterator<Item = MultiMatch>>(matches: I) -> Vec<(usize, Range<usize>)> {
+        matches
+            .map(|m| (m.pattern().as_usize(), m.byte_range()))
+            .collect()
+    }
+
+    #[test]
+    fn multi_find_iter_01() {
+        let r = Rope::from_str(TEXT);
+        for &kind in &[
+            MatchKind::Standard,
+            MatchKind::LeftmostFirst,
+            MatchKind::LeftmostLongest,
+        ] {
+            let dfa = DFA::builder().match_kind(kind).build(PATTERNS).unwrap();
+            let nfa = nfa::noncontiguous::NFA::builder()
+                .match_kind(kind)
+                .build(PATTERNS)
+                .unwrap();
+            let expected = expected(kind, false);
+            assert!(expected.len() > 10);
+            assert_eq!(found(r.multi_find_iter(&dfa)), expected, "{:?}", kind);
+            assert_eq!(found(r.multi_find_iter(&nfa)), expected, "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn multi_find_iter_02() {
+        let r = Rope::from_str(TEXT);
+        let dfa = DFA::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(PATTERNS)
+            .unwrap();
+
+        let matches: Vec<_> = r
+            .slice(2..30)
+            .multi_find_iter(&dfa)
+            .map(|m| m.char_range())
+            .collect();
+        assert_eq!(matches, [4..9, 17..18, 19..22]);
+
+        let m = r.multi_find_iter(&dfa).last().unwrap();
+        assert_eq!(m.pattern().as_usize(), 7);
+        assert_eq!(m.byte_range(), 106..118);
+        assert_eq!(m.char_range(), 94..98);
+    }
+
+    #[test]
+    fn multi_find_iter_03() {
+        // Empty patterns.
+        let r = Rope::from_str("aみb");
+        let dfa = DFA::builder()
+            .match_kind(MatchKind::LeftmostFirst)
+            .build(["b", ""])
+            .unwrap();
+
+        let matches: Vec<_> = r.multi_find_iter(&dfa).map(|m| m.char_range()).collect();
+        assert_eq!(matches, [0..0, 1..1, 2..3]);
+    }
+
+    #[test]
+    fn multi_find_overlapping_iter_01() {
+        let r = Rope::from_str(TEXT);
+        let dfa = DFA::new(PATTERNS).unwrap();
+
+        let expected = expected(MatchKind::Standard, true);
+        assert_eq!(found(r.multi_find_overlapping_iter(&dfa)), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn multi_find_overlapping_iter_02() {
+        let r = Rope::from_str(TEXT);
+        let dfa = DFA::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(PATTERNS)
+            .unwrap();
+        r.multi_find_overlapping_iter(&dfa);
+    }
+}
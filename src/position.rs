@@ -0,0 +1,48 @@
+use std::fmt;
+
+/// A line/column position in a `Rope` or `RopeSlice`.
+///
+/// Both `line` and `col` are zero-indexed.  What `col` counts depends on the
+/// [`PositionEncoding`] the position is used with.
+///
+/// Created by [`Rope::char_to_line_col()`](crate::Rope::char_to_line_col)
+/// and consumed by [`Rope::line_col_to_char()`](crate::Rope::line_col_to_char),
+/// and their `RopeSlice` equivalents.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    /// The line index.
+    pub line: usize,
+    /// The column within the line, in units of the position encoding.
+    pub col: usize,
+}
+
+impl LineCol {
+    /// Creates a new `LineCol`.
+    #[inline]
+    pub fn new(line: usize, col: usize) -> LineCol {
+        LineCol {
+            line: line,
+            col: col,
+        }
+    }
+}
+
+impl fmt::Display for LineCol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+/// The units that the column of a [`LineCol`] is counted in.
+///
+/// These correspond to the position encodings of the Language Server
+/// Protocol.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PositionEncoding {
+    /// Columns count utf8 bytes.
+    Utf8,
+    /// Columns count utf16 code units.
+    Utf16,
+    /// Columns count chars (Unicode scalar values).
+    Utf32,
+}
@@ -0,0 +1,230 @@
+use std::io;
+
+use crate::iter::Chunks;
+use crate::slice::RopeSlice;
+
+/// A reader over the contents of a `Rope` or `RopeSlice`.
+///
+/// Implements [`io::Read`], [`io::BufRead`], and [`io::Seek`], so that a
+/// rope's text can be handed to anything that consumes a reader (hashers,
+/// compressors, a child process's stdin, etc.) without first copying it
+/// into a contiguous buffer.
+///
+/// `fill_buf()` returns the rest of the current chunk directly, so reading
+/// through `BufRead` involves no copying at all.  Seeking is by byte offset
+/// from the start of the text, and takes O(log N) time.
+///
+/// Created with [`Rope::reader()`](crate::Rope::reader) or
+/// [`RopeSlice::reader()`].
+///
+/// # Example
+/// ```
+/// # use std::io::{BufRead, Read, Seek, SeekFrom};
+/// # use ropey::Rope;
+/// #
+/// let rope = Rope::from_str("Hello world!\nHow's it going?");
+/// let mut reader = rope.reader();
+///
+/// let mut line = String::new();
+/// reader.read_line(&mut line).unwrap();
+/// assert_eq!(line, "Hello world!\n");
+///
+/// reader.seek(SeekFrom::Start(6)).unwrap();
+/// let mut rest = String::new();
+/// reader.read_to_string(&mut rest).unwrap();
+/// assert_eq!(rest, "world!\nHow's it going?");
+/// ```
+#[derive(Debug, Clone)]
+pub struct RopeReader<'a> {
+    slice: RopeSlice<'a>,
+    chunks: Chunks<'a>, // Positioned just after `chunk`.
+    chunk: &'a [u8],
+    chunk_byte_idx: usize, // Byte index of the start of `chunk`.
+    byte_idx: usize,       // Current read position.
+}
+
+impl<'a> RopeReader<'a> {
+    pub(crate) fn new(slice: RopeSlice<'a>) -> RopeReader<'a> {
+        let mut chunks = slice.chunks();
+        let chunk = chunks.next().unwrap_or("").as_bytes();
+        RopeReader {
+            slice,
+            chunks,
+            chunk,
+            chunk_byte_idx: 0,
+            byte_idx: 0,
+        }
+    }
+
+    /// Moves the read position to `byte_idx`, which may be past the end of
+    /// the text.
+    fn set_position(&mut self, byte_idx: usize) {
+        let chunk_end = self.chunk_byte_idx + self.chunk.len();
+        if byte_idx < self.chunk_byte_idx || byte_idx >= chunk_end {
+            // Not in the current chunk, so find the chunk it's in.  Positions
+            // at or past the end just leave an empty chunk.
+            let len = self.slice.len_bytes();
+            let (mut chunks, chunk_byte_idx, _, _) = self.slice.chunks_at_byte(byte_idx.min(len));
+            self.chunk = chunks.next().unwrap_or("").as_bytes();
+            self.chunks = chunks;
+            self.chunk_byte_idx = chunk_byte_idx;
+        }
+        self.byte_idx = byte_idx;
+    }
+}
+
+impl<'a> io::Read for RopeReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Fill as much of `buf` as possible, rather than stopping at the end
+        // of the current chunk.
+        let mut n = 0;
+        while n < buf.len() {
+            let available = io::BufRead::fill_buf(self)?;
+            if available.is_empty() {
+                break;
+            }
+            let amt = available.len().min(buf.len() - n);
+            buf[n..(n + amt)].copy_from_slice(&available[..amt]);
+            io::BufRead::consume(self, amt);
+            n += amt;
+        }
+        Ok(n)
+    }
+}
+
+impl<'a> io::BufRead for RopeReader<'a> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let chunk_end = self.chunk_byte_idx + self.chunk.len();
+        if self.byte_idx == chunk_end {
+            if let Some(chunk) = self.chunks.next() {
+                self.chunk = chunk.as_bytes();
+                self.chunk_byte_idx = chunk_end;
+            }
+        }
+        if self.byte_idx >= self.chunk_byte_idx + self.chunk.len() {
+            return Ok(&[]);
+        }
+        Ok(&self.chunk[(self.byte_idx - self.chunk_byte_idx)..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.byte_idx = (self.byte_idx + amt).min(self.chunk_byte_idx + self.chunk.len());
+    }
+}
+
+impl<'a> io::Seek for RopeReader<'a> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            io::SeekFrom::Start(n) => (n, 0),
+            io::SeekFrom::End(n) => (self.slice.len_bytes() as u64, n),
+            io::SeekFrom::Current(n) => (self.byte_idx as u64, n),
+        };
+        let new_pos = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(offset.wrapping_neg() as u64)
+        };
+
+        match new_pos {
+            Some(n) if n <= usize::MAX as u64 => {
+                self.set_position(n as usize);
+                Ok(n)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::Rope;
+    use std::io::{BufRead, Read, Seek, SeekFrom};
+
+    // 127 bytes, 103 chars, 1 line
+    const TEXT: &str = "Hello there!  How're you doing?  It's \
+                        a fine day, isn't it?  Aren't you glad \
+                        we're alive?  こんにちは、みんなさん！";
+
+    #[test]
+    fn read_01() {
+        let r = Rope::from_str(TEXT);
+        let mut reader = r.reader();
+        let mut buf = [0u8; 10];
+        assert_eq!(reader.read(&mut buf).unwrap(), 10);
+        assert_eq!(&buf, b"Hello ther");
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, &TEXT.as_bytes()[10..]);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_02() {
+        let r = Rope::from_str(TEXT);
+        let s = r.slice(6..31);
+        let mut text = String::new();
+        s.reader().read_to_string(&mut text).unwrap();
+        assert_eq!(text, "there!  How're you doing?");
+
+        let mut text = String::new();
+        Rope::new().reader().read_to_string(&mut text).unwrap();
+        assert_eq!(text, "");
+    }
+
+    #[test]
+    fn fill_buf_01() {
+        // `fill_buf()` returns the chunks themselves, without copying.
+        let r = Rope::from_str(TEXT);
+        let mut reader = r.reader();
+        for chunk in r.chunks() {
+            let buf = reader.fill_buf().unwrap();
+            assert_eq!(buf.as_ptr(), chunk.as_ptr());
+            assert_eq!(buf.len(), chunk.len());
+            let amt = buf.len();
+            reader.consume(amt);
+        }
+        assert!(reader.fill_buf().unwrap().is_empty());
+    }
+
+    #[test]
+    fn seek_01() {
+        let r = Rope::from_str(TEXT);
+        let mut reader = r.reader();
+        let mut buf = [0u8; 5];
+
+        assert_eq!(reader.seek(SeekFrom::Start(14)).unwrap(), 14);
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"How'r");
+
+        assert_eq!(reader.seek(SeekFrom::Current(-10)).unwrap(), 9);
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"re!  ");
+
+        assert_eq!(reader.seek(SeekFrom::End(-3)).unwrap(), 124);
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "！");
+    }
+
+    #[test]
+    fn seek_02() {
+        let r = Rope::from_str(TEXT);
+        let mut reader = r.reader();
+
+        // Seeking past the end is fine, and reads nothing.
+        assert_eq!(reader.seek(SeekFrom::End(10)).unwrap(), 137);
+        assert_eq!(reader.read(&mut [0u8; 5]).unwrap(), 0);
+        assert_eq!(reader.seek(SeekFrom::Current(-132)).unwrap(), 5);
+        assert_eq!(reader.fill_buf().unwrap()[0], b' ');
+
+        // Seeking before the start is an error, and doesn't move.
+        assert!(reader.seek(SeekFrom::Current(-6)).is_err());
+        assert_eq!(reader.stream_position().unwrap(), 5);
+    }
+}
@@ -0,0 +1,486 @@
+//! Regex searching over a `Rope`'s text.
+//!
+//! This runs the lazy DFAs of a [`Regex`] directly over a `Rope`'s chunks,
+//! so that searches never need to copy the text into a contiguous `String`:
+//!
+//! ```
+//! # use ropey::Rope;
+//! use ropey::regex::Regex;
+//!
+//! let rope = Rope::from_str("Hello there!  How're you doing?");
+//! let regex = Regex::new(r"\w+'?\w*").unwrap();
+//!
+//! let words: Vec<_> = rope
+//!     .regex_find_iter(&regex)
+//!     .map(|m| rope.slice(m.char_range()).to_string())
+//!     .collect();
+//! assert_eq!(words, ["Hello", "there", "How're", "you", "doing"]);
+//! ```
+//!
+//! [`Regex`] is re-exported from the `regex-automata` crate, and can be
+//! configured with its builder.  Searches panic if the regex's lazy DFAs
+//! give up, which with the default configuration never happens.
+//!
+//! Requires the `regex` feature flag.
+
+use std::ops::Range;
+
+use regex_automata::hybrid::dfa::{Cache as DfaCache, DFA};
+use regex_automata::hybrid::LazyStateID;
+use regex_automata::util::start;
+use regex_automata::Anchored;
+
+use crate::slice::RopeSlice;
+
+pub use regex_automata::hybrid::regex::Regex;
+
+/// A match of a regex in a `Rope` or `RopeSlice`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RegexMatch {
+    byte_range: Range<usize>,
+    char_range: Range<usize>,
+}
+
+impl RegexMatch {
+    /// The byte range of the match.
+    #[inline]
+    pub fn byte_range(&self) -> Range<usize> {
+        self.byte_range.clone()
+    }
+
+    /// The char range of the match.
+    #[inline]
+    pub fn char_range(&self) -> Range<usize> {
+        self.char_range.clone()
+    }
+
+    /// Whether the match is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.byte_range.is_empty()
+    }
+}
+
+//==============================================================
+
+/// An iterator over the non-overlapping matches of a regex in a `Rope`.
+///
+/// Depending on how the iterator was created, it either searches forward
+/// from the start of the text or backward from the end.  See
+/// [`Rope::regex_rfind_iter()`](crate::Rope::regex_rfind_iter) for how
+/// backward searches pick their matches.
+///
+/// Unlike Ropey's other iterators, this is a one-way iterator: it can't be
+/// reversed or moved backward.
+#[derive(Debug)]
+pub struct RegexMatches<'a, 'r> {
+    searcher: Searcher<'a, 'r>,
+    /// Where the next search starts (forward) or ends (backward), in bytes.
+    byte_idx: usize,
+    /// The end (forward) or start (backward) of the previous match.
+    last: Option<usize>,
+    is_reversed: bool,
+    done: bool,
+}
+
+impl<'a, 'r> RegexMatches<'a, 'r> {
+    pub(crate) fn new(
+        slice: &RopeSlice<'a>,
+        regex: &'r Regex,
+        byte_idx: usize,
+        reverse: bool,
+    ) -> RegexMatches<'a, 'r> {
+        RegexMatches {
+            searcher: Searcher::new(slice, regex),
+            byte_idx: byte_idx,
+            last: None,
+            is_reversed: reverse,
+            done: false,
+        }
+    }
+
+    fn next_impl(&mut self) -> Option<Range<usize>> {
+        loop {
+            let m = self.searcher.find_fwd(self.byte_idx)?;
+            if m.is_empty() && Some(m.end) == self.last {
+                // Don't report an empty match right where the previous
+                // match ended.
+                if m.end == self.searcher.text.len_bytes() {
+                    return None;
+                }
+                self.byte_idx = m.end + 1;
+                continue;
+            }
+            self.byte_idx = m.end;
+            self.last = Some(m.end);
+            return Some(m);
+        }
+    }
+
+    fn prev_impl(&mut self) -> Option<Range<usize>> {
+        loop {
+            let m = self.searcher.find_rev(self.byte_idx)?;
+            if m.is_empty() && Some(m.start) == self.last {
+                if m.start == 0 {
+                    return None;
+                }
+                self.byte_idx = m.start - 1;
+                continue;
+            }
+            self.byte_idx = m.start;
+            self.last = Some(m.start);
+            return Some(m);
+        }
+    }
+}
+
+impl<'a, 'r> Iterator for RegexMatches<'a, 'r> {
+    type Item = RegexMatch;
+
+    /// Advances the iterator and returns the next match.
+    ///
+    /// Runs in time linear in the length of the text searched to find the
+    /// match.
+    fn next(&mut self) -> Option<RegexMatch> {
+        if self.done {
+            return None;
+        }
+        let m = if !self.is_reversed {
+            self.next_impl()
+        } else {
+            self.prev_impl()
+        };
+        match m {
+            Some(byte_range) => Some(self.searcher.to_match(byte_range)),
+            None => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+//==============================================================
+
+/// Runs a `Regex`'s lazy DFAs over the text of a `RopeSlice`.
+///
+/// All indices are byte indices.
+#[derive(Debug)]
+pub(crate) struct Searcher<'a, 'r> {
+    text: RopeSlice<'a>,
+    regex: &'r Regex,
+    fwd_cache: DfaCache,
+    rev_cache: DfaCache,
+}
+
+impl<'a, 'r> Searcher<'a, 'r> {
+    pub(crate) fn new(slice: &RopeSlice<'a>, regex: &'r Regex) -> Searcher<'a, 'r> {
+        Searcher {
+            text: *slice,
+            regex: regex,
+            fwd_cache: regex.forward().create_cache(),
+            rev_cache: regex.reverse().create_cache(),
+        }
+    }
+
+    pub(crate) fn to_match(&self, byte_range: Range<usize>) -> RegexMatch {
+        let start = self.text.byte_to_char(byte_range.start);
+        let end = start + self.text.byte_slice(byte_range.clone()).len_chars();
+        RegexMatch {
+            byte_range: byte_range,
+            char_range: start..end,
+        }
+    }
+
+    /// Finds the leftmost-first match that starts at or after `from`.
+    pub(crate) fn find_fwd(&mut self, mut from: usize) -> Option<Range<usize>> {
+        let len = self.text.len_bytes();
+        loop {
+            let end = self.scan_fwd(from, len, Anchored::No)?;
+            let start = self.scan_rev(end, from, Anchored::Yes, false).unwrap();
+            if start == end && !self.is_char_boundary(end) {
+                // Empty matches can't split chars.
+                from = end + 1;
+                continue;
+            }
+            return Some(start..end);
+        }
+    }
+
+    /// Finds the match that ends at or before `to`, searching backward.
+    ///
+    /// This finds the rightmost position where a match starts, takes the
+    /// leftmost-first match from there, and then extends that match as far
+    /// backward as the regex allows while keeping its end.
+    pub(crate) fn find_rev(&mut self, mut to: usize) -> Option<Range<usize>> {
+        loop {
+            let candidate = self.scan_rev(to, 0, Anchored::No, true)?;
+            let end = self.scan_fwd(candidate, to, Anchored::Yes).unwrap();
+            let start = self.scan_rev(end, 0, Anchored::Yes, false).unwrap();
+            if start == end && !self.is_char_boundary(start) {
+                to = start - 1;
+                continue;
+            }
+            return Some(start..end);
+        }
+    }
+
+    fn is_char_boundary(&self, byte_idx: usize) -> bool {
+        byte_idx == self.text.len_bytes() || (self.text.byte(byte_idx) & 0xC0) != 0x80
+    }
+
+    /// Runs the forward DFA over `from..to`, and returns the end of the last
+    /// match it reports before dying.
+    fn scan_fwd(&mut self, from: usize, to: usize, anchored: Anchored) -> Option<usize> {
+        let dfa = self.regex.forward();
+        let cache = &mut self.fwd_cache;
+        let look_behind = if from > 0 {
+            Some(self.text.byte(from - 1))
+        } else {
+            None
+        };
+        let config = start::Config::new()
+            .anchored(anchored)
+            .look_behind(look_behind);
+        let mut sid = dfa
+            .start_state(cache, &config)
+            .expect("regex search gave up");
+
+        let mut mat = None;
+        let mut byte_idx = from;
+        while byte_idx < to {
+            let (chunk, chunk_byte_idx, _, _) = self.text.chunk_at_byte(byte_idx);
+            let chunk_end = (chunk_byte_idx + chunk.len()).min(to);
+            for &byte in
+                &chunk.as_bytes()[(byte_idx - chunk_byte_idx)..(chunk_end - chunk_byte_idx)]
+            {
+                sid = next_state(dfa, cache, sid, Some(byte));
+                // Matches are reported one byte late.
+                if sid.is_match() {
+                    mat = Some(byte_idx);
+                } else if sid.is_dead() {
+                    return mat;
+                }
+                byte_idx += 1;
+            }
+        }
+
+        let look_ahead = if to < self.text.len_bytes() {
+            Some(self.text.byte(to))
+        } else {
+            None
+        };
+        if next_state(dfa, cache, sid, look_ahead).is_match() {
+            mat = Some(to);
+        }
+        mat
+    }
+
+    /// Runs the reverse DFA backward over `to..from`, and returns the start
+    /// of the first match it reports if `earliest` is true, or otherwise
+    /// of the last match it reports before dying.
+    fn scan_rev(
+        &mut self,
+        from: usize,
+        to: usize,
+        anchored: Anchored,
+        earliest: bool,
+    ) -> Option<usize> {
+        let dfa = self.regex.reverse();
+        let cache = &mut self.rev_cache;
+        let look_behind = if from < self.text.len_bytes() {
+            Some(self.text.byte(from))
+        } else {
+            None
+        };
+        let config = start::Config::new()
+            .anchored(anchored)
+            .look_behind(look_behind);
+        let mut sid = dfa
+            .start_state(cache, &config)
+            .expect("regex search gave up");
+
+        let mut mat = None;
+        let mut byte_idx = from;
+        while byte_idx > to {
+            let (chunk, chunk_byte_idx, _, _) = self.text.chunk_at_byte(byte_idx - 1);
+            let chunk_start = chunk_byte_idx.max(to);
+            for &byte in chunk.as_bytes()
+                [(chunk_start - chunk_byte_idx)..(byte_idx - chunk_byte_idx)]
+                .iter()
+                .rev()
+            {
+                sid = next_state(dfa, cache, sid, Some(byte));
+                // Matches are reported one byte late.
+                if sid.is_match() {
+                    mat = Some(byte_idx);
+                    if earliest {
+                        return mat;
+                    }
+                } else if sid.is_dead() {
+                    return mat;
+                }
+                byte_idx -= 1;
+            }
+        }
+
+        let look_ahead = if to > 0 {
+            Some(self.text.byte(to - 1))
+        } else {
+            None
+        };
+        if next_state(dfa, cache, sid, look_ahead).is_match() {
+            mat = Some(to);
+        }
+        mat
+    }
+}
+
+/// Transitions on `byte`, or on the end of input if it's `None`.
+#[inline(always)]
+fn next_state(dfa: &DFA, cache: &mut DfaCache, sid: LazyStateID, byte: Option<u8>) -> LazyStateID {
+    let sid = match byte {
+        Some(byte) => dfa.next_state(cache, sid, byte),
+        None => dfa.next_eoi_state(cache, sid),
+    }
+    .expect("regex search gave up");
+    assert!(!sid.is_quit(), "regex search gave up");
+    sid
+}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rope;
+
+    // 124 bytes, 100 chars, 4 lines
+    const TEXT: &str = "Hello there!  How're you doing?\nIt's \
+                        a fine day, isn't it?\nAren't you glad \
+                        we're alive?\nこんにちは、みんなさん！";
+
+    fn expected_matches(text: &str, regex: &Regex) -> Vec<(Range<usize>, Range<usize>)> {
+        let mut cache = regex.create_cache();
+        regex
+            .find_iter(&mut cache, text)
+            .map(|m| {
+                let start = text[..m.start()].chars().count();
+                let end = start + text[m.range()].chars().count();
+                (m.range(), start..end)
+            })
+            .collect()
+    }
+
+    fn ranges<I>(matches: I) -> Vec<(Range<usize>, Range<usize>)>
+    where
+        I: Iterator<Item = RegexMatch>,
+    {
+        matches.map(|m| (m.byte_range(), m.char_range())).collect()
+    }
+
+    #[test]
+    fn regex_find_iter_01() {
+        let r = Rope::from_str(TEXT);
+        for pattern in &[
+            r"\w+",
+            r"[aeiou]",
+            r"(?m)^\w+",
+            r"(?m)\w+$",
+            r"(?-u:\b)i\w*",
+            r"you|y",
+            r"\?\n",
+            r"みんな|ん",
+            r"\p{Hiragana}+",
+            r"x*",
+        ] {
+            let regex = Regex::new(pattern).unwrap();
+            let expected = expected_matches(TEXT, &regex);
+            assert_eq!(ranges(r.regex_find_iter(&regex)), expected, "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn regex_find_iter_02() {
+        let r = Rope::from_str(TEXT);
+        let s = r.slice(13..74);
+        let text = &TEXT[13..74];
+        for pattern in &[r"\w+", r"(?m)^\w+", r"(?-u:\b)\w", r"\w(?-u:\b)", r"x*"] {
+            let regex = Regex::new(pattern).unwrap();
+            let expected = expected_matches(text, &regex);
+            assert_eq!(ranges(s.regex_find_iter(&regex)), expected, "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn regex_rfind_iter_01() {
+        let r = Rope::from_str("hello world, it's a fine day");
+        let regex = Regex::new(r"\w+").unwrap();
+
+        let words: Vec<_> = r
+            .regex_rfind_iter(&regex)
+            .map(|m| r.slice(m.char_range()).to_string())
+            .collect();
+        assert_eq!(words, ["day", "fine", "a", "s", "it", "world", "hello"]);
+    }
+
+    #[test]
+    fn regex_rfind_iter_02() {
+        let r = Rope::from_str("baaa");
+        let regex = Regex::new(r"a*").unwrap();
+
+        let matches: Vec<_> = r.regex_rfind_iter(&regex).map(|m| m.char_range()).collect();
+        assert_eq!(matches, [1..4, 0..0]);
+    }
+
+    #[test]
+    fn regex_rfind_iter_03() {
+        let r = Rope::from_str(TEXT);
+        let regex = Regex::new(r"\p{Hiragana}+|\w+").unwrap();
+
+        let mut expected: Vec<_> = expected_matches(TEXT, &regex);
+        expected.reverse();
+        assert_eq!(ranges(r.regex_rfind_iter(&regex)), expected);
+    }
+
+    #[test]
+    fn regex_find_01() {
+        let r = Rope::from_str(TEXT);
+        let regex = Regex::new(r"you").unwrap();
+
+        assert_eq!(r.regex_find(&regex).unwrap().char_range(), 21..24);
+        assert_eq!(r.regex_rfind(&regex).unwrap().char_range(), 66..69);
+        assert_eq!(r.regex_find_from(22, &regex).unwrap().char_range(), 66..69);
+        assert_eq!(r.regex_rfind_from(68, &regex).unwrap().char_range(), 21..24);
+        assert_eq!(r.regex_find_from(67, &regex), None);
+        assert_eq!(r.regex_rfind_from(23, &regex), None);
+
+        let m = r.regex_find(&Regex::new(r"みんな").unwrap()).unwrap();
+        assert_eq!(m.char_range(), 94..97);
+        assert_eq!(m.byte_range(), 106..115);
+    }
+
+    #[test]
+    fn regex_find_02() {
+        // Look-behind context comes from before the search start.
+        let r = Rope::from_str("abc abc");
+        let regex = Regex::new(r"(?-u:\b)bc").unwrap();
+
+        assert_eq!(r.regex_find_from(1, &regex), None);
+        assert_eq!(r.regex_rfind_from(3, &regex), None);
+        // But a slice is treated as the whole text.
+        assert_eq!(r.slice(1..).regex_find(&regex).unwrap().char_range(), 0..2);
+        assert!(r.try_regex_find_from(8, &regex).is_err());
+        assert!(r.try_regex_rfind_from(8, &regex).is_err());
+    }
+
+    #[test]
+    fn regex_find_03() {
+        // Empty matches don't split chars.
+        let r = Rope::from_str("aみb");
+        let regex = Regex::new(r"(?-u:\b)|").unwrap();
+
+        let matches: Vec<_> = r.regex_find_iter(&regex).map(|m| m.char_range()).collect();
+        assert_eq!(matches, [0..0, 1..1, 2..2, 3..3]);
+    }
+}
@@ -1,17 +1,32 @@
 use std::io;
 use std::iter::FromIterator;
-use std::ops::RangeBounds;
+use std::ops::{Range, RangeBounds};
 use std::sync::Arc;
 
 use crate::crlf;
-use crate::iter::{Bytes, Chars, Chunks, Lines};
-use crate::rope_builder::RopeBuilder;
+#[cfg(feature = "graphemes")]
+use crate::iter::Graphemes;
+use crate::iter::{
+    Bytes, CharIndices, Chars, ChunkIndices, Chunks, LineInfos, Lines, LinesWithoutEndings,
+    Matches, MatchesIgnoreCase, Pattern, Split,
+};
+use crate::line_ending::{convert_line_endings, LineEnding, LineEndingStats};
+#[cfg(feature = "multi_search")]
+use crate::multi_search::{Automaton, MultiMatches, OverlappingMultiMatches};
+use crate::position::{LineCol, PositionEncoding};
+use crate::reader::RopeReader;
+#[cfg(feature = "regex")]
+use crate::regex::{Regex, RegexMatch, RegexMatches};
+use crate::rope_builder::{LeafSplicer, RopeBuilder, Utf8Replacement};
 use crate::slice::RopeSlice;
 use crate::str_utils::{
     byte_to_char_idx, byte_to_line_idx, byte_to_utf16_surrogate_idx, char_to_byte_idx,
     char_to_line_idx, line_to_byte_idx, line_to_char_idx, utf16_code_unit_to_char_idx,
 };
-use crate::tree::{Count, Node, NodeChildren, TextInfo, MAX_BYTES, MIN_BYTES};
+use crate::tree::{
+    Count, EditMetric, Node, NodeChildren, NotCharBoundary, RangeEdit, TextInfo, MAX_BYTES,
+    MIN_BYTES,
+};
 use crate::{end_bound_to_num, start_bound_to_num, Error, Result};
 
 /// A utf8 text rope.
@@ -191,6 +206,64 @@ impl Rope {
         }
     }
 
+    /// Creates a `Rope` from the output of a reader, replacing invalid utf8
+    /// with U+FFFD (the replacement character).
+    ///
+    /// This is the same as [`from_reader()`](Rope::from_reader), except
+    /// that invalid utf8 isn't an error.  Each maximal invalid sequence is
+    /// replaced with a single replacement character, as with
+    /// `String::from_utf8_lossy()`, even when it is split across reads.
+    ///
+    /// Also returns the replacements that were made, in order.  Their
+    /// input byte ranges are byte offsets in the reader's output.
+    ///
+    /// Runs in O(N) time.
+    ///
+    /// # Errors
+    ///
+    /// If the reader returns an error, `from_reader_lossy` stops and
+    /// returns that error.
+    ///
+    /// Note: some data from the reader is likely consumed even if there is
+    /// an error.
+    ///
+    /// # Example
+    /// ```
+    /// # use ropey::Rope;
+    /// let data: &[u8] = b"Hello\xC0 world!";
+    /// let (rope, replacements) = Rope::from_reader_lossy(data).unwrap();
+    ///
+    /// assert_eq!(rope, "Hello\u{FFFD} world!");
+    /// assert_eq!(replacements.len(), 1);
+    /// assert_eq!(replacements[0].input_byte_range, 5..6);
+    /// ```
+    pub fn from_reader_lossy<T: io::Read>(
+        mut reader: T,
+    ) -> io::Result<(Self, Vec<Utf8Replacement>)> {
+        const BUFFER_SIZE: usize = MAX_BYTES * 2;
+        let mut builder = RopeBuilder::new();
+        let mut buffer = [0u8; BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(read_count) => {
+                    // If we're done reading
+                    if read_count == 0 {
+                        return Ok(builder.finish_lossy());
+                    }
+
+                    // Sequences split across reads are held back by the
+                    // builder until the rest of them arrives.
+                    builder.append_bytes_lossy(&buffer[..read_count]);
+                }
+
+                Err(e) => {
+                    // Read error
+                    return Err(e);
+                }
+            }
+        }
+    }
+
     //-----------------------------------------------------------------------
     // Convenience output methods
 
@@ -221,6 +294,54 @@ impl Rope {
         Ok(())
     }
 
+    /// Writes the contents of the `Rope` to a writer, with all line breaks
+    /// converted to `line_ending`.
+    ///
+    /// The conversion is done chunk by chunk as the text is written, without
+    /// modifying the `Rope`.  Which line breaks are converted is the same as
+    /// for [`normalize_line_endings()`](Rope::normalize_line_endings).
+    ///
+    /// Runs in O(N) time.
+    ///
+    /// # Errors
+    ///
+    /// - If the writer returns an error, `write_to_with_line_ending` stops
+    ///   and returns that error.
+    ///
+    /// Note: some data may have been written even if an error is returned.
+    #[allow(unused_mut)]
+    pub fn write_to_with_line_ending<T: io::Write>(
+        &self,
+        mut writer: T,
+        line_ending: LineEnding,
+    ) -> io::Result<()> {
+        // Chunks never split a CRLF pair, so each one can be converted on
+        // its own.
+        let mut buf = String::new();
+        for chunk in self.chunks() {
+            buf.clear();
+            if convert_line_endings(chunk, line_ending, &mut buf) == 0 {
+                writer.write_all(chunk.as_bytes())?;
+            } else {
+                writer.write_all(buf.as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates a reader over the contents of the `Rope`.
+    ///
+    /// The reader implements `io::Read`, `io::BufRead`, and `io::Seek`, and
+    /// reads directly from the `Rope`'s chunks.  See [`RopeReader`] for
+    /// details.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn reader(&self) -> RopeReader<'_> {
+        self.slice(..).reader()
+    }
+
     //-----------------------------------------------------------------------
     // Informational methods
 
@@ -263,6 +384,35 @@ impl Rope {
         (info.chars + info.utf16_surrogates) as usize
     }
 
+    /// Counts the line breaks in the `Rope` by kind.
+    ///
+    /// Only line breaks recognized under the enabled feature flags are
+    /// counted, except that CRLF pairs are always counted as `crlf` rather
+    /// than `lf`.  Either way, the total is `len_lines() - 1`.
+    ///
+    /// Runs in O(N) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::{LineEnding, Rope};
+    /// let rope = Rope::from_str("Hello\r\nworld\r\n!\n");
+    /// let stats = rope.line_ending_stats();
+    ///
+    /// assert_eq!(stats.crlf, 2);
+    /// assert_eq!(stats.lf, 1);
+    /// assert_eq!(stats.most_common(), Some(LineEnding::Crlf));
+    /// ```
+    pub fn line_ending_stats(&self) -> LineEndingStats {
+        // CRLF pairs are never split between leaves, so each leaf can be
+        // counted on its own.
+        let mut stats = LineEndingStats::default();
+        Node::for_each_leaf(&self.root, &mut |leaf, _| {
+            stats.add_str(leaf.leaf_text());
+        });
+        stats
+    }
+
     //-----------------------------------------------------------------------
     // Memory management methods
 
@@ -357,6 +507,51 @@ impl Rope {
         self.try_insert_char(char_idx, ch).unwrap()
     }
 
+    /// Inserts the contents of another `Rope` at char index `char_idx`,
+    /// consuming the other `Rope`.
+    ///
+    /// Unlike inserting the other `Rope`'s text with `insert()`, this shares
+    /// the other `Rope`'s data rather than copying it, so it is efficient
+    /// even for very large insertions.
+    ///
+    /// Runs in O(log N + log M) time, where N is the length of this `Rope`
+    /// and M is the length of the other `Rope`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let mut rope = Rope::from_str("Hello world!");
+    /// rope.insert_rope(6, Rope::from_str("big wide "));
+    ///
+    /// assert_eq!("Hello big wide world!", rope);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn insert_rope(&mut self, char_idx: usize, rope: Rope) {
+        self.try_insert_rope(char_idx, rope).unwrap()
+    }
+
+    /// Inserts the contents of a `RopeSlice` at char index `char_idx`.
+    ///
+    /// Like [`insert_rope()`](Rope::insert_rope), this shares the data of
+    /// the `Rope` the slice belongs to rather than copying it, aside from
+    /// the partial chunks at the slice's edges.
+    ///
+    /// Runs in O(log N + log M) time, where N is the length of this `Rope`
+    /// and M is the length of the `Rope` the slice belongs to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn insert_slice(&mut self, char_idx: usize, slice: RopeSlice) {
+        self.try_insert_slice(char_idx, slice).unwrap()
+    }
+
     /// Private internal-only method that does a single insertion of
     /// sufficiently small text.
     ///
@@ -546,6 +741,165 @@ impl Rope {
         self.try_remove(char_range).unwrap()
     }
 
+    /// Replaces the text in the given char index range with `text`.
+    ///
+    /// Uses range syntax, e.g. `2..7`, `2..`, etc.  The range is in `char`
+    /// indices.
+    ///
+    /// This is equivalent to a `remove()` followed by an `insert()`, but is
+    /// done in a single pass over the tree, even when the range spans many
+    /// chunks.  When the range and `text` are small, the edit is usually
+    /// done in-place within a single chunk.
+    ///
+    /// Runs in O(M + log N) time, where N is the length of the `Rope` and M
+    /// is the length of the range being replaced plus the length of `text`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let mut rope = Rope::from_str("Hello world!");
+    /// rope.replace(6..11, "there");
+    ///
+    /// assert_eq!("Hello there!", rope);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. `end > len_chars()`).
+    pub fn replace<R>(&mut self, char_range: R, text: &str)
+    where
+        R: RangeBounds<usize>,
+    {
+        self.try_replace(char_range, text).unwrap()
+    }
+
+    /// Replaces all non-overlapping occurrences of `pattern` with
+    /// `replacement`, returning the number of occurrences replaced.
+    ///
+    /// Occurrences are found as by [`matches()`](Rope::matches), so an empty
+    /// `pattern` matches at every char boundary.
+    ///
+    /// Rather than editing the `Rope` once per occurrence, this applies all
+    /// of the replacements in a single pass over the tree.  Subtrees that
+    /// don't contain any occurrences are left untouched (and stay shared
+    /// with any clones of the `Rope`), so the cost is dominated by the
+    /// search itself even with millions of occurrences.
+    ///
+    /// Runs in O(N + K) time, where K is the total length of the
+    /// replacement text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let mut rope = Rope::from_str("one fish, two fish");
+    /// let count = rope.replace_all("fish", "cat");
+    ///
+    /// assert_eq!(count, 2);
+    /// assert_eq!("one cat, two cat", rope);
+    /// ```
+    #[inline]
+    pub fn replace_all(&mut self, pattern: &str, replacement: &str) -> usize {
+        self.replace_all_with(pattern, |_| replacement)
+    }
+
+    /// Replaces all non-overlapping occurrences of `pattern` with text
+    /// computed by `replacer`, returning the number of occurrences replaced.
+    ///
+    /// `replacer` is called for each occurrence in order, with the
+    /// occurrence's char range in the original text.  Otherwise this works
+    /// like [`replace_all()`](Rope::replace_all).
+    ///
+    /// Runs in O(N + K) time, where K is the total length of the
+    /// replacement text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let mut rope = Rope::from_str("[ ] eggs, [ ] milk");
+    /// let mut n = 0;
+    /// rope.replace_all_with("[ ]", |_| {
+    ///     n += 1;
+    ///     format!("{}.", n)
+    /// });
+    ///
+    /// assert_eq!("1. eggs, 2. milk", rope);
+    /// ```
+    pub fn replace_all_with<F, S>(&mut self, pattern: &str, mut replacer: F) -> usize
+    where
+        F: FnMut(Range<usize>) -> S,
+        S: AsRef<str>,
+    {
+        let replacements: Vec<(Range<usize>, S)> = self
+            .matches(pattern)
+            .map(|m| {
+                let text = replacer(m.clone());
+                (m, text)
+            })
+            .collect();
+
+        let edits: Vec<RangeEdit> = replacements
+            .iter()
+            .filter(|(m, text)| !m.is_empty() || !text.as_ref().is_empty())
+            .map(|(m, text)| RangeEdit {
+                start: m.start,
+                end: m.end,
+                text: text.as_ref(),
+            })
+            .collect();
+        self.edit_ranges(&edits, EditMetric::Chars).unwrap();
+
+        replacements.len()
+    }
+
+    /// Converts all line breaks in the `Rope` to `line_ending`.
+    ///
+    /// Only line breaks recognized under the enabled feature flags are
+    /// converted, except that CRLF pairs are always converted as a unit.
+    /// (Without the `cr_lines` feature, converting just the LF of a CRLF
+    /// would leave a stray CR behind.)
+    ///
+    /// This is done in a single pass over the text.  Leaves that don't
+    /// need any changes are shared with the original rope rather than
+    /// copied.
+    ///
+    /// Returns the number of line breaks that were changed.
+    ///
+    /// Runs in O(N) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::{LineEnding, Rope};
+    /// let mut rope = Rope::from_str("Hello\r\nworld\n!\r\n");
+    /// assert_eq!(rope.normalize_line_endings(LineEnding::Lf), 2);
+    ///
+    /// assert_eq!(rope, "Hello\nworld\n!\n");
+    /// ```
+    pub fn normalize_line_endings(&mut self, line_ending: LineEnding) -> usize {
+        let mut splicer = LeafSplicer::new();
+        let mut count = 0;
+        let mut buf = String::new();
+        Node::for_each_leaf(&self.root, &mut |leaf, _| {
+            buf.clear();
+            let changed = convert_line_endings(leaf.leaf_text(), line_ending, &mut buf);
+            if changed == 0 {
+                splicer.push_leaf(leaf);
+            } else {
+                splicer.push_str(&buf);
+                count += changed;
+            }
+        });
+
+        if count > 0 {
+            *self = splicer.finish();
+        }
+        count
+    }
+
     /// Splits the `Rope` at `char_idx`, returning the right part of
     /// the split.
     ///
@@ -613,103 +967,302 @@ impl Rope {
         }
     }
 
-    //-----------------------------------------------------------------------
-    // Index conversion methods
-
-    /// Returns the char index of the given byte.
+    /// Moves the text in `char_range` to `dest_char_idx`.
     ///
-    /// Notes:
+    /// `dest_char_idx` is a char index in the `Rope` as it is *before* the
+    /// move, and must not be inside of `char_range` (though it can be at
+    /// either end of it, in which case nothing changes).
     ///
-    /// - If the byte is in the middle of a multi-byte char, returns the
-    ///   index of the char that the byte belongs to.
-    /// - `byte_idx` can be one-past-the-end, which will return
-    ///   one-past-the-end char index.
+    /// The moved text is spliced out and back in by splitting and appending
+    /// the `Rope`, so it shares data rather than being copied, and large
+    /// moves are as efficient as small ones.
     ///
     /// Runs in O(log N) time.
     ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let mut rope = Rope::from_str("one\ntwo\nthree\n");
+    /// rope.move_range(0..4, 8);
+    ///
+    /// assert_eq!("two\none\nthree\n", rope);
+    /// ```
+    ///
     /// # Panics
     ///
-    /// Panics if `byte_idx` is out of bounds (i.e. `byte_idx > len_bytes()`).
-    #[inline]
-    pub fn byte_to_char(&self, byte_idx: usize) -> usize {
-        self.try_byte_to_char(byte_idx).unwrap()
+    /// Panics if the start of the range is greater than the end, if the end
+    /// or `dest_char_idx` is out of bounds (i.e. `> len_chars()`), or if
+    /// `dest_char_idx` is inside the range.
+    pub fn move_range<R>(&mut self, char_range: R, dest_char_idx: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        self.try_move_range(char_range, dest_char_idx).unwrap()
     }
 
-    /// Returns the line index of the given byte.
+    /// Inserts a copy of the text in `char_range` at `dest_char_idx`.
     ///
-    /// Notes:
+    /// `dest_char_idx` is a char index in the `Rope` as it is *before* the
+    /// copy is inserted, and can be anywhere in the `Rope`, including inside
+    /// of `char_range`.
     ///
-    /// - Lines are zero-indexed.  This is functionally equivalent to
-    ///   counting the line endings before the specified byte.
-    /// - `byte_idx` can be one-past-the-end, which will return the
-    ///   last line index.
+    /// The copy shares data with the original text rather than copying it,
+    /// so large duplications are as efficient as small ones.
     ///
     /// Runs in O(log N) time.
     ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let mut rope = Rope::from_str("one\ntwo\n");
+    /// rope.duplicate_range(0..4, 4);
+    ///
+    /// assert_eq!("one\none\ntwo\n", rope);
+    /// ```
+    ///
     /// # Panics
     ///
-    /// Panics if `byte_idx` is out of bounds (i.e. `byte_idx > len_bytes()`).
-    #[inline]
-    pub fn byte_to_line(&self, byte_idx: usize) -> usize {
-        self.try_byte_to_line(byte_idx).unwrap()
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end or `dest_char_idx` is out of bounds (i.e. `> len_chars()`).
+    pub fn duplicate_range<R>(&mut self, char_range: R, dest_char_idx: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        self.try_duplicate_range(char_range, dest_char_idx).unwrap()
     }
 
-    /// Returns the byte index of the given char.
+    /// Applies a batch of edits atomically.
     ///
-    /// Notes:
+    /// Each edit is a `(char_range, text)` pair that replaces the text in
+    /// `char_range` with `text`.  All ranges are in terms of the `Rope`'s
+    /// char indices *before* any of the edits are applied, so there is no
+    /// need to shift the indices of later edits to account for earlier ones.
+    /// The edits may be given in any order, but their ranges must not
+    /// overlap.  Multiple insertions (empty ranges) at the same index are
+    /// applied in the order given.
     ///
-    /// - `char_idx` can be one-past-the-end, which will return
-    ///   one-past-the-end byte index.
+    /// Either all of the edits are applied or none of them are.
     ///
-    /// Runs in O(log N) time.
+    /// The edits are applied together in a single pass over the tree, so
+    /// edits that are close to each other share the work of finding them,
+    /// and subtrees with no edits in them are left untouched.
+    ///
+    /// Runs in O(M + K log N) time, where N is the length of the `Rope`, K
+    /// is the number of edits, and M is the total length of the edited
+    /// ranges and inserted texts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let mut rope = Rope::from_str("Hello world!");
+    /// rope.apply_edits([(11..12, "?"), (0..5, "Goodbye"), (5..5, ",")]);
+    ///
+    /// assert_eq!("Goodbye, world?", rope);
+    /// ```
     ///
     /// # Panics
     ///
-    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
-    #[inline]
-    pub fn char_to_byte(&self, char_idx: usize) -> usize {
-        self.try_char_to_byte(char_idx).unwrap()
+    /// Panics if the start of any range is greater than its end, if the end
+    /// of any range is out of bounds (i.e. `end > len_chars()`), or if any
+    /// two ranges overlap.  In those cases the `Rope` is left unmodified.
+    pub fn apply_edits<'t, I, R>(&mut self, edits: I)
+    where
+        I: IntoIterator<Item = (R, &'t str)>,
+        R: RangeBounds<usize>,
+    {
+        self.try_apply_edits(edits).unwrap()
     }
 
-    /// Returns the line index of the given char.
+    /// Inserts `text` at byte index `byte_idx`.
     ///
-    /// Notes:
-    ///
-    /// - Lines are zero-indexed.  This is functionally equivalent to
-    ///   counting the line endings before the specified char.
-    /// - `char_idx` can be one-past-the-end, which will return the
-    ///   last line index.
+    /// Identical to [`insert()`](Rope::insert), except using a byte index.
     ///
-    /// Runs in O(log N) time.
+    /// Runs in O(M + log N) time, where N is the length of the `Rope` and M
+    /// is the length of `text`.
     ///
     /// # Panics
     ///
-    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    /// Panics if `byte_idx` is out of bounds (i.e. `byte_idx > len_bytes()`)
+    /// or is not a char boundary.
     #[inline]
-    pub fn char_to_line(&self, char_idx: usize) -> usize {
-        self.try_char_to_line(char_idx).unwrap()
+    pub fn insert_at_byte(&mut self, byte_idx: usize, text: &str) {
+        match self.try_insert_at_byte(byte_idx, text) {
+            Ok(_) => {}
+            Err(e) => panic!("insert_at_byte(): {}", e),
+        }
     }
 
-    /// Returns the utf16 code unit index of the given char.
+    /// Inserts a single char `ch` at byte index `byte_idx`.
     ///
-    /// Ropey stores text internally as utf8, but sometimes it is necessary
-    /// to interact with external APIs that still use utf16.  This function is
-    /// primarily intended for such situations, and is otherwise not very
-    /// useful.
+    /// Identical to [`insert_char()`](Rope::insert_char), except using a
+    /// byte index.
     ///
     /// Runs in O(log N) time.
     ///
     /// # Panics
     ///
-    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    /// Panics if `byte_idx` is out of bounds (i.e. `byte_idx > len_bytes()`)
+    /// or is not a char boundary.
     #[inline]
-    pub fn char_to_utf16_cu(&self, char_idx: usize) -> usize {
-        self.try_char_to_utf16_cu(char_idx).unwrap()
+    pub fn insert_char_at_byte(&mut self, byte_idx: usize, ch: char) {
+        match self.try_insert_char_at_byte(byte_idx, ch) {
+            Ok(_) => {}
+            Err(e) => panic!("insert_char_at_byte(): {}", e),
+        }
     }
 
-    /// Returns the char index of the given utf16 code unit.
+    /// Removes the text in the given byte index range.
     ///
-    /// Ropey stores text internally as utf8, but sometimes it is necessary
+    /// Identical to [`remove()`](Rope::remove), except using byte indices.
+    ///
+    /// Runs in O(M + log N) time, where N is the length of the `Rope` and M
+    /// is the length of the range being removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let mut rope = Rope::from_str("こんにちは world!");
+    /// rope.remove_byte_range(..16);
+    ///
+    /// assert_eq!("world!", rope);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - The start of the range is greater than the end.
+    /// - The end is out of bounds (i.e. `end > len_bytes()`).
+    /// - The range doesn't align with char boundaries.
+    pub fn remove_byte_range<R>(&mut self, byte_range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        match self.try_remove_byte_range(byte_range) {
+            Ok(_) => {}
+            Err(e) => panic!("remove_byte_range(): {}", e),
+        }
+    }
+
+    /// Splits the `Rope` at `byte_idx`, returning the right part of
+    /// the split.
+    ///
+    /// Identical to [`split_off()`](Rope::split_off), except using a byte
+    /// index.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is out of bounds (i.e. `byte_idx > len_bytes()`)
+    /// or is not a char boundary.
+    pub fn split_off_at_byte(&mut self, byte_idx: usize) -> Self {
+        match self.try_split_off_at_byte(byte_idx) {
+            Ok(r) => r,
+            Err(e) => panic!("split_off_at_byte(): {}", e),
+        }
+    }
+
+    //-----------------------------------------------------------------------
+    // Index conversion methods
+
+    /// Returns the char index of the given byte.
+    ///
+    /// Notes:
+    ///
+    /// - If the byte is in the middle of a multi-byte char, returns the
+    ///   index of the char that the byte belongs to.
+    /// - `byte_idx` can be one-past-the-end, which will return
+    ///   one-past-the-end char index.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is out of bounds (i.e. `byte_idx > len_bytes()`).
+    #[inline]
+    pub fn byte_to_char(&self, byte_idx: usize) -> usize {
+        self.try_byte_to_char(byte_idx).unwrap()
+    }
+
+    /// Returns the line index of the given byte.
+    ///
+    /// Notes:
+    ///
+    /// - Lines are zero-indexed.  This is functionally equivalent to
+    ///   counting the line endings before the specified byte.
+    /// - `byte_idx` can be one-past-the-end, which will return the
+    ///   last line index.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is out of bounds (i.e. `byte_idx > len_bytes()`).
+    #[inline]
+    pub fn byte_to_line(&self, byte_idx: usize) -> usize {
+        self.try_byte_to_line(byte_idx).unwrap()
+    }
+
+    /// Returns the byte index of the given char.
+    ///
+    /// Notes:
+    ///
+    /// - `char_idx` can be one-past-the-end, which will return
+    ///   one-past-the-end byte index.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn char_to_byte(&self, char_idx: usize) -> usize {
+        self.try_char_to_byte(char_idx).unwrap()
+    }
+
+    /// Returns the line index of the given char.
+    ///
+    /// Notes:
+    ///
+    /// - Lines are zero-indexed.  This is functionally equivalent to
+    ///   counting the line endings before the specified char.
+    /// - `char_idx` can be one-past-the-end, which will return the
+    ///   last line index.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn char_to_line(&self, char_idx: usize) -> usize {
+        self.try_char_to_line(char_idx).unwrap()
+    }
+
+    /// Returns the utf16 code unit index of the given char.
+    ///
+    /// Ropey stores text internally as utf8, but sometimes it is necessary
+    /// to interact with external APIs that still use utf16.  This function is
+    /// primarily intended for such situations, and is otherwise not very
+    /// useful.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn char_to_utf16_cu(&self, char_idx: usize) -> usize {
+        self.try_char_to_utf16_cu(char_idx).unwrap()
+    }
+
+    /// Returns the char index of the given utf16 code unit.
+    ///
+    /// Ropey stores text internally as utf8, but sometimes it is necessary
     /// to interact with external APIs that still use utf16.  This function is
     /// primarily intended for such situations, and is otherwise not very
     /// useful.
@@ -728,6 +1281,46 @@ impl Rope {
         self.try_utf16_cu_to_char(utf16_cu_idx).unwrap()
     }
 
+    /// Returns the line/column position of the given char.
+    ///
+    /// The column is counted from the start of the char's line, in the units
+    /// of `encoding`.
+    ///
+    /// Notes:
+    ///
+    /// - `char_idx` can be one-past-the-end, which will return the position
+    ///   of the end of the last line.
+    /// - If `char_idx` is between the CR and LF of a CRLF pair, the position
+    ///   of the start of the CRLF is returned, since columns end at the start
+    ///   of the line break.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn char_to_line_col(&self, char_idx: usize, encoding: PositionEncoding) -> LineCol {
+        self.try_char_to_line_col(char_idx, encoding).unwrap()
+    }
+
+    /// Returns the char index of the given line/column position.
+    ///
+    /// The column is interpreted in the units of `encoding`, and can be at
+    /// most the length of the line excluding its line break.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos.line` is out of bounds (i.e. `pos.line >= len_lines()`),
+    /// if `pos.col` is past the end of the line, or if `pos.col` is in the
+    /// middle of a char.
+    #[inline]
+    pub fn line_col_to_char(&self, pos: LineCol, encoding: PositionEncoding) -> usize {
+        self.try_line_col_to_char(pos, encoding).unwrap()
+    }
+
     /// Returns the byte index of the start of the given line.
     ///
     /// Notes:
@@ -1041,6 +1634,41 @@ impl Rope {
         }
     }
 
+    /// Creates an iterator over the chars of the `Rope`, along with their
+    /// positions.
+    ///
+    /// Each char is yielded as a `(char_idx, byte_idx, char)` tuple.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn char_indices(&self) -> CharIndices<'_> {
+        CharIndices::new(self.chars(), 0, 0)
+    }
+
+    /// Creates an iterator over the chars of the `Rope`, along with their
+    /// positions, starting at char `char_idx`.
+    ///
+    /// If `char_idx == len_chars()` then an iterator at the end of the
+    /// `Rope` is created (i.e. `next()` will return `None`).
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn char_indices_at(&self, char_idx: usize) -> CharIndices<'_> {
+        if let Some(out) = self.get_char_indices_at(char_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of Rope: char index {}, Rope char length {}",
+                char_idx,
+                self.len_chars()
+            );
+        }
+    }
+
     /// Creates an iterator over the lines of the `Rope`.
     ///
     /// Runs in O(log N) time.
@@ -1073,6 +1701,76 @@ impl Rope {
         }
     }
 
+    /// Creates an iterator over the lines of the `Rope`, without their line
+    /// breaks.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn lines_without_endings(&self) -> LinesWithoutEndings<'_> {
+        LinesWithoutEndings::new(self.lines())
+    }
+
+    /// Creates an iterator over the lines of the `Rope`, without their line
+    /// breaks, starting at line `line_idx`.
+    ///
+    /// If `line_idx == len_lines()` then an iterator at the end of the
+    /// `Rope` is created (i.e. `next()` will return `None`).
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_idx` is out of bounds (i.e. `line_idx > len_lines()`).
+    #[inline]
+    pub fn lines_without_endings_at(&self, line_idx: usize) -> LinesWithoutEndings<'_> {
+        if let Some(out) = self.get_lines_without_endings_at(line_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of Rope: line index {}, Rope line length {}",
+                line_idx,
+                self.len_lines()
+            );
+        }
+    }
+
+    /// Creates an iterator over the lines of the `Rope`, along with their
+    /// line breaks and positions.
+    ///
+    /// Each line is yielded as a [`LineInfo`](crate::iter::LineInfo), giving the line's text
+    /// without its line break, the kind of line break, and the char and byte
+    /// range of the whole line.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn line_infos(&self) -> LineInfos<'_> {
+        LineInfos::new(self.lines(), 0, 0)
+    }
+
+    /// Creates an iterator over the lines of the `Rope`, along with their
+    /// line breaks and positions, starting at line `line_idx`.
+    ///
+    /// If `line_idx == len_lines()` then an iterator at the end of the
+    /// `Rope` is created (i.e. `next()` will return `None`).
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_idx` is out of bounds (i.e. `line_idx > len_lines()`).
+    #[inline]
+    pub fn line_infos_at(&self, line_idx: usize) -> LineInfos<'_> {
+        if let Some(out) = self.get_line_infos_at(line_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of Rope: line index {}, Rope line length {}",
+                line_idx,
+                self.len_lines()
+            );
+        }
+    }
+
     /// Creates an iterator over the chunks of the `Rope`.
     ///
     /// Runs in O(log N) time.
@@ -1175,1153 +1873,2603 @@ impl Rope {
         }
     }
 
-    /// Returns true if this rope and `other` point to precisely the same
-    /// in-memory data.
-    ///
-    /// This happens when one of the ropes is a clone of the other and
-    /// neither have been modified since then.  Because clones initially
-    /// share all the same data, it can be useful to check if they still
-    /// point to precisely the same memory as a way of determining
-    /// whether they are both still unmodified.
+    /// Creates an iterator over the chunks of the `Rope`, along with their
+    /// positions.
     ///
-    /// Note: this is distinct from checking for equality: two ropes can
-    /// have the same *contents* (equal) but be stored in different
-    /// memory locations (not instances).  Importantly, two clones that
-    /// post-cloning are modified identically will *not* be instances
-    /// anymore, even though they will have equal contents.
+    /// Each chunk is yielded as a `(chunk, byte_idx, char_idx, line_idx)`
+    /// tuple, where the indices are those of the start of the chunk.
     ///
-    /// Runs in O(1) time.
+    /// Runs in O(log N) time.
     #[inline]
-    pub fn is_instance(&self, other: &Rope) -> bool {
-        Arc::ptr_eq(&self.root, &other.root)
+    pub fn chunk_indices(&self) -> ChunkIndices<'_> {
+        ChunkIndices::new(self.chunks(), 0, 0, 0)
     }
 
-    //-----------------------------------------------------------------------
-    // Debugging
-
-    /// NOT PART OF THE PUBLIC API (hidden from docs for a reason!)
+    /// Creates an iterator over the chunks of the `Rope`, along with their
+    /// positions, with the iterator starting at the chunk containing
+    /// `byte_idx`.
     ///
-    /// Debugging tool to make sure that all of the meta-data of the
-    /// tree is consistent with the actual data.
-    #[doc(hidden)]
-    pub fn assert_integrity(&self) {
-        self.root.assert_integrity();
+    /// If `byte_idx == len_bytes()` an iterator at the end of the `Rope`
+    /// (yielding `None` on a call to `next()`) is created.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is out of bounds (i.e. `byte_idx > len_bytes()`).
+    #[inline]
+    pub fn chunk_indices_at_byte(&self, byte_idx: usize) -> ChunkIndices<'_> {
+        if let Some(out) = self.get_chunk_indices_at_byte(byte_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of Rope: byte index {}, Rope byte length {}",
+                byte_idx,
+                self.len_bytes()
+            );
+        }
     }
 
-    /// NOT PART OF THE PUBLIC API (hidden from docs for a reason!)
+    /// Creates an iterator over the chunks of the `Rope`, along with their
+    /// positions, with the iterator starting at the chunk containing
+    /// `char_idx`.
     ///
-    /// Debugging tool to make sure that all of the following invariants
-    /// hold true throughout the tree:
+    /// If `char_idx == len_chars()` an iterator at the end of the `Rope`
+    /// (yielding `None` on a call to `next()`) is created.
     ///
-    /// - The tree is the same height everywhere.
-    /// - All internal nodes have the minimum number of children.
-    /// - All leaf nodes are non-empty.
-    /// - CRLF pairs are never split over chunk boundaries.
-    #[doc(hidden)]
-    pub fn assert_invariants(&self) {
-        self.root.assert_balance();
-        self.root.assert_node_size(true);
-        self.assert_crlf_seams();
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn chunk_indices_at_char(&self, char_idx: usize) -> ChunkIndices<'_> {
+        if let Some(out) = self.get_chunk_indices_at_char(char_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of Rope: char index {}, Rope char length {}",
+                char_idx,
+                self.len_chars()
+            );
+        }
     }
 
-    /// Checks that CRLF pairs are never split over chunk boundaries.
-    fn assert_crlf_seams(&self) {
-        if self.chunks().count() > 0 {
-            let mut itr = self.chunks();
-            let mut last_chunk = itr.next().unwrap();
-            for chunk in itr {
-                if !chunk.is_empty() && !last_chunk.is_empty() {
-                    assert!(crlf::seam_is_break(last_chunk.as_bytes(), chunk.as_bytes()));
-                    last_chunk = chunk;
-                } else if last_chunk.is_empty() {
-                    last_chunk = chunk;
-                }
-            }
+    /// Creates an iterator over the chunks of the `Rope`, along with their
+    /// positions, with the iterator starting at the chunk containing
+    /// `line_break_idx`.
+    ///
+    /// If `line_break_idx == len_lines()` an iterator at the end of the `Rope`
+    /// (yielding `None` on a call to `next()`) is created.
+    ///
+    /// See [`chunks_at_line_break()`](Rope::chunks_at_line_break) for how
+    /// line break indices are interpreted.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_break_idx` is out of bounds (i.e. `line_break_idx > len_lines()`).
+    #[inline]
+    pub fn chunk_indices_at_line_break(&self, line_break_idx: usize) -> ChunkIndices<'_> {
+        if let Some(out) = self.get_chunk_indices_at_line_break(line_break_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of Rope: line break index {}, max index {}",
+                line_break_idx,
+                self.len_lines()
+            );
         }
     }
 
-    //-----------------------------------------------------------------------
-    // Internal utilities
+    /// Returns true if this rope and `other` point to precisely the same
+    /// in-memory data.
+    ///
+    /// This happens when one of the ropes is a clone of the other and
+    /// neither have been modified since then.  Because clones initially
+    /// share all the same data, it can be useful to check if they still
+    /// point to precisely the same memory as a way of determining
+    /// whether they are both still unmodified.
+    ///
+    /// Note: this is distinct from checking for equality: two ropes can
+    /// have the same *contents* (equal) but be stored in different
+    /// memory locations (not instances).  Importantly, two clones that
+    /// post-cloning are modified identically will *not* be instances
+    /// anymore, even though they will have equal contents.
+    ///
+    /// Runs in O(1) time.
+    #[inline]
+    pub fn is_instance(&self, other: &Rope) -> bool {
+        Arc::ptr_eq(&self.root, &other.root)
+    }
 
-    /// Iteratively replaces the root node with its child if it only has
-    /// one child.
-    pub(crate) fn pull_up_singular_nodes(&mut self) {
-        while (!self.root.is_leaf()) && self.root.child_count() == 1 {
-            let child = if let Node::Internal(ref children) = *self.root {
-                Arc::clone(&children.nodes()[0])
-            } else {
-                unreachable!()
-            };
+    //-----------------------------------------------------------------------
+    // Grapheme methods
 
-            self.root = child;
-        }
+    /// Creates an iterator over the extended grapheme clusters of the
+    /// `Rope`.
+    ///
+    /// Graphemes are yielded as `RopeSlice`s, and are correctly found even
+    /// when they span chunk boundaries.
+    ///
+    /// Only available with the `graphemes` feature.
+    ///
+    /// Runs in O(log N) time.
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn graphemes(&self) -> Graphemes<'_> {
+        Graphemes::new(&self.slice(..))
     }
-}
 
-/// # Non-Panicking
-///
-/// The methods in this impl block provide non-panicking versions of
-/// `Rope`'s panicking methods.  They return either `Option::None` or
-/// `Result::Err()` when their panicking counterparts would have panicked.
-impl Rope {
-    /// Non-panicking version of [`insert()`](Rope::insert).
+    /// Returns whether `char_idx` is on an extended grapheme cluster
+    /// boundary.
+    ///
+    /// The start and end of the `Rope` are always boundaries.
+    ///
+    /// Only available with the `graphemes` feature.
+    ///
+    /// Runs in O(log N) time for each chunk that needs to be examined,
+    /// which is usually just one or two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "graphemes")]
     #[inline]
-    pub fn try_insert(&mut self, char_idx: usize, text: &str) -> Result<()> {
-        // Bounds check
-        if char_idx <= self.len_chars() {
-            // We have three cases here:
-            // 1. The insertion text is very large, in which case building a new
-            //    Rope out of it and splicing it into the existing Rope is most
-            //    efficient.
-            // 2. The insertion text is somewhat large, in which case splitting it
-            //    up into chunks and repeatedly inserting them is the most
-            //    efficient.  The splitting is necessary because the insertion code
-            //    only works correctly below a certain insertion size.
-            // 3. The insertion text is small, in which case we can simply insert
-            //    it.
-            //
-            // Cases #2 and #3 are rolled into one case here, where case #3 just
-            // results in the text being "split" into only one chunk.
-            //
-            // The boundary for what constitutes "very large" text was arrived at
-            // experimentally, by testing at what point Rope build + splice becomes
-            // faster than split + repeated insert.  This constant is likely worth
-            // revisiting from time to time as Ropey evolves.
-            if text.len() > MAX_BYTES * 6 {
-                // Case #1: very large text, build rope and splice it in.
-                let text_rope = Rope::from_str(text);
-                let right = self.split_off(char_idx);
-                self.append(text_rope);
-                self.append(right);
-            } else {
-                // Cases #2 and #3: split into chunks and repeatedly insert.
-                let mut text = text;
-                while !text.is_empty() {
-                    // Split a chunk off from the end of the text.
-                    // We do this from the end instead of the front so that
-                    // the repeated insertions can keep re-using the same
-                    // insertion point.
-                    let split_idx = crlf::find_good_split(
-                        text.len() - (MAX_BYTES - 4).min(text.len()),
-                        text.as_bytes(),
-                        false,
-                    );
-                    let ins_text = &text[split_idx..];
-                    text = &text[..split_idx];
-
-                    // Do the insertion.
-                    self.insert_internal(char_idx, ins_text);
-                }
-            }
-            Ok(())
-        } else {
-            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
-        }
+    pub fn is_grapheme_boundary(&self, char_idx: usize) -> bool {
+        self.try_is_grapheme_boundary(char_idx).unwrap()
     }
 
-    /// Non-panicking version of [`insert_char()`](Rope::insert_char).
+    /// Returns the char index of the extended grapheme cluster boundary
+    /// before `char_idx`.
+    ///
+    /// Returns 0 if there is no boundary before `char_idx`.
+    ///
+    /// Only available with the `graphemes` feature.
+    ///
+    /// Runs in O(log N) time for each chunk that needs to be examined,
+    /// which is usually just one or two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "graphemes")]
     #[inline]
-    pub fn try_insert_char(&mut self, char_idx: usize, ch: char) -> Result<()> {
-        // Bounds check
-        if char_idx <= self.len_chars() {
-            let mut buf = [0u8; 4];
-            self.insert_internal(char_idx, ch.encode_utf8(&mut buf));
-            Ok(())
-        } else {
-            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
-        }
+    pub fn prev_grapheme_boundary(&self, char_idx: usize) -> usize {
+        self.try_prev_grapheme_boundary(char_idx).unwrap()
     }
 
-    /// Non-panicking version of [`remove()`](Rope::remove).
-    pub fn try_remove<R>(&mut self, char_range: R) -> Result<()>
-    where
-        R: RangeBounds<usize>,
-    {
-        let start_opt = start_bound_to_num(char_range.start_bound());
-        let end_opt = end_bound_to_num(char_range.end_bound());
-        let start = start_opt.unwrap_or(0);
-        let end = end_opt.unwrap_or_else(|| self.len_chars());
-        if end.max(start) > self.len_chars() {
-            Err(Error::CharRangeOutOfBounds(
-                start_opt,
-                end_opt,
-                self.len_chars(),
-            ))
-        } else if start > end {
-            Err(Error::CharRangeInvalid(start, end))
-        } else {
-            // A special case that the rest of the logic doesn't handle
-            // correctly.
-            if start == 0 && end == self.len_chars() {
-                self.root = Arc::new(Node::new());
-                return Ok(());
-            }
-
-            let root = Arc::make_mut(&mut self.root);
-
-            let root_info = root.text_info();
-            let (_, crlf_seam, needs_fix) = root.remove_char_range(start, end, root_info);
+    /// Returns the char index of the extended grapheme cluster boundary
+    /// after `char_idx`.
+    ///
+    /// Returns `len_chars()` if there is no boundary after `char_idx`.
+    ///
+    /// Only available with the `graphemes` feature.
+    ///
+    /// Runs in O(log N) time for each chunk that needs to be examined,
+    /// which is usually just one or two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn next_grapheme_boundary(&self, char_idx: usize) -> usize {
+        self.try_next_grapheme_boundary(char_idx).unwrap()
+    }
 
-            if crlf_seam {
-                let seam_idx = root.char_to_text_info(start).bytes;
-                root.fix_crlf_seam(seam_idx as Count, false);
-            }
+    //-----------------------------------------------------------------------
+    // Search methods
 
-            if needs_fix {
-                root.fix_tree_seam(start);
-            }
+    /// Returns the char index of the first occurrence of `pattern` in the
+    /// `Rope`, or `None` if there isn't one.
+    ///
+    /// Occurrences that span chunk boundaries are found without copying
+    /// the text.  An empty `pattern` matches at index 0.
+    ///
+    /// Runs in O(N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn find(&self, pattern: &str) -> Option<usize> {
+        self.slice(..).find(pattern)
+    }
 
-            self.pull_up_singular_nodes();
-            Ok(())
-        }
+    /// Returns the char index of the last occurrence of `pattern` in the
+    /// `Rope`, or `None` if there isn't one.
+    ///
+    /// An empty `pattern` matches at `len_chars()`.
+    ///
+    /// Runs in O(N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn rfind(&self, pattern: &str) -> Option<usize> {
+        self.slice(..).rfind(pattern)
     }
 
-    /// Non-panicking version of [`split_off()`](Rope::split_off).
-    pub fn try_split_off(&mut self, char_idx: usize) -> Result<Self> {
-        // Bounds check
-        if char_idx <= self.len_chars() {
-            if char_idx == 0 {
-                // Special case 1
-                let mut new_rope = Rope::new();
-                std::mem::swap(self, &mut new_rope);
-                Ok(new_rope)
-            } else if char_idx == self.len_chars() {
-                // Special case 2
-                Ok(Rope::new())
-            } else {
-                // Do the split
-                let mut new_rope = Rope {
-                    root: Arc::new(Arc::make_mut(&mut self.root).split(char_idx)),
-                };
-
-                // Fix up the edges
-                Arc::make_mut(&mut self.root).zip_fix_right();
-                Arc::make_mut(&mut new_rope.root).zip_fix_left();
-                self.pull_up_singular_nodes();
-                new_rope.pull_up_singular_nodes();
+    /// Returns the char index of the first occurrence of `pattern` that
+    /// starts at or after `char_idx`, or `None` if there isn't one.
+    ///
+    /// Runs in O(log N + K + M) time, where K is the length of the text
+    /// searched and M is the length of `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn find_from(&self, char_idx: usize, pattern: &str) -> Option<usize> {
+        self.try_find_from(char_idx, pattern).unwrap()
+    }
 
-                Ok(new_rope)
-            }
-        } else {
-            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
-        }
+    /// Returns the char index of the last occurrence of `pattern` that
+    /// ends at or before `char_idx`, or `None` if there isn't one.
+    ///
+    /// Runs in O(log N + K + M) time, where K is the length of the text
+    /// searched and M is the length of `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn rfind_from(&self, char_idx: usize, pattern: &str) -> Option<usize> {
+        self.try_rfind_from(char_idx, pattern).unwrap()
     }
 
-    /// Non-panicking version of [`byte_to_char()`](Rope::byte_to_char).
+    /// Creates an iterator over the non-overlapping occurrences of
+    /// `pattern` in the `Rope`, from front to back.
+    ///
+    /// Each occurrence is yielded as its char range.  An empty `pattern`
+    /// matches at every char boundary.
+    ///
+    /// Runs in O(log N + M) time, where M is the length of `pattern`.
     #[inline]
-    pub fn try_byte_to_char(&self, byte_idx: usize) -> Result<usize> {
-        // Bounds check
-        if byte_idx <= self.len_bytes() {
-            let (chunk, b, c, _) = self.chunk_at_byte(byte_idx);
-            Ok(c + byte_to_char_idx(chunk, byte_idx - b))
-        } else {
-            Err(Error::ByteIndexOutOfBounds(byte_idx, self.len_bytes()))
-        }
+    pub fn matches(&self, pattern: &str) -> Matches<'_> {
+        self.slice(..).matches(pattern)
     }
 
-    /// Non-panicking version of [`byte_to_line()`](Rope::byte_to_line).
+    /// Creates an iterator over the non-overlapping occurrences of
+    /// `pattern` in the `Rope`, from back to front.
+    ///
+    /// When occurrences overlap, this can find different ones than
+    /// [`matches()`](Rope::matches): for example, `"aa"` is found at `1..3`
+    /// in `"aaa"`, rather than at `0..2`.
+    ///
+    /// Runs in O(log N + M) time, where M is the length of `pattern`.
     #[inline]
-    pub fn try_byte_to_line(&self, byte_idx: usize) -> Result<usize> {
-        // Bounds check
-        if byte_idx <= self.len_bytes() {
-            let (chunk, b, _, l) = self.chunk_at_byte(byte_idx);
-            Ok(l + byte_to_line_idx(chunk, byte_idx - b))
-        } else {
-            Err(Error::ByteIndexOutOfBounds(byte_idx, self.len_bytes()))
-        }
+    pub fn rmatches(&self, pattern: &str) -> Matches<'_> {
+        self.slice(..).rmatches(pattern)
     }
 
-    /// Non-panicking version of [`char_to_byte()`](Rope::char_to_byte).
+    /// Returns the char range of the first case-insensitive occurrence of
+    /// `pattern` in the `Rope`, or `None` if there isn't one.
+    ///
+    /// The text and `pattern` are compared after full Unicode case folding,
+    /// so the occurrence can be a different length than `pattern`: for
+    /// example, `"SS"` is found at `4..5` in `"Straße"`.  Occurrences always
+    /// cover whole chars.
+    ///
+    /// Runs in O(N + M) time, where M is the length of `pattern`.
     #[inline]
-    pub fn try_char_to_byte(&self, char_idx: usize) -> Result<usize> {
-        // Bounds check
-        if char_idx <= self.len_chars() {
-            let (chunk, b, c, _) = self.chunk_at_char(char_idx);
-            Ok(b + char_to_byte_idx(chunk, char_idx - c))
-        } else {
-            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
-        }
+    pub fn find_ignore_case(&self, pattern: &str) -> Option<Range<usize>> {
+        self.slice(..).find_ignore_case(pattern)
     }
 
-    /// Non-panicking version of [`char_to_line()`](Rope::char_to_line).
+    /// Returns the char range of the last case-insensitive occurrence of
+    /// `pattern` in the `Rope`, or `None` if there isn't one.
+    ///
+    /// Runs in O(N + M) time, where M is the length of `pattern`.
     #[inline]
-    pub fn try_char_to_line(&self, char_idx: usize) -> Result<usize> {
-        // Bounds check
-        if char_idx <= self.len_chars() {
-            let (chunk, _, c, l) = self.chunk_at_char(char_idx);
-            Ok(l + char_to_line_idx(chunk, char_idx - c))
-        } else {
-            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
-        }
+    pub fn rfind_ignore_case(&self, pattern: &str) -> Option<Range<usize>> {
+        self.slice(..).rfind_ignore_case(pattern)
     }
 
-    /// Non-panicking version of [`char_to_utf16_cu()`](Rope::char_to_utf16_cu).
+    /// Returns the char range of the first case-insensitive occurrence of
+    /// `pattern` that starts at or after `char_idx`, or `None` if there isn't
+    /// one.
+    ///
+    /// Runs in O(log N + K + M) time, where K is the length of the text
+    /// searched and M is the length of `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
     #[inline]
-    pub fn try_char_to_utf16_cu(&self, char_idx: usize) -> Result<usize> {
-        // Bounds check
-        if char_idx <= self.len_chars() {
-            let (chunk, chunk_start_info) = self.root.get_chunk_at_char(char_idx);
-            let chunk_byte_idx =
-                char_to_byte_idx(chunk, char_idx - chunk_start_info.chars as usize);
-            let surrogate_count = byte_to_utf16_surrogate_idx(chunk, chunk_byte_idx);
+    pub fn find_from_ignore_case(&self, char_idx: usize, pattern: &str) -> Option<Range<usize>> {
+        self.try_find_from_ignore_case(char_idx, pattern).unwrap()
+    }
 
-            Ok(char_idx + chunk_start_info.utf16_surrogates as usize + surrogate_count)
-        } else {
-            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
-        }
+    /// Returns the char range of the last case-insensitive occurrence of
+    /// `pattern` that ends at or before `char_idx`, or `None` if there isn't
+    /// one.
+    ///
+    /// Runs in O(log N + K + M) time, where K is the length of the text
+    /// searched and M is the length of `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn rfind_from_ignore_case(&self, char_idx: usize, pattern: &str) -> Option<Range<usize>> {
+        self.try_rfind_from_ignore_case(char_idx, pattern).unwrap()
     }
 
-    /// Non-panicking version of [`utf16_cu_to_char()`](Rope::utf16_cu_to_char).
+    /// Creates an iterator over the non-overlapping case-insensitive
+    /// occurrences of `pattern` in the `Rope`, from front to back.
+    ///
+    /// See [`find_ignore_case()`](Rope::find_ignore_case) for how the text is
+    /// compared.  An empty `pattern` matches at every char boundary.
+    ///
+    /// Runs in O(log N + M) time, where M is the length of `pattern`.
     #[inline]
-    pub fn try_utf16_cu_to_char(&self, utf16_cu_idx: usize) -> Result<usize> {
-        // Bounds check
-        if utf16_cu_idx <= self.len_utf16_cu() {
-            let (chunk, chunk_start_info) = self.root.get_chunk_at_utf16_code_unit(utf16_cu_idx);
-            let chunk_utf16_cu_idx = utf16_cu_idx
-                - (chunk_start_info.chars + chunk_start_info.utf16_surrogates) as usize;
-            let chunk_char_idx = utf16_code_unit_to_char_idx(chunk, chunk_utf16_cu_idx);
+    pub fn matches_ignore_case(&self, pattern: &str) -> MatchesIgnoreCase<'_> {
+        self.slice(..).matches_ignore_case(pattern)
+    }
 
-            Ok(chunk_start_info.chars as usize + chunk_char_idx)
-        } else {
-            Err(Error::Utf16IndexOutOfBounds(
-                utf16_cu_idx,
-                self.len_utf16_cu(),
-            ))
-        }
+    /// Creates an iterator over the non-overlapping case-insensitive
+    /// occurrences of `pattern` in the `Rope`, from back to front.
+    ///
+    /// Runs in O(log N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn rmatches_ignore_case(&self, pattern: &str) -> MatchesIgnoreCase<'_> {
+        self.slice(..).rmatches_ignore_case(pattern)
     }
 
-    /// Non-panicking version of [`line_to_byte()`](Rope::line_to_byte).
+    //-----------------------------------------------------------------------
+    // Split methods
+
+    /// Creates an iterator over the pieces of the `Rope` separated by
+    /// `pattern`, from front to back.
+    ///
+    /// `pattern` can be a `char`, a `&str`, or a `FnMut(char) -> bool`
+    /// predicate, and the pieces are the same as `str::split()` would give.
+    /// Each piece is yielded as a `RopeSlice`.
+    ///
+    /// Runs in O(1) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let rope = Rope::from_str("name,age\tcity");
+    /// let fields: Vec<_> = rope.split(|c| c == ',' || c == '\t').collect();
+    ///
+    /// assert_eq!(fields, ["name", "age", "city"]);
+    /// ```
     #[inline]
-    pub fn try_line_to_byte(&self, line_idx: usize) -> Result<usize> {
-        // Bounds check
-        if line_idx <= self.len_lines() {
-            if line_idx == self.len_lines() {
-                Ok(self.len_bytes())
-            } else {
-                let (chunk, b, _, l) = self.chunk_at_line_break(line_idx);
-                Ok(b + line_to_byte_idx(chunk, line_idx - l))
-            }
-        } else {
-            Err(Error::LineIndexOutOfBounds(line_idx, self.len_lines()))
-        }
+    pub fn split<P: Pattern>(&self, pattern: P) -> Split<'_, P> {
+        self.slice(..).split(pattern)
     }
 
-    /// Non-panicking version of [`line_to_char()`](Rope::line_to_char).
+    /// Creates an iterator over the pieces of the `Rope` separated by
+    /// `pattern`, from back to front.
+    ///
+    /// The pieces are the same as `str::rsplit()` would give.
+    ///
+    /// Runs in O(1) time.
     #[inline]
-    pub fn try_line_to_char(&self, line_idx: usize) -> Result<usize> {
-        // Bounds check
-        if line_idx <= self.len_lines() {
-            if line_idx == self.len_lines() {
-                Ok(self.len_chars())
-            } else {
-                let (chunk, _, c, l) = self.chunk_at_line_break(line_idx);
-                Ok(c + line_to_char_idx(chunk, line_idx - l))
-            }
-        } else {
-            Err(Error::LineIndexOutOfBounds(line_idx, self.len_lines()))
-        }
+    pub fn rsplit<P: Pattern>(&self, pattern: P) -> Split<'_, P> {
+        self.slice(..).rsplit(pattern)
     }
 
-    /// Non-panicking version of [`byte()`](Rope::byte).
+    /// Like [`split()`](Rope::split), except that a trailing empty piece is
+    /// skipped.
+    ///
+    /// This is useful for text where `pattern` terminates each piece rather
+    /// than separating them, such as lines that each end with a line break.
+    ///
+    /// Runs in O(1) time.
     #[inline]
-    pub fn get_byte(&self, byte_idx: usize) -> Option<u8> {
-        // Bounds check
-        if byte_idx < self.len_bytes() {
-            let (chunk, chunk_byte_idx, _, _) = self.chunk_at_byte(byte_idx);
-            let chunk_rel_byte_idx = byte_idx - chunk_byte_idx;
-            Some(chunk.as_bytes()[chunk_rel_byte_idx])
-        } else {
-            None
-        }
+    pub fn split_terminator<P: Pattern>(&self, pattern: P) -> Split<'_, P> {
+        self.slice(..).split_terminator(pattern)
     }
 
-    /// Non-panicking version of [`char()`](Rope::char).
+    /// Like [`split()`](Rope::split), except that there are at most `n`
+    /// pieces.
+    ///
+    /// The last piece holds the rest of the `Rope`, including any further
+    /// occurrences of `pattern`.
+    ///
+    /// Runs in O(1) time.
     #[inline]
-    pub fn get_char(&self, char_idx: usize) -> Option<char> {
-        // Bounds check
-        if char_idx < self.len_chars() {
-            let (chunk, _, chunk_char_idx, _) = self.chunk_at_char(char_idx);
-            let byte_idx = char_to_byte_idx(chunk, char_idx - chunk_char_idx);
-            Some(chunk[byte_idx..].chars().next().unwrap())
-        } else {
-            None
-        }
+    pub fn splitn<P: Pattern>(&self, n: usize, pattern: P) -> Split<'_, P> {
+        self.slice(..).splitn(n, pattern)
     }
 
-    /// Non-panicking version of [`line()`](Rope::line).
+    /// Creates an iterator over the pieces of the `Rope` separated by
+    /// whitespace, from front to back.
+    ///
+    /// Any amount of whitespace separates pieces, and leading and trailing
+    /// whitespace is ignored, as with `str::split_whitespace()`.
+    ///
+    /// Runs in O(1) time.
     #[inline]
-    pub fn get_line(&self, line_idx: usize) -> Option<RopeSlice<'_>> {
-        use crate::slice::RSEnum;
-        use crate::str_utils::{count_chars, count_utf16_surrogates};
+    pub fn split_whitespace(&self) -> Split<'_, fn(char) -> bool> {
+        self.slice(..).split_whitespace()
+    }
 
-        let len_lines = self.len_lines();
+    //-----------------------------------------------------------------------
+    // Regex methods
 
-        // Bounds check
-        if line_idx < len_lines {
-            let (chunk_1, _, c1, l1) = self.chunk_at_line_break(line_idx);
-            let (chunk_2, _, c2, l2) = self.chunk_at_line_break(line_idx + 1);
-            if c1 == c2 {
-                let text1 = &chunk_1[line_to_byte_idx(chunk_1, line_idx - l1)..];
-                let text2 = &text1[..line_to_byte_idx(text1, 1)];
-                Some(RopeSlice(RSEnum::Light {
-                    text: text2,
-                    char_count: count_chars(text2) as Count,
-                    utf16_surrogate_count: count_utf16_surrogates(text2) as Count,
-                    line_break_count: if line_idx == (len_lines - 1) { 0 } else { 1 },
-                }))
-            } else {
-                let start = c1 + line_to_char_idx(chunk_1, line_idx - l1);
-                let end = c2 + line_to_char_idx(chunk_2, line_idx + 1 - l2);
-                Some(self.slice(start..end))
-            }
-        } else {
-            None
-        }
+    /// Returns the first match of `regex` in the `Rope`, or `None` if there
+    /// isn't one.
+    ///
+    /// The regex is run directly over the `Rope`'s chunks, without copying
+    /// the text.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(N) time.
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex_find(&self, regex: &Regex) -> Option<RegexMatch> {
+        self.slice(..).regex_find(regex)
     }
 
-    /// Non-panicking version of [`chunk_at_byte()`](Rope::chunk_at_byte).
+    /// Returns the last match of `regex` in the `Rope`, or `None` if there
+    /// isn't one.
+    ///
+    /// See [`regex_rfind_iter()`](Rope::regex_rfind_iter) for how matches
+    /// are found when searching backward.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(N) time.
+    #[cfg(feature = "regex")]
     #[inline]
-    pub fn get_chunk_at_byte(&self, byte_idx: usize) -> Option<(&str, usize, usize, usize)> {
-        // Bounds check
-        if byte_idx <= self.len_bytes() {
-            let (chunk, info) = self.root.get_chunk_at_byte(byte_idx);
-            Some((
-                chunk,
-                info.bytes as usize,
-                info.chars as usize,
-                info.line_breaks as usize,
-            ))
-        } else {
-            None
-        }
+    pub fn regex_rfind(&self, regex: &Regex) -> Option<RegexMatch> {
+        self.slice(..).regex_rfind(regex)
     }
 
-    /// Non-panicking version of [`chunk_at_char()`](Rope::chunk_at_char).
+    /// Returns the first match of `regex` that starts at or after
+    /// `char_idx`, or `None` if there isn't one.
+    ///
+    /// The text before `char_idx` is still taken into account for
+    /// look-around assertions like `\b`.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(log N + K) time, where K is the length of the text
+    /// searched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "regex")]
     #[inline]
-    pub fn get_chunk_at_char(&self, char_idx: usize) -> Option<(&str, usize, usize, usize)> {
-        // Bounds check
-        if char_idx <= self.len_chars() {
-            let (chunk, info) = self.root.get_chunk_at_char(char_idx);
-            Some((
-                chunk,
-                info.bytes as usize,
-                info.chars as usize,
-                info.line_breaks as usize,
-            ))
-        } else {
-            None
-        }
+    pub fn regex_find_from(&self, char_idx: usize, regex: &Regex) -> Option<RegexMatch> {
+        self.try_regex_find_from(char_idx, regex).unwrap()
     }
 
-    /// Non-panicking version of [`chunk_at_line_break()`](Rope::chunk_at_line_break).
-    #[inline]
-    pub fn get_chunk_at_line_break(
-        &self,
-        line_break_idx: usize,
-    ) -> Option<(&str, usize, usize, usize)> {
-        // Bounds check
-        if line_break_idx <= self.len_lines() {
-            let (chunk, info) = self.root.get_chunk_at_line_break(line_break_idx);
-            Some((
-                chunk,
-                info.bytes as usize,
-                info.chars as usize,
-                info.line_breaks as usize,
-            ))
-        } else {
-            None
-        }
+    /// Returns the match of `regex` found by searching backward from
+    /// `char_idx`, or `None` if there isn't one.
+    ///
+    /// The match ends at or before `char_idx`.  See
+    /// [`regex_rfind_iter()`](Rope::regex_rfind_iter) for how matches are
+    /// found when searching backward.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(log N + K) time, where K is the length of the text
+    /// searched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex_rfind_from(&self, char_idx: usize, regex: &Regex) -> Option<RegexMatch> {
+        self.try_regex_rfind_from(char_idx, regex).unwrap()
     }
 
-    /// Non-panicking version of [`slice()`](Rope::slice).
+    /// Creates an iterator over the non-overlapping matches of `regex` in
+    /// the `Rope`, from front to back.
+    ///
+    /// Matches are found with the regex's usual leftmost-first semantics,
+    /// and empty matches never split a char.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(1) time.
+    #[cfg(feature = "regex")]
     #[inline]
-    pub fn get_slice<R>(&self, char_range: R) -> Option<RopeSlice<'_>>
-    where
-        R: RangeBounds<usize>,
-    {
-        let start = start_bound_to_num(char_range.start_bound()).unwrap_or(0);
-        let end = end_bound_to_num(char_range.end_bound()).unwrap_or_else(|| self.len_chars());
+    pub fn regex_find_iter<'r>(&self, regex: &'r Regex) -> RegexMatches<'_, 'r> {
+        self.slice(..).regex_find_iter(regex)
+    }
 
-        // Bounds check
-        if start <= end && end <= self.len_chars() {
-            Some(RopeSlice::new_with_range(&self.root, start, end))
-        } else {
-            None
-        }
+    /// Creates an iterator over the non-overlapping matches of `regex` in
+    /// the `Rope`, from back to front.
+    ///
+    /// Searching backward finds the match that starts the furthest back
+    /// among those that start as late as possible: for example, `\w+` finds
+    /// `"world"` rather than `"d"` at the end of `"hello world"`.  This can
+    /// find different matches than a forward search.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(1) time.
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex_rfind_iter<'r>(&self, regex: &'r Regex) -> RegexMatches<'_, 'r> {
+        self.slice(..).regex_rfind_iter(regex)
     }
 
-    /// Non-panicking version of [`byte_slice()`](Rope::byte_slice).
+    //-----------------------------------------------------------------------
+    // Multi-pattern search methods
+
+    /// Creates an iterator over the non-overlapping matches of the patterns
+    /// of an Aho-Corasick `automaton` in the `Rope`.
+    ///
+    /// Which matches are reported (e.g. leftmost-longest) is determined by
+    /// the automaton's match kind.  See the
+    /// [`multi_search`](crate::multi_search) module for details.
+    ///
+    /// Only available with the `multi_search` feature.
+    ///
+    /// Runs in O(log N) time.
+    #[cfg(feature = "multi_search")]
     #[inline]
-    pub fn get_byte_slice<R>(&self, byte_range: R) -> Option<RopeSlice<'_>>
-    where
-        R: RangeBounds<usize>,
-    {
-        self.get_byte_slice_impl(byte_range).ok()
+    pub fn multi_find_iter<'r, A: Automaton>(&self, automaton: &'r A) -> MultiMatches<'_, 'r, A> {
+        self.slice(..).multi_find_iter(automaton)
     }
 
-    pub(crate) fn get_byte_slice_impl<R>(&self, byte_range: R) -> Result<RopeSlice<'_>>
-    where
-        R: RangeBounds<usize>,
-    {
-        let start_range = start_bound_to_num(byte_range.start_bound());
-        let end_range = end_bound_to_num(byte_range.end_bound());
+    /// Creates an iterator over all matches of the patterns of an
+    /// Aho-Corasick `automaton` in the `Rope`, including overlapping ones.
+    ///
+    /// Only available with the `multi_search` feature.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the automaton's match kind isn't `MatchKind::Standard`.
+    #[cfg(feature = "multi_search")]
+    #[inline]
+    pub fn multi_find_overlapping_iter<'r, A: Automaton>(
+        &self,
+        automaton: &'r A,
+    ) -> OverlappingMultiMatches<'_, 'r, A> {
+        self.slice(..).multi_find_overlapping_iter(automaton)
+    }
 
-        // Bounds checks.
-        match (start_range, end_range) {
-            (Some(s), Some(e)) => {
-                if s > e {
-                    return Err(Error::ByteRangeInvalid(s, e));
-                } else if e > self.len_bytes() {
-                    return Err(Error::ByteRangeOutOfBounds(
-                        start_range,
-                        end_range,
-                        self.len_bytes(),
-                    ));
-                }
-            }
-            (Some(s), None) => {
-                if s > self.len_bytes() {
-                    return Err(Error::ByteRangeOutOfBounds(
-                        start_range,
-                        end_range,
-                        self.len_bytes(),
-                    ));
-                }
-            }
-            (None, Some(e)) => {
-                if e > self.len_bytes() {
-                    return Err(Error::ByteRangeOutOfBounds(
-                        start_range,
-                        end_range,
-                        self.len_bytes(),
-                    ));
-                }
-            }
-            _ => {}
-        }
+    //-----------------------------------------------------------------------
+    // Debugging
 
-        let (start, end) = (
-            start_range.unwrap_or(0),
-            end_range.unwrap_or_else(|| self.len_bytes()),
-        );
+    /// NOT PART OF THE PUBLIC API (hidden from docs for a reason!)
+    ///
+    /// Debugging tool to make sure that all of the meta-data of the
+    /// tree is consistent with the actual data.
+    #[doc(hidden)]
+    pub fn assert_integrity(&self) {
+        self.root.assert_integrity();
+    }
 
-        RopeSlice::new_with_byte_range(&self.root, start, end).map_err(|e| {
-            if let Error::ByteRangeNotCharBoundary(_, _) = e {
-                Error::ByteRangeNotCharBoundary(start_range, end_range)
-            } else {
-                e
-            }
-        })
+    /// NOT PART OF THE PUBLIC API (hidden from docs for a reason!)
+    ///
+    /// Debugging tool to make sure that all of the following invariants
+    /// hold true throughout the tree:
+    ///
+    /// - The tree is the same height everywhere.
+    /// - All internal nodes have the minimum number of children.
+    /// - All leaf nodes are non-empty.
+    /// - CRLF pairs are never split over chunk boundaries.
+    #[doc(hidden)]
+    pub fn assert_invariants(&self) {
+        self.root.assert_balance();
+        self.root.assert_node_size(true);
+        self.assert_crlf_seams();
     }
 
-    /// Non-panicking version of [`bytes_at()`](Rope::bytes_at).
-    #[inline]
-    pub fn get_bytes_at(&self, byte_idx: usize) -> Option<Bytes<'_>> {
-        // Bounds check
-        if byte_idx <= self.len_bytes() {
-            let info = self.root.text_info();
-            Some(Bytes::new_with_range_at(
-                &self.root,
-                byte_idx,
-                (0, info.bytes as usize),
-                (0, info.chars as usize),
-                (0, info.line_breaks as usize + 1),
-            ))
-        } else {
-            None
+    /// Checks that CRLF pairs are never split over chunk boundaries.
+    fn assert_crlf_seams(&self) {
+        if self.chunks().count() > 0 {
+            let mut itr = self.chunks();
+            let mut last_chunk = itr.next().unwrap();
+            for chunk in itr {
+                if !chunk.is_empty() && !last_chunk.is_empty() {
+                    assert!(crlf::seam_is_break(last_chunk.as_bytes(), chunk.as_bytes()));
+                    last_chunk = chunk;
+                } else if last_chunk.is_empty() {
+                    last_chunk = chunk;
+                }
+            }
         }
     }
 
-    /// Non-panicking version of [`chars_at()`](Rope::chars_at).
-    #[inline]
-    pub fn get_chars_at(&self, char_idx: usize) -> Option<Chars<'_>> {
-        // Bounds check
-        if char_idx <= self.len_chars() {
-            let info = self.root.text_info();
-            Some(Chars::new_with_range_at(
-                &self.root,
-                char_idx,
-                (0, info.bytes as usize),
-                (0, info.chars as usize),
-                (0, info.line_breaks as usize + 1),
-            ))
-        } else {
-            None
+    //-----------------------------------------------------------------------
+    // Internal utilities
+
+    /// Private internal-only method that applies a batch of sorted,
+    /// non-overlapping edits in a single pass over the tree.
+    ///
+    /// Only the nodes on the paths to the edited text are modified, so the
+    /// rest of the tree stays shared with any clones of the `Rope`.
+    fn edit_ranges(
+        &mut self,
+        edits: &[RangeEdit],
+        metric: EditMetric,
+    ) -> std::result::Result<(), NotCharBoundary> {
+        if edits.is_empty() {
+            return Ok(());
         }
-    }
 
-    /// Non-panicking version of [`lines_at()`](Rope::lines_at).
-    #[inline]
-    pub fn get_lines_at(&self, line_idx: usize) -> Option<Lines<'_>> {
-        // Bounds check
-        if line_idx <= self.len_lines() {
-            Some(Lines::new_with_range_at(
-                &self.root,
-                line_idx,
-                (0, self.len_bytes()),
-                (0, self.len_lines()),
-            ))
-        } else {
-            None
+        let root_info = self.root.text_info();
+        let (info, siblings) =
+            Arc::make_mut(&mut self.root).edit_ranges(edits, 0, metric, root_info)?;
+
+        if !siblings.is_empty() {
+            let mut nodes = Vec::with_capacity(siblings.len() + 1);
+            nodes.push((info, Arc::clone(&self.root)));
+            nodes.extend(siblings);
+            self.root = Node::from_siblings(nodes);
+        } else if info.bytes == 0 {
+            self.root = Arc::new(Node::new());
         }
+
+        self.pull_up_singular_nodes();
+        Ok(())
     }
 
-    /// Non-panicking version of [`chunks_at_byte()`](Rope::chunks_at_byte).
-    #[inline]
-    pub fn get_chunks_at_byte(&self, byte_idx: usize) -> Option<(Chunks<'_>, usize, usize, usize)> {
-        // Bounds check
-        if byte_idx <= self.len_bytes() {
-            Some(Chunks::new_with_range_at_byte(
-                &self.root,
-                byte_idx,
-                (0, self.len_bytes()),
-                (0, self.len_chars()),
-                (0, self.len_lines()),
-            ))
+    /// Private internal-only method that splits the `Rope` at `idx` (in
+    /// `metric` units), returning the right part of the split.
+    ///
+    /// `idx` must be in bounds.
+    fn split_off_internal(
+        &mut self,
+        idx: usize,
+        metric: EditMetric,
+    ) -> std::result::Result<Self, NotCharBoundary> {
+        if idx == 0 {
+            // Special case 1
+            let mut new_rope = Rope::new();
+            std::mem::swap(self, &mut new_rope);
+            Ok(new_rope)
+        } else if idx == metric.of(self.root.text_info()) {
+            // Special case 2
+            Ok(Rope::new())
         } else {
-            None
+            // Do the split
+            let mut new_rope = Rope {
+                root: Arc::new(Arc::make_mut(&mut self.root).split(idx, metric)?),
+            };
+
+            // Fix up the edges
+            Arc::make_mut(&mut self.root).zip_fix_right();
+            Arc::make_mut(&mut new_rope.root).zip_fix_left();
+            self.pull_up_singular_nodes();
+            new_rope.pull_up_singular_nodes();
+
+            Ok(new_rope)
         }
     }
 
-    /// Non-panicking version of [`chunks_at_char()`](Rope::chunks_at_char).
-    #[inline]
-    pub fn get_chunks_at_char(&self, char_idx: usize) -> Option<(Chunks<'_>, usize, usize, usize)> {
-        // Bounds check
-        if char_idx <= self.len_chars() {
-            Some(Chunks::new_with_range_at_char(
-                &self.root,
-                char_idx,
-                (0, self.len_bytes()),
-                (0, self.len_chars()),
-                (0, self.len_lines()),
-            ))
-        } else {
-            None
+    /// Iteratively replaces the root node with its child if it only has
+    /// one child.
+    pub(crate) fn pull_up_singular_nodes(&mut self) {
+        while (!self.root.is_leaf()) && self.root.child_count() == 1 {
+            let child = if let Node::Internal(ref children) = *self.root {
+                Arc::clone(&children.nodes()[0])
+            } else {
+                unreachable!()
+            };
+
+            self.root = child;
         }
     }
+}
 
-    /// Non-panicking version of [`chunks_at_line_break()`](Rope::chunks_at_line_break).
+/// # Non-Panicking
+///
+/// The methods in this impl block provide non-panicking versions of
+/// `Rope`'s panicking methods.  They return either `Option::None` or
+/// `Result::Err()` when their panicking counterparts would have panicked.
+impl Rope {
+    /// Non-panicking version of [`insert()`](Rope::insert).
     #[inline]
-    pub fn get_chunks_at_line_break(
-        &self,
-        line_break_idx: usize,
-    ) -> Option<(Chunks<'_>, usize, usize, usize)> {
+    pub fn try_insert(&mut self, char_idx: usize, text: &str) -> Result<()> {
         // Bounds check
-        if line_break_idx <= self.len_lines() {
-            Some(Chunks::new_with_range_at_line_break(
-                &self.root,
-                line_break_idx,
-                (0, self.len_bytes()),
-                (0, self.len_chars()),
-                (0, self.len_lines()),
+        if char_idx <= self.len_chars() {
+            // We have three cases here:
+            // 1. The insertion text is very large, in which case building a new
+            //    Rope out of it and splicing it into the existing Rope is most
+            //    efficient.
+            // 2. The insertion text is somewhat large, in which case splitting it
+            //    up into chunks and repeatedly inserting them is the most
+            //    efficient.  The splitting is necessary because the insertion code
+            //    only works correctly below a certain insertion size.
+            // 3. The insertion text is small, in which case we can simply insert
+            //    it.
+            //
+            // Cases #2 and #3 are rolled into one case here, where case #3 just
+            // results in the text being "split" into only one chunk.
+            //
+            // The boundary for what constitutes "very large" text was arrived at
+            // experimentally, by testing at what point Rope build + splice becomes
+            // faster than split + repeated insert.  This constant is likely worth
+            // revisiting from time to time as Ropey evolves.
+            if text.len() > MAX_BYTES * 6 {
+                // Case #1: very large text, build rope and splice it in.
+                let text_rope = Rope::from_str(text);
+                let right = self.split_off(char_idx);
+                self.append(text_rope);
+                self.append(right);
+            } else {
+                // Cases #2 and #3: split into chunks and repeatedly insert.
+                let mut text = text;
+                while !text.is_empty() {
+                    // Split a chunk off from the end of the text.
+                    // We do this from the end instead of the front so that
+                    // the repeated insertions can keep re-using the same
+                    // insertion point.
+                    let split_idx = crlf::find_good_split(
+                        text.len() - (MAX_BYTES - 4).min(text.len()),
+                        text.as_bytes(),
+                        false,
+                    );
+                    let ins_text = &text[split_idx..];
+                    text = &text[..split_idx];
+
+                    // Do the insertion.
+                    self.insert_internal(char_idx, ins_text);
+                }
+            }
+            Ok(())
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`insert_char()`](Rope::insert_char).
+    #[inline]
+    pub fn try_insert_char(&mut self, char_idx: usize, ch: char) -> Result<()> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            let mut buf = [0u8; 4];
+            self.insert_internal(char_idx, ch.encode_utf8(&mut buf));
+            Ok(())
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`insert_rope()`](Rope::insert_rope).
+    pub fn try_insert_rope(&mut self, char_idx: usize, rope: Rope) -> Result<()> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            if rope.root.is_leaf() {
+                // Small enough that it's faster to just insert the text.
+                self.insert(char_idx, rope.root.leaf_text());
+            } else {
+                let right = self.split_off(char_idx);
+                self.append(rope);
+                self.append(right);
+            }
+            Ok(())
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`insert_slice()`](Rope::insert_slice).
+    #[inline]
+    pub fn try_insert_slice(&mut self, char_idx: usize, slice: RopeSlice) -> Result<()> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            if let Some(text) = slice.as_str() {
+                self.insert(char_idx, text);
+                Ok(())
+            } else {
+                self.try_insert_rope(char_idx, Rope::from(slice))
+            }
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`remove()`](Rope::remove).
+    pub fn try_remove<R>(&mut self, char_range: R) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start_opt = start_bound_to_num(char_range.start_bound());
+        let end_opt = end_bound_to_num(char_range.end_bound());
+        let start = start_opt.unwrap_or(0);
+        let end = end_opt.unwrap_or_else(|| self.len_chars());
+        if end.max(start) > self.len_chars() {
+            Err(Error::CharRangeOutOfBounds(
+                start_opt,
+                end_opt,
+                self.len_chars(),
             ))
+        } else if start > end {
+            Err(Error::CharRangeInvalid(start, end))
         } else {
-            None
+            // A special case that the rest of the logic doesn't handle
+            // correctly.
+            if start == 0 && end == self.len_chars() {
+                self.root = Arc::new(Node::new());
+                return Ok(());
+            }
+
+            let root = Arc::make_mut(&mut self.root);
+
+            let root_info = root.text_info();
+            let (_, crlf_seam, needs_fix) = root.remove_char_range(start, end, root_info);
+
+            if crlf_seam {
+                let seam_idx = root.char_to_text_info(start).bytes;
+                root.fix_crlf_seam(seam_idx as Count, false);
+            }
+
+            if needs_fix {
+                root.fix_tree_seam(start);
+            }
+
+            self.pull_up_singular_nodes();
+            Ok(())
         }
     }
-}
 
-//==============================================================
-// Conversion impls
+    /// Non-panicking version of [`replace()`](Rope::replace).
+    pub fn try_replace<R>(&mut self, char_range: R, text: &str) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start_opt = start_bound_to_num(char_range.start_bound());
+        let end_opt = end_bound_to_num(char_range.end_bound());
+        let start = start_opt.unwrap_or(0);
+        let end = end_opt.unwrap_or_else(|| self.len_chars());
+        if end.max(start) > self.len_chars() {
+            Err(Error::CharRangeOutOfBounds(
+                start_opt,
+                end_opt,
+                self.len_chars(),
+            ))
+        } else if start > end {
+            Err(Error::CharRangeInvalid(start, end))
+        } else {
+            if start < end || !text.is_empty() {
+                let edit = RangeEdit { start, end, text };
+                self.edit_ranges(&[edit], EditMetric::Chars).unwrap();
+            }
+            Ok(())
+        }
+    }
 
-impl<'a> From<&'a str> for Rope {
+    /// Non-panicking version of [`split_off()`](Rope::split_off).
+    pub fn try_split_off(&mut self, char_idx: usize) -> Result<Self> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            Ok(self
+                .split_off_internal(char_idx, EditMetric::Chars)
+                .unwrap())
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`move_range()`](Rope::move_range).
+    pub fn try_move_range<R>(&mut self, char_range: R, dest_char_idx: usize) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start_opt = start_bound_to_num(char_range.start_bound());
+        let end_opt = end_bound_to_num(char_range.end_bound());
+        let start = start_opt.unwrap_or(0);
+        let end = end_opt.unwrap_or_else(|| self.len_chars());
+        if end.max(start) > self.len_chars() {
+            Err(Error::CharRangeOutOfBounds(
+                start_opt,
+                end_opt,
+                self.len_chars(),
+            ))
+        } else if start > end {
+            Err(Error::CharRangeInvalid(start, end))
+        } else if dest_char_idx > self.len_chars() {
+            Err(Error::CharIndexOutOfBounds(dest_char_idx, self.len_chars()))
+        } else if dest_char_idx > start && dest_char_idx < end {
+            Err(Error::CharIndexInsideMovedRange(dest_char_idx, start, end))
+        } else {
+            if dest_char_idx < start {
+                // [..dest][dest..start][start..end][end..]
+                let right = self.split_off(end);
+                let moved = self.split_off(start);
+                let middle = self.split_off(dest_char_idx);
+                self.append(moved);
+                self.append(middle);
+                self.append(right);
+            } else if dest_char_idx > end {
+                // [..start][start..end][end..dest][dest..]
+                let right = self.split_off(dest_char_idx);
+                let middle = self.split_off(end);
+                let moved = self.split_off(start);
+                self.append(middle);
+                self.append(moved);
+                self.append(right);
+            }
+            Ok(())
+        }
+    }
+
+    /// Non-panicking version of [`duplicate_range()`](Rope::duplicate_range).
+    pub fn try_duplicate_range<R>(&mut self, char_range: R, dest_char_idx: usize) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start_opt = start_bound_to_num(char_range.start_bound());
+        let end_opt = end_bound_to_num(char_range.end_bound());
+        let start = start_opt.unwrap_or(0);
+        let end = end_opt.unwrap_or_else(|| self.len_chars());
+        if end.max(start) > self.len_chars() {
+            Err(Error::CharRangeOutOfBounds(
+                start_opt,
+                end_opt,
+                self.len_chars(),
+            ))
+        } else if start > end {
+            Err(Error::CharRangeInvalid(start, end))
+        } else if dest_char_idx > self.len_chars() {
+            Err(Error::CharIndexOutOfBounds(dest_char_idx, self.len_chars()))
+        } else {
+            let copy = Rope::from(self.slice(start..end));
+            self.insert_rope(dest_char_idx, copy);
+            Ok(())
+        }
+    }
+
+    /// Non-panicking version of [`apply_edits()`](Rope::apply_edits).
+    ///
+    /// On error the `Rope` is left unmodified.
+    pub fn try_apply_edits<'t, I, R>(&mut self, edits: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (R, &'t str)>,
+        R: RangeBounds<usize>,
+    {
+        // Validate all of the edits up-front, so that we never leave the
+        // rope partially edited.
+        let mut edits_checked: Vec<(usize, usize, &str)> = Vec::new();
+        for (char_range, text) in edits {
+            let start_opt = start_bound_to_num(char_range.start_bound());
+            let end_opt = end_bound_to_num(char_range.end_bound());
+            let start = start_opt.unwrap_or(0);
+            let end = end_opt.unwrap_or_else(|| self.len_chars());
+            if end.max(start) > self.len_chars() {
+                return Err(Error::CharRangeOutOfBounds(
+                    start_opt,
+                    end_opt,
+                    self.len_chars(),
+                ));
+            } else if start > end {
+                return Err(Error::CharRangeInvalid(start, end));
+            }
+            edits_checked.push((start, end, text));
+        }
+
+        // Sort by position.  The sort is stable, so multiple insertions at
+        // the same index keep their given order.
+        edits_checked.sort_by_key(|&(start, end, _)| (start, end));
+        for pair in edits_checked.windows(2) {
+            if pair[0].1 > pair[1].0 {
+                return Err(Error::CharRangeOverlap(pair[1].0, pair[1].1));
+            }
+        }
+
+        let edits_checked: Vec<RangeEdit> = edits_checked
+            .into_iter()
+            .filter(|&(start, end, text)| start < end || !text.is_empty())
+            .map(|(start, end, text)| RangeEdit { start, end, text })
+            .collect();
+        self.edit_ranges(&edits_checked, EditMetric::Chars).unwrap();
+
+        Ok(())
+    }
+
+    /// Non-panicking version of [`insert_at_byte()`](Rope::insert_at_byte).
     #[inline]
-    fn from(text: &'a str) -> Self {
-        Rope::from_str(text)
+    pub fn try_insert_at_byte(&mut self, byte_idx: usize, text: &str) -> Result<()> {
+        // Bounds check
+        if byte_idx <= self.len_bytes() {
+            let edit = RangeEdit {
+                start: byte_idx,
+                end: byte_idx,
+                text,
+            };
+            self.edit_ranges(&[edit], EditMetric::Bytes)
+                .map_err(|_| Error::ByteIndexNotCharBoundary(byte_idx))
+        } else {
+            Err(Error::ByteIndexOutOfBounds(byte_idx, self.len_bytes()))
+        }
+    }
+
+    /// Non-panicking version of [`insert_char_at_byte()`](Rope::insert_char_at_byte).
+    #[inline]
+    pub fn try_insert_char_at_byte(&mut self, byte_idx: usize, ch: char) -> Result<()> {
+        let mut buf = [0u8; 4];
+        self.try_insert_at_byte(byte_idx, ch.encode_utf8(&mut buf))
+    }
+
+    /// Non-panicking version of [`remove_byte_range()`](Rope::remove_byte_range).
+    pub fn try_remove_byte_range<R>(&mut self, byte_range: R) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start_opt = start_bound_to_num(byte_range.start_bound());
+        let end_opt = end_bound_to_num(byte_range.end_bound());
+        let start = start_opt.unwrap_or(0);
+        let end = end_opt.unwrap_or_else(|| self.len_bytes());
+        if end.max(start) > self.len_bytes() {
+            Err(Error::ByteRangeOutOfBounds(
+                start_opt,
+                end_opt,
+                self.len_bytes(),
+            ))
+        } else if start > end {
+            Err(Error::ByteRangeInvalid(start, end))
+        } else {
+            let edit = RangeEdit {
+                start,
+                end,
+                text: "",
+            };
+            self.edit_ranges(&[edit], EditMetric::Bytes)
+                .map_err(|_| Error::ByteRangeNotCharBoundary(start_opt, end_opt))
+        }
+    }
+
+    /// Non-panicking version of [`split_off_at_byte()`](Rope::split_off_at_byte).
+    pub fn try_split_off_at_byte(&mut self, byte_idx: usize) -> Result<Self> {
+        // Bounds check
+        if byte_idx <= self.len_bytes() {
+            self.split_off_internal(byte_idx, EditMetric::Bytes)
+                .map_err(|_| Error::ByteIndexNotCharBoundary(byte_idx))
+        } else {
+            Err(Error::ByteIndexOutOfBounds(byte_idx, self.len_bytes()))
+        }
+    }
+
+    /// Non-panicking version of [`byte_to_char()`](Rope::byte_to_char).
+    #[inline]
+    pub fn try_byte_to_char(&self, byte_idx: usize) -> Result<usize> {
+        // Bounds check
+        if byte_idx <= self.len_bytes() {
+            let (chunk, b, c, _) = self.chunk_at_byte(byte_idx);
+            Ok(c + byte_to_char_idx(chunk, byte_idx - b))
+        } else {
+            Err(Error::ByteIndexOutOfBounds(byte_idx, self.len_bytes()))
+        }
+    }
+
+    /// Non-panicking version of [`byte_to_line()`](Rope::byte_to_line).
+    #[inline]
+    pub fn try_byte_to_line(&self, byte_idx: usize) -> Result<usize> {
+        // Bounds check
+        if byte_idx <= self.len_bytes() {
+            let (chunk, b, _, l) = self.chunk_at_byte(byte_idx);
+            Ok(l + byte_to_line_idx(chunk, byte_idx - b))
+        } else {
+            Err(Error::ByteIndexOutOfBounds(byte_idx, self.len_bytes()))
+        }
+    }
+
+    /// Non-panicking version of [`char_to_byte()`](Rope::char_to_byte).
+    #[inline]
+    pub fn try_char_to_byte(&self, char_idx: usize) -> Result<usize> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            let (chunk, b, c, _) = self.chunk_at_char(char_idx);
+            Ok(b + char_to_byte_idx(chunk, char_idx - c))
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`char_to_line()`](Rope::char_to_line).
+    #[inline]
+    pub fn try_char_to_line(&self, char_idx: usize) -> Result<usize> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            let (chunk, _, c, l) = self.chunk_at_char(char_idx);
+            Ok(l + char_to_line_idx(chunk, char_idx - c))
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`char_to_utf16_cu()`](Rope::char_to_utf16_cu).
+    #[inline]
+    pub fn try_char_to_utf16_cu(&self, char_idx: usize) -> Result<usize> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            let (chunk, chunk_start_info) = self.root.get_chunk_at_char(char_idx);
+            let chunk_byte_idx =
+                char_to_byte_idx(chunk, char_idx - chunk_start_info.chars as usize);
+            let surrogate_count = byte_to_utf16_surrogate_idx(chunk, chunk_byte_idx);
+
+            Ok(char_idx + chunk_start_info.utf16_surrogates as usize + surrogate_count)
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`utf16_cu_to_char()`](Rope::utf16_cu_to_char).
+    #[inline]
+    pub fn try_utf16_cu_to_char(&self, utf16_cu_idx: usize) -> Result<usize> {
+        // Bounds check
+        if utf16_cu_idx <= self.len_utf16_cu() {
+            let (chunk, chunk_start_info) = self.root.get_chunk_at_utf16_code_unit(utf16_cu_idx);
+            let chunk_utf16_cu_idx = utf16_cu_idx
+                - (chunk_start_info.chars + chunk_start_info.utf16_surrogates) as usize;
+            let chunk_char_idx = utf16_code_unit_to_char_idx(chunk, chunk_utf16_cu_idx);
+
+            Ok(chunk_start_info.chars as usize + chunk_char_idx)
+        } else {
+            Err(Error::Utf16IndexOutOfBounds(
+                utf16_cu_idx,
+                self.len_utf16_cu(),
+            ))
+        }
+    }
+
+    /// Non-panicking version of [`char_to_line_col()`](Rope::char_to_line_col).
+    #[inline]
+    pub fn try_char_to_line_col(
+        &self,
+        char_idx: usize,
+        encoding: PositionEncoding,
+    ) -> Result<LineCol> {
+        self.slice(..).try_char_to_line_col(char_idx, encoding)
+    }
+
+    /// Non-panicking version of [`line_col_to_char()`](Rope::line_col_to_char).
+    #[inline]
+    pub fn try_line_col_to_char(&self, pos: LineCol, encoding: PositionEncoding) -> Result<usize> {
+        self.slice(..).try_line_col_to_char(pos, encoding)
+    }
+
+    /// Non-panicking version of [`line_to_byte()`](Rope::line_to_byte).
+    #[inline]
+    pub fn try_line_to_byte(&self, line_idx: usize) -> Result<usize> {
+        // Bounds check
+        if line_idx <= self.len_lines() {
+            if line_idx == self.len_lines() {
+                Ok(self.len_bytes())
+            } else {
+                let (chunk, b, _, l) = self.chunk_at_line_break(line_idx);
+                Ok(b + line_to_byte_idx(chunk, line_idx - l))
+            }
+        } else {
+            Err(Error::LineIndexOutOfBounds(line_idx, self.len_lines()))
+        }
+    }
+
+    /// Non-panicking version of [`line_to_char()`](Rope::line_to_char).
+    #[inline]
+    pub fn try_line_to_char(&self, line_idx: usize) -> Result<usize> {
+        // Bounds check
+        if line_idx <= self.len_lines() {
+            if line_idx == self.len_lines() {
+                Ok(self.len_chars())
+            } else {
+                let (chunk, _, c, l) = self.chunk_at_line_break(line_idx);
+                Ok(c + line_to_char_idx(chunk, line_idx - l))
+            }
+        } else {
+            Err(Error::LineIndexOutOfBounds(line_idx, self.len_lines()))
+        }
+    }
+
+    /// Non-panicking version of [`byte()`](Rope::byte).
+    #[inline]
+    pub fn get_byte(&self, byte_idx: usize) -> Option<u8> {
+        // Bounds check
+        if byte_idx < self.len_bytes() {
+            let (chunk, chunk_byte_idx, _, _) = self.chunk_at_byte(byte_idx);
+            let chunk_rel_byte_idx = byte_idx - chunk_byte_idx;
+            Some(chunk.as_bytes()[chunk_rel_byte_idx])
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`char()`](Rope::char).
+    #[inline]
+    pub fn get_char(&self, char_idx: usize) -> Option<char> {
+        // Bounds check
+        if char_idx < self.len_chars() {
+            let (chunk, _, chunk_char_idx, _) = self.chunk_at_char(char_idx);
+            let byte_idx = char_to_byte_idx(chunk, char_idx - chunk_char_idx);
+            Some(chunk[byte_idx..].chars().next().unwrap())
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`line()`](Rope::line).
+    #[inline]
+    pub fn get_line(&self, line_idx: usize) -> Option<RopeSlice<'_>> {
+        use crate::slice::RSEnum;
+        use crate::str_utils::{count_chars, count_utf16_surrogates};
+
+        let len_lines = self.len_lines();
+
+        // Bounds check
+        if line_idx < len_lines {
+            let (chunk_1, _, c1, l1) = self.chunk_at_line_break(line_idx);
+            let (chunk_2, _, c2, l2) = self.chunk_at_line_break(line_idx + 1);
+            if c1 == c2 {
+                let text1 = &chunk_1[line_to_byte_idx(chunk_1, line_idx - l1)..];
+                let text2 = &text1[..line_to_byte_idx(text1, 1)];
+                Some(RopeSlice(RSEnum::Light {
+                    text: text2,
+                    char_count: count_chars(text2) as Count,
+                    utf16_surrogate_count: count_utf16_surrogates(text2) as Count,
+                    line_break_count: if line_idx == (len_lines - 1) { 0 } else { 1 },
+                }))
+            } else {
+                let start = c1 + line_to_char_idx(chunk_1, line_idx - l1);
+                let end = c2 + line_to_char_idx(chunk_2, line_idx + 1 - l2);
+                Some(self.slice(start..end))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`chunk_at_byte()`](Rope::chunk_at_byte).
+    #[inline]
+    pub fn get_chunk_at_byte(&self, byte_idx: usize) -> Option<(&str, usize, usize, usize)> {
+        // Bounds check
+        if byte_idx <= self.len_bytes() {
+            let (chunk, info) = self.root.get_chunk_at_byte(byte_idx);
+            Some((
+                chunk,
+                info.bytes as usize,
+                info.chars as usize,
+                info.line_breaks as usize,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`chunk_at_char()`](Rope::chunk_at_char).
+    #[inline]
+    pub fn get_chunk_at_char(&self, char_idx: usize) -> Option<(&str, usize, usize, usize)> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            let (chunk, info) = self.root.get_chunk_at_char(char_idx);
+            Some((
+                chunk,
+                info.bytes as usize,
+                info.chars as usize,
+                info.line_breaks as usize,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`chunk_at_line_break()`](Rope::chunk_at_line_break).
+    #[inline]
+    pub fn get_chunk_at_line_break(
+        &self,
+        line_break_idx: usize,
+    ) -> Option<(&str, usize, usize, usize)> {
+        // Bounds check
+        if line_break_idx <= self.len_lines() {
+            let (chunk, info) = self.root.get_chunk_at_line_break(line_break_idx);
+            Some((
+                chunk,
+                info.bytes as usize,
+                info.chars as usize,
+                info.line_breaks as usize,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`slice()`](Rope::slice).
+    #[inline]
+    pub fn get_slice<R>(&self, char_range: R) -> Option<RopeSlice<'_>>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = start_bound_to_num(char_range.start_bound()).unwrap_or(0);
+        let end = end_bound_to_num(char_range.end_bound()).unwrap_or_else(|| self.len_chars());
+
+        // Bounds check
+        if start <= end && end <= self.len_chars() {
+            Some(RopeSlice::new_with_range(&self.root, start, end))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`byte_slice()`](Rope::byte_slice).
+    #[inline]
+    pub fn get_byte_slice<R>(&self, byte_range: R) -> Option<RopeSlice<'_>>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.get_byte_slice_impl(byte_range).ok()
+    }
+
+    pub(crate) fn get_byte_slice_impl<R>(&self, byte_range: R) -> Result<RopeSlice<'_>>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start_range = start_bound_to_num(byte_range.start_bound());
+        let end_range = end_bound_to_num(byte_range.end_bound());
+
+        // Bounds checks.
+        match (start_range, end_range) {
+            (Some(s), Some(e)) => {
+                if s > e {
+                    return Err(Error::ByteRangeInvalid(s, e));
+                } else if e > self.len_bytes() {
+                    return Err(Error::ByteRangeOutOfBounds(
+                        start_range,
+                        end_range,
+                        self.len_bytes(),
+                    ));
+                }
+            }
+            (Some(s), None) => {
+                if s > self.len_bytes() {
+                    return Err(Error::ByteRangeOutOfBounds(
+                        start_range,
+                        end_range,
+                        self.len_bytes(),
+                    ));
+                }
+            }
+            (None, Some(e)) => {
+                if e > self.len_bytes() {
+                    return Err(Error::ByteRangeOutOfBounds(
+                        start_range,
+                        end_range,
+                        self.len_bytes(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        let (start, end) = (
+            start_range.unwrap_or(0),
+            end_range.unwrap_or_else(|| self.len_bytes()),
+        );
+
+        RopeSlice::new_with_byte_range(&self.root, start, end).map_err(|e| {
+            if let Error::ByteRangeNotCharBoundary(_, _) = e {
+                Error::ByteRangeNotCharBoundary(start_range, end_range)
+            } else {
+                e
+            }
+        })
+    }
+
+    /// Non-panicking version of [`bytes_at()`](Rope::bytes_at).
+    #[inline]
+    pub fn get_bytes_at(&self, byte_idx: usize) -> Option<Bytes<'_>> {
+        // Bounds check
+        if byte_idx <= self.len_bytes() {
+            let info = self.root.text_info();
+            Some(Bytes::new_with_range_at(
+                &self.root,
+                byte_idx,
+                (0, info.bytes as usize),
+                (0, info.chars as usize),
+                (0, info.line_breaks as usize + 1),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`chars_at()`](Rope::chars_at).
+    #[inline]
+    pub fn get_chars_at(&self, char_idx: usize) -> Option<Chars<'_>> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            let info = self.root.text_info();
+            Some(Chars::new_with_range_at(
+                &self.root,
+                char_idx,
+                (0, info.bytes as usize),
+                (0, info.chars as usize),
+                (0, info.line_breaks as usize + 1),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`char_indices_at()`](Rope::char_indices_at).
+    #[inline]
+    pub fn get_char_indices_at(&self, char_idx: usize) -> Option<CharIndices<'_>> {
+        let chars = self.get_chars_at(char_idx)?;
+        Some(CharIndices::new(
+            chars,
+            char_idx,
+            self.char_to_byte(char_idx),
+        ))
+    }
+
+    /// Non-panicking version of [`lines_at()`](Rope::lines_at).
+    #[inline]
+    pub fn get_lines_at(&self, line_idx: usize) -> Option<Lines<'_>> {
+        // Bounds check
+        if line_idx <= self.len_lines() {
+            Some(Lines::new_with_range_at(
+                &self.root,
+                line_idx,
+                (0, self.len_bytes()),
+                (0, self.len_lines()),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`lines_without_endings_at()`](Rope::lines_without_endings_at).
+    #[inline]
+    pub fn get_lines_without_endings_at(&self, line_idx: usize) -> Option<LinesWithoutEndings<'_>> {
+        self.get_lines_at(line_idx).map(LinesWithoutEndings::new)
+    }
+
+    /// Non-panicking version of [`line_infos_at()`](Rope::line_infos_at).
+    #[inline]
+    pub fn get_line_infos_at(&self, line_idx: usize) -> Option<LineInfos<'_>> {
+        let lines = self.get_lines_at(line_idx)?;
+        Some(LineInfos::new(
+            lines,
+            self.line_to_char(line_idx),
+            self.line_to_byte(line_idx),
+        ))
+    }
+
+    /// Non-panicking version of [`chunks_at_byte()`](Rope::chunks_at_byte).
+    #[inline]
+    pub fn get_chunks_at_byte(&self, byte_idx: usize) -> Option<(Chunks<'_>, usize, usize, usize)> {
+        // Bounds check
+        if byte_idx <= self.len_bytes() {
+            Some(Chunks::new_with_range_at_byte(
+                &self.root,
+                byte_idx,
+                (0, self.len_bytes()),
+                (0, self.len_chars()),
+                (0, self.len_lines()),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`chunks_at_char()`](Rope::chunks_at_char).
+    #[inline]
+    pub fn get_chunks_at_char(&self, char_idx: usize) -> Option<(Chunks<'_>, usize, usize, usize)> {
+        // Bounds check
+        if char_idx <= self.len_chars() {
+            Some(Chunks::new_with_range_at_char(
+                &self.root,
+                char_idx,
+                (0, self.len_bytes()),
+                (0, self.len_chars()),
+                (0, self.len_lines()),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`chunks_at_line_break()`](Rope::chunks_at_line_break).
+    #[inline]
+    pub fn get_chunks_at_line_break(
+        &self,
+        line_break_idx: usize,
+    ) -> Option<(Chunks<'_>, usize, usize, usize)> {
+        // Bounds check
+        if line_break_idx <= self.len_lines() {
+            Some(Chunks::new_with_range_at_line_break(
+                &self.root,
+                line_break_idx,
+                (0, self.len_bytes()),
+                (0, self.len_chars()),
+                (0, self.len_lines()),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Non-panicking version of [`chunk_indices_at_byte()`](Rope::chunk_indices_at_byte).
+    #[inline]
+    pub fn get_chunk_indices_at_byte(&self, byte_idx: usize) -> Option<ChunkIndices<'_>> {
+        let (chunks, byte_idx, char_idx, line_idx) = self.get_chunks_at_byte(byte_idx)?;
+        Some(ChunkIndices::new(chunks, byte_idx, char_idx, line_idx))
+    }
+
+    /// Non-panicking version of [`chunk_indices_at_char()`](Rope::chunk_indices_at_char).
+    #[inline]
+    pub fn get_chunk_indices_at_char(&self, char_idx: usize) -> Option<ChunkIndices<'_>> {
+        let (chunks, byte_idx, char_idx, line_idx) = self.get_chunks_at_char(char_idx)?;
+        Some(ChunkIndices::new(chunks, byte_idx, char_idx, line_idx))
+    }
+
+    /// Non-panicking version of [`chunk_indices_at_line_break()`](Rope::chunk_indices_at_line_break).
+    #[inline]
+    pub fn get_chunk_indices_at_line_break(
+        &self,
+        line_break_idx: usize,
+    ) -> Option<ChunkIndices<'_>> {
+        let (chunks, byte_idx, char_idx, line_idx) =
+            self.get_chunks_at_line_break(line_break_idx)?;
+        Some(ChunkIndices::new(chunks, byte_idx, char_idx, line_idx))
+    }
+
+    /// Non-panicking version of [`is_grapheme_boundary()`](Rope::is_grapheme_boundary).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn try_is_grapheme_boundary(&self, char_idx: usize) -> Result<bool> {
+        self.slice(..).try_is_grapheme_boundary(char_idx)
+    }
+
+    /// Non-panicking version of [`prev_grapheme_boundary()`](Rope::prev_grapheme_boundary).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn try_prev_grapheme_boundary(&self, char_idx: usize) -> Result<usize> {
+        self.slice(..).try_prev_grapheme_boundary(char_idx)
+    }
+
+    /// Non-panicking version of [`next_grapheme_boundary()`](Rope::next_grapheme_boundary).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn try_next_grapheme_boundary(&self, char_idx: usize) -> Result<usize> {
+        self.slice(..).try_next_grapheme_boundary(char_idx)
+    }
+
+    /// Non-panicking version of [`find_from()`](Rope::find_from).
+    #[inline]
+    pub fn try_find_from(&self, char_idx: usize, pattern: &str) -> Result<Option<usize>> {
+        self.slice(..).try_find_from(char_idx, pattern)
+    }
+
+    /// Non-panicking version of [`rfind_from()`](Rope::rfind_from).
+    #[inline]
+    pub fn try_rfind_from(&self, char_idx: usize, pattern: &str) -> Result<Option<usize>> {
+        self.slice(..).try_rfind_from(char_idx, pattern)
+    }
+
+    /// Non-panicking version of [`find_from_ignore_case()`](Rope::find_from_ignore_case).
+    #[inline]
+    pub fn try_find_from_ignore_case(
+        &self,
+        char_idx: usize,
+        pattern: &str,
+    ) -> Result<Option<Range<usize>>> {
+        self.slice(..).try_find_from_ignore_case(char_idx, pattern)
+    }
+
+    /// Non-panicking version of [`rfind_from_ignore_case()`](Rope::rfind_from_ignore_case).
+    #[inline]
+    pub fn try_rfind_from_ignore_case(
+        &self,
+        char_idx: usize,
+        pattern: &str,
+    ) -> Result<Option<Range<usize>>> {
+        self.slice(..).try_rfind_from_ignore_case(char_idx, pattern)
+    }
+
+    /// Non-panicking version of [`regex_find_from()`](Rope::regex_find_from).
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn try_regex_find_from(
+        &self,
+        char_idx: usize,
+        regex: &Regex,
+    ) -> Result<Option<RegexMatch>> {
+        self.slice(..).try_regex_find_from(char_idx, regex)
+    }
+
+    /// Non-panicking version of [`regex_rfind_from()`](Rope::regex_rfind_from).
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn try_regex_rfind_from(
+        &self,
+        char_idx: usize,
+        regex: &Regex,
+    ) -> Result<Option<RegexMatch>> {
+        self.slice(..).try_regex_rfind_from(char_idx, regex)
+    }
+}
+
+//==============================================================
+// Conversion impls
+
+impl<'a> From<&'a str> for Rope {
+    #[inline]
+    fn from(text: &'a str) -> Self {
+        Rope::from_str(text)
+    }
+}
+
+impl<'a> From<std::borrow::Cow<'a, str>> for Rope {
+    #[inline]
+    fn from(text: std::borrow::Cow<'a, str>) -> Self {
+        Rope::from_str(&text)
+    }
+}
+
+impl From<String> for Rope {
+    #[inline]
+    fn from(text: String) -> Self {
+        Rope::from_str(&text)
+    }
+}
+
+/// Will share data where possible.
+///
+/// The resulting `Rope` shares all chunks and subtrees that are fully
+/// contained in the slice with the `Rope` the slice was taken from.  Only
+/// the partial chunks at the slice's edges and the nodes along the paths to
+/// them are copied.
+///
+/// Runs in O(log N) time.
+impl<'a> From<RopeSlice<'a>> for Rope {
+    fn from(s: RopeSlice<'a>) -> Self {
+        use crate::slice::RSEnum;
+        match s {
+            RopeSlice(RSEnum::Full {
+                node,
+                start_info,
+                end_info,
+            }) => {
+                let mut rope = Rope {
+                    root: Arc::clone(node),
+                };
+
+                // Chop off right end if needed
+                if end_info.chars < node.text_info().chars {
+                    {
+                        let root = Arc::make_mut(&mut rope.root);
+                        root.split(end_info.chars as usize, EditMetric::Chars)
+                            .unwrap();
+                        root.zip_fix_right();
+                    }
+                    rope.pull_up_singular_nodes();
+                }
+
+                // Chop off left end if needed
+                if start_info.chars > 0 {
+                    {
+                        let root = Arc::make_mut(&mut rope.root);
+                        *root = root
+                            .split(start_info.chars as usize, EditMetric::Chars)
+                            .unwrap();
+                        root.zip_fix_left();
+                    }
+                    rope.pull_up_singular_nodes();
+                }
+
+                // Return the rope
+                rope
+            }
+            RopeSlice(RSEnum::Light { text, .. }) => Rope::from_str(text),
+        }
+    }
+}
+
+impl From<Rope> for String {
+    #[inline]
+    fn from(r: Rope) -> Self {
+        String::from(&r)
+    }
+}
+
+impl<'a> From<&'a Rope> for String {
+    #[inline]
+    fn from(r: &'a Rope) -> Self {
+        let mut text = String::with_capacity(r.len_bytes());
+        text.extend(r.chunks());
+        text
+    }
+}
+
+impl<'a> From<Rope> for std::borrow::Cow<'a, str> {
+    #[inline]
+    fn from(r: Rope) -> Self {
+        std::borrow::Cow::Owned(String::from(r))
+    }
+}
+
+/// Attempts to borrow the contents of the `Rope`, but will convert to an
+/// owned string if the contents is not contiguous in memory.
+///
+/// Runs in best case O(1), worst case O(N).
+impl<'a> From<&'a Rope> for std::borrow::Cow<'a, str> {
+    #[inline]
+    fn from(r: &'a Rope) -> Self {
+        if let Node::Leaf(ref text) = *r.root {
+            std::borrow::Cow::Borrowed(text)
+        } else {
+            std::borrow::Cow::Owned(String::from(r))
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Rope {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = &'a str>,
+    {
+        let mut builder = RopeBuilder::new();
+        for chunk in iter {
+            builder.append(chunk);
+        }
+        builder.finish()
+    }
+}
+
+impl<'a> FromIterator<std::borrow::Cow<'a, str>> for Rope {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = std::borrow::Cow<'a, str>>,
+    {
+        let mut builder = RopeBuilder::new();
+        for chunk in iter {
+            builder.append(&chunk);
+        }
+        builder.finish()
+    }
+}
+
+impl FromIterator<String> for Rope {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = String>,
+    {
+        let mut builder = RopeBuilder::new();
+        for chunk in iter {
+            builder.append(&chunk);
+        }
+        builder.finish()
+    }
+}
+
+//==============================================================
+// Other impls
+
+impl std::fmt::Debug for Rope {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.chunks()).finish()
+    }
+}
+
+impl std::fmt::Display for Rope {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for chunk in self.chunks() {
+            write!(f, "{}", chunk)?
+        }
+        Ok(())
+    }
+}
+
+impl std::default::Default for Rope {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::cmp::Eq for Rope {}
+
+impl std::cmp::PartialEq<Rope> for Rope {
+    #[inline]
+    fn eq(&self, other: &Rope) -> bool {
+        self.slice(..) == other.slice(..)
+    }
+}
+
+impl<'a> std::cmp::PartialEq<&'a str> for Rope {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.slice(..) == *other
+    }
+}
+
+impl<'a> std::cmp::PartialEq<Rope> for &'a str {
+    #[inline]
+    fn eq(&self, other: &Rope) -> bool {
+        *self == other.slice(..)
+    }
+}
+
+impl std::cmp::PartialEq<str> for Rope {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.slice(..) == other
+    }
+}
+
+impl std::cmp::PartialEq<Rope> for str {
+    #[inline]
+    fn eq(&self, other: &Rope) -> bool {
+        self == other.slice(..)
+    }
+}
+
+impl std::cmp::PartialEq<String> for Rope {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        self.slice(..) == other.as_str()
+    }
+}
+
+impl std::cmp::PartialEq<Rope> for String {
+    #[inline]
+    fn eq(&self, other: &Rope) -> bool {
+        self.as_str() == other.slice(..)
+    }
+}
+
+impl<'a> std::cmp::PartialEq<std::borrow::Cow<'a, str>> for Rope {
+    #[inline]
+    fn eq(&self, other: &std::borrow::Cow<'a, str>) -> bool {
+        self.slice(..) == **other
+    }
+}
+
+impl<'a> std::cmp::PartialEq<Rope> for std::borrow::Cow<'a, str> {
+    #[inline]
+    fn eq(&self, other: &Rope) -> bool {
+        **self == other.slice(..)
+    }
+}
+
+impl std::cmp::Ord for Rope {
+    #[inline]
+    fn cmp(&self, other: &Rope) -> std::cmp::Ordering {
+        self.slice(..).cmp(&other.slice(..))
+    }
+}
+
+impl std::cmp::PartialOrd<Rope> for Rope {
+    #[inline]
+    fn partial_cmp(&self, other: &Rope) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::hash::Hash for Rope {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.slice(..).hash(state)
+    }
+}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::str_utils::*;
+    use std::hash::{Hash, Hasher};
+
+    // 127 bytes, 103 chars, 1 line
+    const TEXT: &str = "Hello there!  How're you doing?  It's \
+                        a fine day, isn't it?  Aren't you glad \
+                        we're alive?  こんにちは、みんなさん！";
+    // 124 bytes, 100 chars, 4 lines
+    const TEXT_LINES: &str = "Hello there!  How're you doing?\nIt's \
+                              a fine day, isn't it?\nAren't you glad \
+                              we're alive?\nこんにちは、みんなさん！";
+    // 127 bytes, 107 chars, 111 utf16 code units, 1 line
+    const TEXT_EMOJI: &str = "Hello there!🐸  How're you doing?🐸  It's \
+                              a fine day, isn't it?🐸  Aren't you glad \
+                              we're alive?🐸  こんにちは、みんなさん！";
+
+    #[test]
+    fn new_01() {
+        let r = Rope::new();
+        assert_eq!(r, "");
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn from_str() {
+        let r = Rope::from_str(TEXT);
+        assert_eq!(r, TEXT);
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn len_bytes_01() {
+        let r = Rope::from_str(TEXT);
+        assert_eq!(r.len_bytes(), 127);
+    }
+
+    #[test]
+    fn len_bytes_02() {
+        let r = Rope::from_str("");
+        assert_eq!(r.len_bytes(), 0);
+    }
+
+    #[test]
+    fn len_chars_01() {
+        let r = Rope::from_str(TEXT);
+        assert_eq!(r.len_chars(), 103);
+    }
+
+    #[test]
+    fn len_chars_02() {
+        let r = Rope::from_str("");
+        assert_eq!(r.len_chars(), 0);
+    }
+
+    #[test]
+    fn len_lines_01() {
+        let r = Rope::from_str(TEXT_LINES);
+        assert_eq!(r.len_lines(), 4);
+    }
+
+    #[test]
+    fn len_lines_02() {
+        let r = Rope::from_str("");
+        assert_eq!(r.len_lines(), 1);
+    }
+
+    #[test]
+    fn len_utf16_cu_01() {
+        let r = Rope::from_str(TEXT);
+        assert_eq!(r.len_utf16_cu(), 103);
+    }
+
+    #[test]
+    fn len_utf16_cu_02() {
+        let r = Rope::from_str(TEXT_EMOJI);
+        assert_eq!(r.len_utf16_cu(), 111);
+    }
+
+    #[test]
+    fn len_utf16_cu_03() {
+        let r = Rope::from_str("");
+        assert_eq!(r.len_utf16_cu(), 0);
+    }
+
+    #[test]
+    fn insert_01() {
+        let mut r = Rope::from_str(TEXT);
+        r.insert(3, "AA");
+
+        assert_eq!(
+            r,
+            "HelAAlo there!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！"
+        );
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn insert_02() {
+        let mut r = Rope::from_str(TEXT);
+        r.insert(0, "AA");
+
+        assert_eq!(
+            r,
+            "AAHello there!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！"
+        );
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn insert_03() {
+        let mut r = Rope::from_str(TEXT);
+        r.insert(103, "AA");
+
+        assert_eq!(
+            r,
+            "Hello there!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！AA"
+        );
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn insert_04() {
+        let mut r = Rope::new();
+        r.insert(0, "He");
+        r.insert(2, "l");
+        r.insert(3, "l");
+        r.insert(4, "o w");
+        r.insert(7, "o");
+        r.insert(8, "rl");
+        r.insert(10, "d!");
+        r.insert(3, "zopter");
+
+        assert_eq!("Helzopterlo world!", r);
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn insert_05() {
+        let mut r = Rope::new();
+        r.insert(0, "こんいちは、みんなさん！");
+        r.insert(7, "zopter");
+        assert_eq!("こんいちは、みzopterんなさん！", r);
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn insert_06() {
+        let mut r = Rope::new();
+        r.insert(0, "こ");
+        r.insert(1, "ん");
+        r.insert(2, "い");
+        r.insert(3, "ち");
+        r.insert(4, "は");
+        r.insert(5, "、");
+        r.insert(6, "み");
+        r.insert(7, "ん");
+        r.insert(8, "な");
+        r.insert(9, "さ");
+        r.insert(10, "ん");
+        r.insert(11, "！");
+        r.insert(7, "zopter");
+        assert_eq!("こんいちは、みzopterんなさん！", r);
+
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-impl<'a> From<std::borrow::Cow<'a, str>> for Rope {
-    #[inline]
-    fn from(text: std::borrow::Cow<'a, str>) -> Self {
-        Rope::from_str(&text)
-    }
-}
+    #[test]
+    fn insert_char_01() {
+        let mut r = Rope::from_str(TEXT);
+        r.insert_char(3, 'A');
+        r.insert_char(12, '!');
+        r.insert_char(12, '!');
 
-impl From<String> for Rope {
-    #[inline]
-    fn from(text: String) -> Self {
-        Rope::from_str(&text)
-    }
-}
+        assert_eq!(
+            r,
+            "HelAlo there!!!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！"
+        );
 
-/// Will share data where possible.
-///
-/// Runs in O(log N) time.
-impl<'a> From<RopeSlice<'a>> for Rope {
-    fn from(s: RopeSlice<'a>) -> Self {
-        use crate::slice::RSEnum;
-        match s {
-            RopeSlice(RSEnum::Full {
-                node,
-                start_info,
-                end_info,
-            }) => {
-                let mut rope = Rope {
-                    root: Arc::clone(node),
-                };
+        r.assert_integrity();
+        r.assert_invariants();
+    }
 
-                // Chop off right end if needed
-                if end_info.chars < node.text_info().chars {
-                    {
-                        let root = Arc::make_mut(&mut rope.root);
-                        root.split(end_info.chars as usize);
-                        root.zip_fix_right();
-                    }
-                    rope.pull_up_singular_nodes();
-                }
+    #[test]
+    fn insert_char_02() {
+        let mut r = Rope::new();
 
-                // Chop off left end if needed
-                if start_info.chars > 0 {
-                    {
-                        let root = Arc::make_mut(&mut rope.root);
-                        *root = root.split(start_info.chars as usize);
-                        root.zip_fix_left();
-                    }
-                    rope.pull_up_singular_nodes();
-                }
+        r.insert_char(0, '！');
+        r.insert_char(0, 'こ');
+        r.insert_char(1, 'ん');
+        r.insert_char(2, 'い');
+        r.insert_char(3, 'ち');
+        r.insert_char(4, 'は');
+        r.insert_char(5, '、');
+        r.insert_char(6, 'み');
+        r.insert_char(7, 'ん');
+        r.insert_char(8, 'な');
+        r.insert_char(9, 'さ');
+        r.insert_char(10, 'ん');
+        assert_eq!("こんいちは、みんなさん！", r);
 
-                // Return the rope
-                rope
-            }
-            RopeSlice(RSEnum::Light { text, .. }) => Rope::from_str(text),
-        }
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-impl From<Rope> for String {
-    #[inline]
-    fn from(r: Rope) -> Self {
-        String::from(&r)
-    }
-}
+    #[test]
+    fn insert_rope_01() {
+        let mut r = Rope::from_str(TEXT);
+        r.insert_rope(3, Rope::from_str(TEXT));
+        r.insert_rope(0, Rope::from_str("AA"));
+        r.insert_rope(208, Rope::new());
+
+        let mut s = String::from("AA");
+        s.push_str(&TEXT[..3]);
+        s.push_str(TEXT);
+        s.push_str(&TEXT[3..]);
+        assert_eq!(r, s);
 
-impl<'a> From<&'a Rope> for String {
-    #[inline]
-    fn from(r: &'a Rope) -> Self {
-        let mut text = String::with_capacity(r.len_bytes());
-        text.extend(r.chunks());
-        text
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-impl<'a> From<Rope> for std::borrow::Cow<'a, str> {
-    #[inline]
-    fn from(r: Rope) -> Self {
-        std::borrow::Cow::Owned(String::from(r))
-    }
-}
+    #[test]
+    fn insert_rope_02() {
+        let mut r = Rope::from_str("\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
+        let r2 = Rope::from_str("\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
 
-/// Attempts to borrow the contents of the `Rope`, but will convert to an
-/// owned string if the contents is not contiguous in memory.
-///
-/// Runs in best case O(1), worst case O(N).
-impl<'a> From<&'a Rope> for std::borrow::Cow<'a, str> {
-    #[inline]
-    fn from(r: &'a Rope) -> Self {
-        if let Node::Leaf(ref text) = *r.root {
-            std::borrow::Cow::Borrowed(text)
-        } else {
-            std::borrow::Cow::Owned(String::from(r))
-        }
-    }
-}
+        // Make sure CRLF pairs get merged properly, via
+        // assert_invariants() below.
+        r.insert_rope(25, r2.clone());
+        r.insert_rope(2, r2);
+        assert_eq!(r, "\r\n\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
 
-impl<'a> FromIterator<&'a str> for Rope {
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = &'a str>,
-    {
-        let mut builder = RopeBuilder::new();
-        for chunk in iter {
-            builder.append(chunk);
-        }
-        builder.finish()
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-impl<'a> FromIterator<std::borrow::Cow<'a, str>> for Rope {
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = std::borrow::Cow<'a, str>>,
-    {
-        let mut builder = RopeBuilder::new();
-        for chunk in iter {
-            builder.append(&chunk);
-        }
-        builder.finish()
+    #[test]
+    #[should_panic]
+    fn insert_rope_03() {
+        let mut r = Rope::from_str(TEXT);
+        r.insert_rope(104, Rope::from_str(TEXT)); // One past the end of the rope
     }
-}
 
-impl FromIterator<String> for Rope {
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = String>,
-    {
-        let mut builder = RopeBuilder::new();
-        for chunk in iter {
-            builder.append(&chunk);
-        }
-        builder.finish()
+    #[test]
+    fn insert_slice_01() {
+        let r2 = Rope::from_str(TEXT);
+        let mut r = Rope::from_str(TEXT);
+        r.insert_slice(50, r2.slice(12..97));
+        r.insert_slice(0, r2.slice(..5));
+        r.insert_slice(0, r2.slice(5..5));
+
+        let mut s = String::from(&TEXT[..5]);
+        s.push_str(&TEXT[..50]);
+        s.push_str(&r2.slice(12..97).to_string());
+        s.push_str(&TEXT[50..]);
+        assert_eq!(r, s);
+
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-//==============================================================
-// Other impls
+    #[test]
+    fn remove_01() {
+        let mut r = Rope::from_str(TEXT);
 
-impl std::fmt::Debug for Rope {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        f.debug_list().entries(self.chunks()).finish()
-    }
-}
+        r.remove(5..11);
+        r.remove(24..31);
+        r.remove(19..25);
+        r.remove(75..79);
+        assert_eq!(
+            r,
+            "Hello!  How're you \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにんなさん！"
+        );
 
-impl std::fmt::Display for Rope {
-    #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        for chunk in self.chunks() {
-            write!(f, "{}", chunk)?
-        }
-        Ok(())
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-impl std::default::Default for Rope {
-    #[inline]
-    fn default() -> Self {
-        Self::new()
-    }
-}
+    #[test]
+    fn remove_02() {
+        let mut r = Rope::from_str("\r\n\r\n\r\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n");
 
-impl std::cmp::Eq for Rope {}
+        // Make sure CRLF pairs get merged properly, via
+        // assert_invariants() below.
+        r.remove(3..6);
+        assert_eq!(r, "\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n");
 
-impl std::cmp::PartialEq<Rope> for Rope {
-    #[inline]
-    fn eq(&self, other: &Rope) -> bool {
-        self.slice(..) == other.slice(..)
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-impl<'a> std::cmp::PartialEq<&'a str> for Rope {
-    #[inline]
-    fn eq(&self, other: &&'a str) -> bool {
-        self.slice(..) == *other
-    }
-}
+    #[test]
+    fn remove_03() {
+        let mut r = Rope::from_str(TEXT);
 
-impl<'a> std::cmp::PartialEq<Rope> for &'a str {
-    #[inline]
-    fn eq(&self, other: &Rope) -> bool {
-        *self == other.slice(..)
-    }
-}
+        // Make sure removing nothing actually does nothing.
+        r.remove(45..45);
+        assert_eq!(r, TEXT);
 
-impl std::cmp::PartialEq<str> for Rope {
-    #[inline]
-    fn eq(&self, other: &str) -> bool {
-        self.slice(..) == other
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-impl std::cmp::PartialEq<Rope> for str {
-    #[inline]
-    fn eq(&self, other: &Rope) -> bool {
-        self == other.slice(..)
-    }
-}
+    #[test]
+    fn remove_04() {
+        let mut r = Rope::from_str(TEXT);
+
+        // Make sure removing everything works.
+        r.remove(0..103);
+        assert_eq!(r, "");
 
-impl std::cmp::PartialEq<String> for Rope {
-    #[inline]
-    fn eq(&self, other: &String) -> bool {
-        self.slice(..) == other.as_str()
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-impl std::cmp::PartialEq<Rope> for String {
-    #[inline]
-    fn eq(&self, other: &Rope) -> bool {
-        self.as_str() == other.slice(..)
+    #[test]
+    fn remove_05() {
+        let mut r = Rope::from_str(TEXT);
+
+        // Make sure removing a large range works.
+        r.remove(3..100);
+        assert_eq!(r, "Helさん！");
+
+        r.assert_integrity();
+        r.assert_invariants();
     }
-}
 
-impl<'a> std::cmp::PartialEq<std::borrow::Cow<'a, str>> for Rope {
-    #[inline]
-    fn eq(&self, other: &std::borrow::Cow<'a, str>) -> bool {
-        self.slice(..) == **other
+    #[test]
+    #[should_panic]
+    fn remove_06() {
+        let mut r = Rope::from_str(TEXT);
+        #[allow(clippy::reversed_empty_ranges)]
+        r.remove(56..55); // Wrong ordering of start/end on purpose.
     }
-}
 
-impl<'a> std::cmp::PartialEq<Rope> for std::borrow::Cow<'a, str> {
-    #[inline]
-    fn eq(&self, other: &Rope) -> bool {
-        **self == other.slice(..)
+    #[test]
+    #[should_panic]
+    fn remove_07() {
+        let mut r = Rope::from_str(TEXT);
+        r.remove(102..104); // Removing past the end
     }
-}
 
-impl std::cmp::Ord for Rope {
-    #[inline]
-    fn cmp(&self, other: &Rope) -> std::cmp::Ordering {
-        self.slice(..).cmp(&other.slice(..))
+    #[test]
+    #[should_panic]
+    fn remove_08() {
+        let mut r = Rope::from_str(TEXT);
+        r.remove(103..104); // Removing past the end
     }
-}
 
-impl std::cmp::PartialOrd<Rope> for Rope {
-    #[inline]
-    fn partial_cmp(&self, other: &Rope) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+    #[test]
+    #[should_panic]
+    fn remove_09() {
+        let mut r = Rope::from_str(TEXT);
+        r.remove(104..104); // Removing past the end
     }
-}
 
-impl std::hash::Hash for Rope {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.slice(..).hash(state)
+    #[test]
+    #[should_panic]
+    fn remove_10() {
+        let mut r = Rope::from_str(TEXT);
+        r.remove(104..105); // Removing past the end
     }
-}
 
-//==============================================================
+    #[test]
+    fn replace_01() {
+        let mut r = Rope::from_str(TEXT);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::str_utils::*;
-    use std::hash::{Hash, Hasher};
+        r.replace(6..11, "world");
+        r.replace(40..44, "great");
+        r.replace(0..0, "Oh, ");
+        r.replace(102..108, "");
+        assert_eq!(
+            r,
+            "Oh, Hello world!  How're you doing?  It's \
+             a great day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、"
+        );
 
-    // 127 bytes, 103 chars, 1 line
-    const TEXT: &str = "Hello there!  How're you doing?  It's \
-                        a fine day, isn't it?  Aren't you glad \
-                        we're alive?  こんにちは、みんなさん！";
-    // 124 bytes, 100 chars, 4 lines
-    const TEXT_LINES: &str = "Hello there!  How're you doing?\nIt's \
-                              a fine day, isn't it?\nAren't you glad \
-                              we're alive?\nこんにちは、みんなさん！";
-    // 127 bytes, 107 chars, 111 utf16 code units, 1 line
-    const TEXT_EMOJI: &str = "Hello there!🐸  How're you doing?🐸  It's \
-                              a fine day, isn't it?🐸  Aren't you glad \
-                              we're alive?🐸  こんにちは、みんなさん！";
+        r.assert_integrity();
+        r.assert_invariants();
+    }
 
     #[test]
-    fn new_01() {
-        let r = Rope::new();
-        assert_eq!(r, "");
+    fn replace_02() {
+        let mut r = Rope::from_str(TEXT);
+
+        // Make sure replacing a large range works.
+        r.replace(3..100, "p!  All is well in ");
+        assert_eq!(r, "Help!  All is well in さん！");
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn from_str() {
-        let r = Rope::from_str(TEXT);
-        assert_eq!(r, TEXT);
+    fn replace_03() {
+        let mut r = Rope::from_str(TEXT);
+
+        // Make sure replacing with a large text works.
+        r.replace(50..60, TEXT);
+        assert_eq!(r.len_chars(), 103 - 10 + 103);
+        assert_eq!(r.slice(50..153), TEXT);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn len_bytes_01() {
-        let r = Rope::from_str(TEXT);
-        assert_eq!(r.len_bytes(), 127);
+    fn replace_04() {
+        let mut r = Rope::from_str("\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n");
+
+        // Make sure CRLF pairs get merged properly, via
+        // assert_invariants() below.
+        r.replace(3..6, "\n\r");
+        r.replace(10..11, "\r");
+        r.replace(0..1, "\n");
+        assert_eq!(r, "\n\n\r\n\r\r\n\r\n\r\r\r\n\r\n\r\n\r\n\r\n\r\n");
+
+        r.assert_integrity();
+        r.assert_invariants();
     }
 
     #[test]
-    fn len_bytes_02() {
-        let r = Rope::from_str("");
-        assert_eq!(r.len_bytes(), 0);
+    fn replace_05() {
+        let mut r = Rope::from_str(TEXT);
+
+        // Make sure replacing everything works.
+        r.replace(.., "Hi!");
+        assert_eq!(r, "Hi!");
+        r.replace(.., "");
+        assert_eq!(r, "");
+
+        r.assert_integrity();
+        r.assert_invariants();
     }
 
     #[test]
-    fn len_chars_01() {
-        let r = Rope::from_str(TEXT);
-        assert_eq!(r.len_chars(), 103);
+    #[should_panic]
+    fn replace_06() {
+        let mut r = Rope::from_str(TEXT);
+        #[allow(clippy::reversed_empty_ranges)]
+        r.replace(56..55, "a"); // Wrong ordering of start/end on purpose.
     }
 
     #[test]
-    fn len_chars_02() {
-        let r = Rope::from_str("");
-        assert_eq!(r.len_chars(), 0);
+    #[should_panic]
+    fn replace_07() {
+        let mut r = Rope::from_str(TEXT);
+        r.replace(102..104, "a"); // Replacing past the end
     }
 
     #[test]
-    fn len_lines_01() {
-        let r = Rope::from_str(TEXT_LINES);
-        assert_eq!(r.len_lines(), 4);
+    fn replace_08() {
+        let text = TEXT.repeat(10);
+        let mut r = Rope::from_str(&text);
+        let r2 = r.clone();
+
+        // Replacing in a shared rope only copies the path to the edit.
+        r.replace(5..6, "A");
+        r.replace(300..700, "\r\nB\r\n");
+        assert_eq!(r2, text.as_str());
+        assert_eq!(
+            r.chunks().last().unwrap().as_ptr(),
+            r2.chunks().last().unwrap().as_ptr()
+        );
+        assert_eq!(r.len_chars(), 1030 - 400 + 5);
+        assert_eq!(r.slice(298..305), "んに\r\nB\r\n");
+
+        r.assert_integrity();
+        r.assert_invariants();
     }
 
     #[test]
-    fn len_lines_02() {
-        let r = Rope::from_str("");
-        assert_eq!(r.len_lines(), 1);
+    fn split_off_01() {
+        let mut r = Rope::from_str(TEXT);
+
+        let r2 = r.split_off(50);
+        assert_eq!(
+            r,
+            "Hello there!  How're you doing?  It's \
+             a fine day, "
+        );
+        assert_eq!(
+            r2,
+            "isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！"
+        );
+
+        r.assert_integrity();
+        r2.assert_integrity();
+        r.assert_invariants();
+        r2.assert_invariants();
     }
 
     #[test]
-    fn len_utf16_cu_01() {
-        let r = Rope::from_str(TEXT);
-        assert_eq!(r.len_utf16_cu(), 103);
+    fn split_off_02() {
+        let mut r = Rope::from_str(TEXT);
+
+        let r2 = r.split_off(1);
+        assert_eq!(r, "H");
+        assert_eq!(
+            r2,
+            "ello there!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！"
+        );
+
+        r.assert_integrity();
+        r2.assert_integrity();
+        r.assert_invariants();
+        r2.assert_invariants();
     }
 
     #[test]
-    fn len_utf16_cu_02() {
-        let r = Rope::from_str(TEXT_EMOJI);
-        assert_eq!(r.len_utf16_cu(), 111);
+    fn split_off_03() {
+        let mut r = Rope::from_str(TEXT);
+
+        let r2 = r.split_off(102);
+        assert_eq!(
+            r,
+            "Hello there!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん"
+        );
+        assert_eq!(r2, "！");
+
+        r.assert_integrity();
+        r2.assert_integrity();
+        r.assert_invariants();
+        r2.assert_invariants();
     }
 
     #[test]
-    fn len_utf16_cu_03() {
-        let r = Rope::from_str("");
-        assert_eq!(r.len_utf16_cu(), 0);
+    fn split_off_04() {
+        let mut r = Rope::from_str(TEXT);
+
+        let r2 = r.split_off(0);
+        assert_eq!(r, "");
+        assert_eq!(
+            r2,
+            "Hello there!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！"
+        );
+
+        r.assert_integrity();
+        r2.assert_integrity();
+        r.assert_invariants();
+        r2.assert_invariants();
     }
 
     #[test]
-    fn insert_01() {
+    fn split_off_05() {
         let mut r = Rope::from_str(TEXT);
-        r.insert(3, "AA");
 
+        let r2 = r.split_off(103);
         assert_eq!(
             r,
-            "HelAAlo there!  How're you doing?  It's \
+            "Hello there!  How're you doing?  It's \
              a fine day, isn't it?  Aren't you glad \
              we're alive?  こんにちは、みんなさん！"
         );
+        assert_eq!(r2, "");
+
+        r.assert_integrity();
+        r2.assert_integrity();
+        r.assert_invariants();
+        r2.assert_invariants();
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_off_06() {
+        let mut r = Rope::from_str(TEXT);
+        r.split_off(104); // One past the end of the rope
+    }
+
+    #[test]
+    fn append_01() {
+        let mut r = Rope::from_str(
+            "Hello there!  How're you doing?  It's \
+             a fine day, isn't ",
+        );
+        let r2 = Rope::from_str(
+            "it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！",
+        );
+
+        r.append(r2);
+        assert_eq!(r, TEXT);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn insert_02() {
-        let mut r = Rope::from_str(TEXT);
-        r.insert(0, "AA");
-
-        assert_eq!(
-            r,
-            "AAHello there!  How're you doing?  It's \
+    fn append_02() {
+        let mut r = Rope::from_str(
+            "Hello there!  How're you doing?  It's \
              a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん！"
+             we're alive?  こんに",
         );
+        let r2 = Rope::from_str("ちは、みんなさん！");
+
+        r.append(r2);
+        assert_eq!(r, TEXT);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn insert_03() {
-        let mut r = Rope::from_str(TEXT);
-        r.insert(103, "AA");
-
-        assert_eq!(
-            r,
+    fn append_03() {
+        let mut r = Rope::from_str(
             "Hello there!  How're you doing?  It's \
              a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん！AA"
+             we're alive?  こんにちは、みんなさん",
         );
+        let r2 = Rope::from_str("！");
+
+        r.append(r2);
+        assert_eq!(r, TEXT);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn insert_04() {
-        let mut r = Rope::new();
-        r.insert(0, "He");
-        r.insert(2, "l");
-        r.insert(3, "l");
-        r.insert(4, "o w");
-        r.insert(7, "o");
-        r.insert(8, "rl");
-        r.insert(10, "d!");
-        r.insert(3, "zopter");
+    fn append_04() {
+        let mut r = Rope::from_str("H");
+        let r2 = Rope::from_str(
+            "ello there!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！",
+        );
 
-        assert_eq!("Helzopterlo world!", r);
+        r.append(r2);
+        assert_eq!(r, TEXT);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn insert_05() {
-        let mut r = Rope::new();
-        r.insert(0, "こんいちは、みんなさん！");
-        r.insert(7, "zopter");
-        assert_eq!("こんいちは、みzopterんなさん！", r);
-
-        r.assert_integrity();
-        r.assert_invariants();
-    }
+    fn append_05() {
+        let mut r = Rope::from_str(TEXT);
+        let r2 = Rope::from_str("");
 
-    #[test]
-    fn insert_06() {
-        let mut r = Rope::new();
-        r.insert(0, "こ");
-        r.insert(1, "ん");
-        r.insert(2, "い");
-        r.insert(3, "ち");
-        r.insert(4, "は");
-        r.insert(5, "、");
-        r.insert(6, "み");
-        r.insert(7, "ん");
-        r.insert(8, "な");
-        r.insert(9, "さ");
-        r.insert(10, "ん");
-        r.insert(11, "！");
-        r.insert(7, "zopter");
-        assert_eq!("こんいちは、みzopterんなさん！", r);
+        r.append(r2);
+        assert_eq!(r, TEXT);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn insert_char_01() {
-        let mut r = Rope::from_str(TEXT);
-        r.insert_char(3, 'A');
-        r.insert_char(12, '!');
-        r.insert_char(12, '!');
+    fn append_06() {
+        let mut r = Rope::from_str("");
+        let r2 = Rope::from_str(TEXT);
 
-        assert_eq!(
-            r,
-            "HelAlo there!!!  How're you doing?  It's \
-             a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん！"
-        );
+        r.append(r2);
+        assert_eq!(r, TEXT);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn insert_char_02() {
-        let mut r = Rope::new();
+    fn append_07() {
+        let mut r = Rope::from_str("\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
+        let r2 = Rope::from_str("\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
 
-        r.insert_char(0, '！');
-        r.insert_char(0, 'こ');
-        r.insert_char(1, 'ん');
-        r.insert_char(2, 'い');
-        r.insert_char(3, 'ち');
-        r.insert_char(4, 'は');
-        r.insert_char(5, '、');
-        r.insert_char(6, 'み');
-        r.insert_char(7, 'ん');
-        r.insert_char(8, 'な');
-        r.insert_char(9, 'さ');
-        r.insert_char(10, 'ん');
-        assert_eq!("こんいちは、みんなさん！", r);
+        r.append(r2);
+        let s2 = r.to_string();
+        assert_eq!(r, "\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
+        assert_eq!(r.len_lines(), byte_to_line_idx(&s2, s2.len()) + 1);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn remove_01() {
+    fn move_range_01() {
         let mut r = Rope::from_str(TEXT);
 
-        r.remove(5..11);
-        r.remove(24..31);
-        r.remove(19..25);
-        r.remove(75..79);
+        r.move_range(0..13, 103);
+        r.move_range(77..90, 0);
+        r.move_range(20..30, 20);
+        r.move_range(20..30, 30);
         assert_eq!(
             r,
-            "Hello!  How're you \
+            " こんにちは、みんなさん！ How're you doing?  It's \
              a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにんなさん！"
+             we're alive? Hello there! "
         );
 
         r.assert_integrity();
@@ -2329,297 +4477,307 @@ mod tests {
     }
 
     #[test]
-    fn remove_02() {
-        let mut r = Rope::from_str("\r\n\r\n\r\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n");
+    fn move_range_02() {
+        let mut r = Rope::from_str("\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n");
 
         // Make sure CRLF pairs get merged properly, via
         // assert_invariants() below.
-        r.remove(3..6);
-        assert_eq!(r, "\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n");
+        r.move_range(3..9, 0);
+        r.move_range(1..2, 22);
+        assert_eq!(r, "\n\n\r\n\r\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn remove_03() {
+    fn move_range_03() {
         let mut r = Rope::from_str(TEXT);
 
-        // Make sure removing nothing actually does nothing.
-        r.remove(45..45);
+        assert_eq!(
+            r.try_move_range(10..20, 15).unwrap_err().to_string(),
+            Error::CharIndexInsideMovedRange(15, 10, 20).to_string(),
+        );
+        assert!(r.try_move_range(10..20, 104).is_err()); // Destination past the end
+        assert!(r.try_move_range(100..104, 0).is_err()); // Range past the end
         assert_eq!(r, TEXT);
-
-        r.assert_integrity();
-        r.assert_invariants();
-    }
-
-    #[test]
-    fn remove_04() {
-        let mut r = Rope::from_str(TEXT);
-
-        // Make sure removing everything works.
-        r.remove(0..103);
-        assert_eq!(r, "");
-
-        r.assert_integrity();
-        r.assert_invariants();
     }
 
     #[test]
-    fn remove_05() {
+    fn duplicate_range_01() {
         let mut r = Rope::from_str(TEXT);
 
-        // Make sure removing a large range works.
-        r.remove(3..100);
-        assert_eq!(r, "Helさん！");
+        r.duplicate_range(91.., 0);
+        r.duplicate_range(15..20, 17);
+        r.duplicate_range(0..0, 5);
+        assert_eq!(
+            r,
+            "こんにちは、みんなさん！Hellolo th there!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちは、みんなさん！"
+        );
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    #[should_panic]
-    fn remove_06() {
+    fn duplicate_range_02() {
         let mut r = Rope::from_str(TEXT);
-        #[allow(clippy::reversed_empty_ranges)]
-        r.remove(56..55); // Wrong ordering of start/end on purpose.
-    }
 
-    #[test]
-    #[should_panic]
-    fn remove_07() {
-        let mut r = Rope::from_str(TEXT);
-        r.remove(102..104); // Removing past the end
+        assert!(r.try_duplicate_range(10..20, 104).is_err()); // Destination past the end
+        assert!(r.try_duplicate_range(100..104, 0).is_err()); // Range past the end
+        assert_eq!(r, TEXT);
     }
 
     #[test]
-    #[should_panic]
-    fn remove_08() {
+    fn apply_edits_01() {
         let mut r = Rope::from_str(TEXT);
-        r.remove(103..104); // Removing past the end
-    }
 
-    #[test]
-    #[should_panic]
-    fn remove_09() {
-        let mut r = Rope::from_str(TEXT);
-        r.remove(104..104); // Removing past the end
-    }
+        r.apply_edits([(0..5, "Goodbye"), (95..97, ""), (38..38, "truly ")]);
+        assert_eq!(
+            r,
+            "Goodbye there!  How're you doing?  It's \
+             truly a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんにちみんなさん！"
+        );
 
-    #[test]
-    #[should_panic]
-    fn remove_10() {
-        let mut r = Rope::from_str(TEXT);
-        r.remove(104..105); // Removing past the end
+        r.assert_integrity();
+        r.assert_invariants();
     }
 
     #[test]
-    fn split_off_01() {
+    fn apply_edits_02() {
         let mut r = Rope::from_str(TEXT);
 
-        let r2 = r.split_off(50);
+        // Insertions at the same index are applied in the order given, and
+        // before a range that starts at that index.
+        r.apply_edits([(6..11, "world"), (6..6, "big "), (6..6, "wide ")]);
         assert_eq!(
             r,
-            "Hello there!  How're you doing?  It's \
-             a fine day, "
-        );
-        assert_eq!(
-            r2,
-            "isn't it?  Aren't you glad \
+            "Hello big wide world!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
              we're alive?  こんにちは、みんなさん！"
         );
 
         r.assert_integrity();
-        r2.assert_integrity();
         r.assert_invariants();
-        r2.assert_invariants();
     }
 
     #[test]
-    fn split_off_02() {
-        let mut r = Rope::from_str(TEXT);
+    fn apply_edits_03() {
+        let mut r = Rope::from_str("\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n");
 
-        let r2 = r.split_off(1);
-        assert_eq!(r, "H");
-        assert_eq!(
-            r2,
-            "ello there!  How're you doing?  It's \
-             a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん！"
-        );
+        // Make sure CRLF pairs get merged properly, via
+        // assert_invariants() below.
+        r.apply_edits([(1..3, ""), (5..5, "\r"), (7..8, "\n")]);
+        assert_eq!(r, "\r\n\r\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n");
 
         r.assert_integrity();
-        r2.assert_integrity();
         r.assert_invariants();
-        r2.assert_invariants();
     }
 
     #[test]
-    fn split_off_03() {
+    fn apply_edits_04() {
         let mut r = Rope::from_str(TEXT);
 
-        let r2 = r.split_off(102);
-        assert_eq!(
-            r,
-            "Hello there!  How're you doing?  It's \
-             a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん"
-        );
-        assert_eq!(r2, "！");
+        // Nothing should be applied if any edit is invalid.
+        assert!(r.try_apply_edits([(0..5, "Hi"), (100..104, "")]).is_err());
+        #[allow(clippy::reversed_empty_ranges)]
+        let res = r.try_apply_edits([(0..5, "Hi"), (56..55, "")]);
+        assert!(res.is_err());
+        assert!(r.try_apply_edits([(0..5, "Hi"), (4..6, "")]).is_err());
+        assert!(r.try_apply_edits([(0..5, "Hi"), (3..3, "")]).is_err());
+        assert_eq!(r, TEXT);
 
         r.assert_integrity();
-        r2.assert_integrity();
         r.assert_invariants();
-        r2.assert_invariants();
     }
 
     #[test]
-    fn split_off_04() {
+    #[should_panic]
+    fn apply_edits_05() {
         let mut r = Rope::from_str(TEXT);
+        r.apply_edits([(10..20, "a"), (15..25, "b")]); // Overlapping ranges
+    }
 
-        let r2 = r.split_off(0);
-        assert_eq!(r, "");
-        assert_eq!(
-            r2,
-            "Hello there!  How're you doing?  It's \
-             a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん！"
-        );
+    #[test]
+    fn apply_edits_06() {
+        let text = TEXT.repeat(20);
+        let mut r = Rope::from_str(&text);
+
+        // Edits spanning many leaves, and ones big enough to split leaves.
+        let big = "\r\n".repeat(100);
+        r.apply_edits([
+            (10..1500, "a"),
+            (1600..1600, big.as_str()),
+            (1700..1702, "b"),
+            (1702..2000, ""),
+        ]);
+
+        let mut expected = String::new();
+        expected.push_str(&text[..char_to_byte_idx(&text, 10)]);
+        expected.push('a');
+        expected.push_str(&text[char_to_byte_idx(&text, 1500)..char_to_byte_idx(&text, 1600)]);
+        expected.push_str(&big);
+        expected.push_str(&text[char_to_byte_idx(&text, 1600)..char_to_byte_idx(&text, 1700)]);
+        expected.push('b');
+        expected.push_str(&text[char_to_byte_idx(&text, 2000)..]);
+        assert_eq!(r, expected);
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn apply_edits_07() {
+        let text = TEXT.repeat(20);
+        let mut r = Rope::from_str(&text);
+        let r2 = r.clone();
+
+        // Leaves away from the edits are still shared with the clone.
+        r.apply_edits([(5..6, "A"), (200..210, "")]);
+        let last_1 = r.chunks().last().unwrap();
+        let last_2 = r2.chunks().last().unwrap();
+        assert_eq!(last_1.as_ptr(), last_2.as_ptr());
 
         r.assert_integrity();
-        r2.assert_integrity();
         r.assert_invariants();
-        r2.assert_invariants();
     }
 
     #[test]
-    fn split_off_05() {
+    fn insert_at_byte_01() {
         let mut r = Rope::from_str(TEXT);
+        r.insert_at_byte(3, "AA");
+        r.insert_at_byte(102, "BB");
+        r.insert_char_at_byte(131, 'C');
 
-        let r2 = r.split_off(103);
         assert_eq!(
             r,
-            "Hello there!  How're you doing?  It's \
+            "HelAAlo there!  How're you doing?  It's \
              a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん！"
+             we're alive?  こんにBBちは、みんなさん！C"
         );
-        assert_eq!(r2, "");
 
         r.assert_integrity();
-        r2.assert_integrity();
         r.assert_invariants();
-        r2.assert_invariants();
     }
 
     #[test]
-    #[should_panic]
-    fn split_off_06() {
+    fn insert_at_byte_02() {
         let mut r = Rope::from_str(TEXT);
-        r.split_off(104); // One past the end of the rope
-    }
-
-    #[test]
-    fn append_01() {
-        let mut r = Rope::from_str(
-            "Hello there!  How're you doing?  It's \
-             a fine day, isn't ",
-        );
-        let r2 = Rope::from_str(
-            "it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん！",
+        assert_eq!(
+            r.try_insert_at_byte(114, "AA").unwrap_err().to_string(),
+            Error::ByteIndexNotCharBoundary(114).to_string(),
         );
-
-        r.append(r2);
+        assert!(r.try_insert_char_at_byte(128, 'A').is_err());
         assert_eq!(r, TEXT);
+    }
 
-        r.assert_integrity();
-        r.assert_invariants();
+    #[test]
+    #[should_panic]
+    fn insert_at_byte_03() {
+        let mut r = Rope::from_str(TEXT);
+        r.insert_at_byte(113, "AA"); // Not a char boundary
     }
 
     #[test]
-    fn append_02() {
-        let mut r = Rope::from_str(
-            "Hello there!  How're you doing?  It's \
-             a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんに",
-        );
-        let r2 = Rope::from_str("ちは、みんなさん！");
+    fn insert_at_byte_04() {
+        let mut r = Rope::from_str(TEXT);
 
-        r.append(r2);
-        assert_eq!(r, TEXT);
+        // Inserting text much bigger than a chunk.
+        r.insert_at_byte(109, TEXT);
+        assert_eq!(r.byte_slice(..109), &TEXT[..109]);
+        assert_eq!(r.byte_slice(109..236), TEXT);
+        assert_eq!(r.byte_slice(236..), &TEXT[109..]);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn append_03() {
-        let mut r = Rope::from_str(
-            "Hello there!  How're you doing?  It's \
+    fn remove_byte_range_01() {
+        let mut r = Rope::from_str(TEXT);
+
+        r.remove_byte_range(5..11);
+        r.remove_byte_range(24..31);
+        r.remove_byte_range(19..25);
+        r.remove_byte_range(81..93);
+        assert_eq!(
+            r,
+            "Hello!  How're you \
              a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん",
+             we're alive?  こんにんなさん！"
         );
-        let r2 = Rope::from_str("！");
-
-        r.append(r2);
-        assert_eq!(r, TEXT);
 
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn append_04() {
-        let mut r = Rope::from_str("H");
-        let r2 = Rope::from_str(
-            "ello there!  How're you doing?  It's \
-             a fine day, isn't it?  Aren't you glad \
-             we're alive?  こんにちは、みんなさん！",
-        );
+    fn remove_byte_range_02() {
+        let mut r = Rope::from_str(TEXT);
 
-        r.append(r2);
+        assert!(r.try_remove_byte_range(100..114).is_err()); // Not a char boundary
+        assert!(r.try_remove_byte_range(101..128).is_err()); // Past the end
+        #[allow(clippy::reversed_empty_ranges)]
+        let res = r.try_remove_byte_range(56..55); // Wrong ordering
+        assert!(res.is_err());
         assert_eq!(r, TEXT);
-
-        r.assert_integrity();
-        r.assert_invariants();
     }
 
     #[test]
-    fn append_05() {
+    fn remove_byte_range_03() {
         let mut r = Rope::from_str(TEXT);
-        let r2 = Rope::from_str("");
 
-        r.append(r2);
+        // Ranges spanning many chunks are checked at both ends before
+        // anything is removed.
+        assert!(r.try_remove_byte_range(113..120).is_err());
+        assert!(r.try_remove_byte_range(3..113).is_err());
         assert_eq!(r, TEXT);
 
+        r.remove_byte_range(3..112);
+        assert_eq!(r, "Helんなさん！");
+
         r.assert_integrity();
         r.assert_invariants();
     }
 
     #[test]
-    fn append_06() {
-        let mut r = Rope::from_str("");
-        let r2 = Rope::from_str(TEXT);
+    fn split_off_at_byte_01() {
+        let mut r = Rope::from_str(TEXT);
 
-        r.append(r2);
-        assert_eq!(r, TEXT);
+        let r2 = r.split_off_at_byte(100);
+        assert_eq!(
+            r,
+            "Hello there!  How're you doing?  It's \
+             a fine day, isn't it?  Aren't you glad \
+             we're alive?  こんに"
+        );
+        assert_eq!(r2, "ちは、みんなさん！");
 
         r.assert_integrity();
+        r2.assert_integrity();
         r.assert_invariants();
+        r2.assert_invariants();
     }
 
     #[test]
-    fn append_07() {
-        let mut r = Rope::from_str("\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
-        let r2 = Rope::from_str("\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
+    #[should_panic]
+    fn split_off_at_byte_02() {
+        let mut r = Rope::from_str(TEXT);
+        r.split_off_at_byte(111); // Not a char boundary
+    }
 
-        r.append(r2);
-        let s2 = r.to_string();
-        assert_eq!(r, "\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
-        assert_eq!(r.len_lines(), byte_to_line_idx(&s2, s2.len()) + 1);
+    #[test]
+    fn split_off_at_byte_03() {
+        let mut r = Rope::from_str(TEXT);
+        assert!(r.try_split_off_at_byte(111).is_err());
+        assert_eq!(r, TEXT);
 
-        r.assert_integrity();
-        r.assert_invariants();
+        let r2 = r.split_off_at_byte(0);
+        assert_eq!(r, "");
+        assert_eq!(r2, TEXT);
     }
 
     #[test]
@@ -2871,6 +5029,394 @@ mod tests {
         assert_eq!(0, r.utf16_cu_to_char(0));
     }
 
+    #[test]
+    fn char_to_line_col_01() {
+        let r = Rope::from_str(TEXT_LINES);
+
+        assert_eq!(
+            r.char_to_line_col(0, PositionEncoding::Utf8),
+            LineCol::new(0, 0)
+        );
+        assert_eq!(
+            r.char_to_line_col(31, PositionEncoding::Utf32),
+            LineCol::new(0, 31)
+        );
+        assert_eq!(
+            r.char_to_line_col(40, PositionEncoding::Utf32),
+            LineCol::new(1, 8)
+        );
+        assert_eq!(
+            r.char_to_line_col(90, PositionEncoding::Utf8),
+            LineCol::new(3, 6)
+        );
+        assert_eq!(
+            r.char_to_line_col(90, PositionEncoding::Utf16),
+            LineCol::new(3, 2)
+        );
+        assert_eq!(
+            r.char_to_line_col(100, PositionEncoding::Utf32),
+            LineCol::new(3, 12)
+        );
+    }
+
+    #[test]
+    fn line_col_round_trip_01() {
+        let r = Rope::from_str(TEXT_LINES);
+        for &encoding in &[
+            PositionEncoding::Utf8,
+            PositionEncoding::Utf16,
+            PositionEncoding::Utf32,
+        ] {
+            for char_idx in 0..=r.len_chars() {
+                let pos = r.char_to_line_col(char_idx, encoding);
+                assert_eq!(r.line_col_to_char(pos, encoding), char_idx);
+            }
+        }
+    }
+
+    #[test]
+    fn char_to_line_col_02() {
+        let r = Rope::from_str("Hi\r\nthere\r\n");
+
+        // Between the CR and LF of a CRLF pair is clamped to the start of
+        // the line break, which `line_col_to_char()` accepts.
+        for &encoding in &[
+            PositionEncoding::Utf8,
+            PositionEncoding::Utf16,
+            PositionEncoding::Utf32,
+        ] {
+            assert_eq!(r.char_to_line_col(3, encoding), LineCol::new(0, 2));
+            assert_eq!(r.char_to_line_col(10, encoding), LineCol::new(1, 5));
+            assert_eq!(r.line_col_to_char(LineCol::new(0, 2), encoding), 2);
+            assert_eq!(r.line_col_to_char(LineCol::new(1, 5), encoding), 9);
+            for char_idx in 0..=r.len_chars() {
+                let pos = r.char_to_line_col(char_idx, encoding);
+                assert!(r.try_line_col_to_char(pos, encoding).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn line_col_to_char_01() {
+        let r = Rope::from_str("a🐸b\r\nc");
+
+        assert_eq!(
+            r.char_to_line_col(2, PositionEncoding::Utf16),
+            LineCol::new(0, 3)
+        );
+        assert_eq!(
+            r.line_col_to_char(LineCol::new(0, 3), PositionEncoding::Utf16),
+            2
+        );
+        assert_eq!(
+            r.line_col_to_char(LineCol::new(0, 4), PositionEncoding::Utf16),
+            3
+        );
+        assert_eq!(
+            r.line_col_to_char(LineCol::new(0, 6), PositionEncoding::Utf8),
+            3
+        );
+        assert_eq!(
+            r.line_col_to_char(LineCol::new(1, 0), PositionEncoding::Utf8),
+            5
+        );
+        assert_eq!(
+            r.line_col_to_char(LineCol::new(1, 1), PositionEncoding::Utf32),
+            6
+        );
+    }
+
+    #[test]
+    fn line_col_to_char_02() {
+        let r = Rope::from_str("a🐸b\r\nc");
+
+        // In the middle of the surrogate pair / utf8 sequence.
+        assert!(matches!(
+            r.try_line_col_to_char(LineCol::new(0, 2), PositionEncoding::Utf16),
+            Err(Error::ColumnIndexNotCharBoundary(2))
+        ));
+        assert!(matches!(
+            r.try_line_col_to_char(LineCol::new(0, 3), PositionEncoding::Utf8),
+            Err(Error::ColumnIndexNotCharBoundary(3))
+        ));
+
+        // Past the end of the line's contents, into or past the CRLF.
+        assert!(matches!(
+            r.try_line_col_to_char(LineCol::new(0, 5), PositionEncoding::Utf16),
+            Err(Error::ColumnIndexOutOfBounds(5, 4))
+        ));
+        assert!(matches!(
+            r.try_line_col_to_char(LineCol::new(1, 2), PositionEncoding::Utf32),
+            Err(Error::ColumnIndexOutOfBounds(2, 1))
+        ));
+
+        // Past the last line.
+        assert!(matches!(
+            r.try_line_col_to_char(LineCol::new(2, 0), PositionEncoding::Utf32),
+            Err(Error::LineIndexOutOfBounds(2, 2))
+        ));
+        assert!(r.try_char_to_line_col(8, PositionEncoding::Utf32).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn line_col_to_char_03() {
+        let r = Rope::from_str(TEXT_LINES);
+        r.line_col_to_char(LineCol::new(0, 32), PositionEncoding::Utf32);
+    }
+
+    #[test]
+    fn find_01() {
+        let r = Rope::from_str(TEXT_LINES);
+
+        assert_eq!(r.find("you"), Some(21));
+        assert_eq!(r.rfind("you"), Some(66));
+        assert_eq!(r.find("みんな"), Some(94));
+        assert_eq!(r.rfind("みんな"), Some(94));
+        assert_eq!(r.find("?\nA"), Some(57));
+        assert_eq!(r.find("Goodbye"), None);
+        assert_eq!(r.rfind("Goodbye"), None);
+        assert_eq!(r.find(""), Some(0));
+        assert_eq!(r.rfind(""), Some(100));
+    }
+
+    #[test]
+    fn find_from_01() {
+        let r = Rope::from_str(TEXT_LINES);
+
+        assert_eq!(r.find_from(21, "you"), Some(21));
+        assert_eq!(r.find_from(22, "you"), Some(66));
+        assert_eq!(r.find_from(67, "you"), None);
+        assert_eq!(r.find_from(100, "you"), None);
+        assert_eq!(r.find_from(100, ""), Some(100));
+    }
+
+    #[test]
+    fn rfind_from_01() {
+        let r = Rope::from_str(TEXT_LINES);
+
+        assert_eq!(r.rfind_from(69, "you"), Some(66));
+        assert_eq!(r.rfind_from(68, "you"), Some(21));
+        assert_eq!(r.rfind_from(23, "you"), None);
+        assert_eq!(r.rfind_from(0, "you"), None);
+        assert_eq!(r.rfind_from(0, ""), Some(0));
+    }
+
+    #[test]
+    fn find_from_02() {
+        let r = Rope::from_str(TEXT_LINES);
+
+        assert!(r.try_find_from(101, "you").is_err());
+        assert!(r.try_rfind_from(101, "you").is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn find_from_03() {
+        let r = Rope::from_str(TEXT_LINES);
+        r.find_from(101, "you");
+    }
+
+    #[test]
+    fn find_ignore_case_01() {
+        let r = Rope::from_str("Maße und MASSE");
+        assert_eq!(r.find_ignore_case("masse"), Some(0..4));
+        assert_eq!(r.rfind_ignore_case("masse"), Some(9..14));
+        assert_eq!(r.find_from_ignore_case(1, "MASSE"), Some(9..14));
+        assert_eq!(r.rfind_from_ignore_case(13, "Maße"), Some(0..4));
+        assert_eq!(r.find_ignore_case("UND"), Some(5..8));
+        assert_eq!(r.find_ignore_case("MASSE UND"), Some(0..8));
+        assert_eq!(r.find_ignore_case("mase"), None);
+        assert!(r.try_find_from_ignore_case(15, "masse").is_err());
+        assert!(r.try_rfind_from_ignore_case(15, "masse").is_err());
+    }
+
+    #[test]
+    fn replace_all_01() {
+        let mut r = Rope::from_str(TEXT);
+        for &(pattern, replacement) in &[
+            ("you", "y'all"),
+            ("e", ""),
+            ("!", "!!!!!!!!!!!!!!!!!!!!"),
+            ("みんな", "皆"),
+            (" ", "  "),
+        ] {
+            let expected = r.to_string().replace(pattern, replacement);
+            let count = r.to_string().matches(pattern).count();
+            assert_eq!(r.replace_all(pattern, replacement), count);
+            assert_eq!(r, expected);
+            r.assert_integrity();
+            r.assert_invariants();
+        }
+    }
+
+    #[test]
+    fn replace_all_02() {
+        let mut r = Rope::from_str("aaaaa");
+        assert_eq!(r.replace_all("aa", "b"), 2);
+        assert_eq!(r, "bba");
+        assert_eq!(r.replace_all("x", "y"), 0);
+        assert_eq!(r, "bba");
+        assert_eq!(r.replace_all("", "-"), 4);
+        assert_eq!(r, "-b-b-a-");
+        assert_eq!(r.replace_all("-b-b-a-", ""), 1);
+        assert_eq!(r, "");
+        assert_eq!(r.replace_all("", "x"), 1);
+        assert_eq!(r, "x");
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn replace_all_03() {
+        // Edits that join up CRLF pairs.
+        let text = "\rX\n".repeat(20);
+        let mut r = Rope::from_str(&text);
+        r.replace_all("X", "");
+        assert_eq!(r, "\r\n".repeat(20));
+        assert_eq!(r.len_lines(), 21);
+        r.assert_invariants();
+
+        let mut r = Rope::from_str(&text);
+        r.replace_all("X\n", "\n\r");
+        assert_eq!(r, "\r\n\r".repeat(20));
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn replace_all_04() {
+        // Chunks without matches are shared with the original rope.
+        let r = Rope::from_str(&TEXT.repeat(10));
+        let mut r2 = r.clone();
+        r2.replace_all("!", "?");
+
+        let shared = r2
+            .chunks()
+            .filter(|c2| r.chunks().any(|c| std::ptr::eq(c.as_ptr(), c2.as_ptr())))
+            .count();
+        assert!(shared > r2.chunks().count() / 2);
+        assert_eq!(r2, TEXT.repeat(10).replace('!', "?"));
+        r2.assert_invariants();
+    }
+
+    #[test]
+    fn replace_all_05() {
+        // Whole subtrees without matches are shared with the original rope,
+        // not just their chunks.
+        let text = format!("{}{}", TEXT, "abc ".repeat(500));
+        let r = Rope::from_str(&text);
+        let mut r2 = r.clone();
+        assert_eq!(r2.replace_all("!", "?"), 1);
+
+        let nodes = r.root.children().nodes();
+        let nodes2 = r2.root.children().nodes();
+        assert!(Arc::ptr_eq(nodes.last().unwrap(), nodes2.last().unwrap()));
+        assert_eq!(r2, text.replace('!', "?"));
+        r2.assert_integrity();
+        r2.assert_invariants();
+    }
+
+    #[test]
+    fn replace_all_with_01() {
+        let mut r = Rope::from_str(TEXT);
+        let mut ranges = Vec::new();
+        let count = r.replace_all_with("you", |range| {
+            ranges.push(range.clone());
+            format!("<{}>", range.start)
+        });
+        assert_eq!(count, 2);
+        assert_eq!(ranges, vec![21..24, 68..71]);
+        assert_eq!(
+            r,
+            TEXT.replacen("you", "<21>", 1).replacen("you", "<68>", 1)
+        );
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn normalize_line_endings_01() {
+        let mut r = Rope::from_str("a\r\nb\nc\r\n\r\nd");
+        assert_eq!(r.normalize_line_endings(LineEnding::Crlf), 1);
+        assert_eq!(r, "a\r\nb\r\nc\r\n\r\nd");
+        assert_eq!(r.normalize_line_endings(LineEnding::Crlf), 0);
+        assert_eq!(r.normalize_line_endings(LineEnding::Lf), 4);
+        assert_eq!(r, "a\nb\nc\n\nd");
+        r.assert_integrity();
+        r.assert_invariants();
+
+        #[cfg(feature = "unicode_lines")]
+        {
+            let mut r = Rope::from_str("a\u{2028}b\rc\u{85}\u{0C}");
+            assert_eq!(r.normalize_line_endings(LineEnding::Ps), 4);
+            assert_eq!(r, "a\u{2029}b\u{2029}c\u{2029}\u{2029}");
+            r.assert_integrity();
+            r.assert_invariants();
+        }
+    }
+
+    #[test]
+    fn normalize_line_endings_02() {
+        // Chunks without line breaks to change are shared with the original
+        // rope.
+        let text = format!("{}\r\n", TEXT).repeat(10);
+        let r = Rope::from_str(&text);
+        let mut r2 = r.clone();
+        assert_eq!(r2.normalize_line_endings(LineEnding::Lf), 10);
+
+        let shared = r2
+            .chunks()
+            .filter(|c2| r.chunks().any(|c| std::ptr::eq(c.as_ptr(), c2.as_ptr())))
+            .count();
+        assert!(shared > r2.chunks().count() / 2);
+        assert_eq!(r2, text.replace("\r\n", "\n"));
+        r2.assert_integrity();
+        r2.assert_invariants();
+    }
+
+    #[test]
+    fn normalize_line_endings_03() {
+        // Splitting CRLF pairs apart must not happen, even when the
+        // replacement is longer and leaves have to be split.
+        let text = "\r\n\n".repeat(50);
+        let mut r = Rope::from_str(&text);
+        assert_eq!(r.normalize_line_endings(LineEnding::Crlf), 50);
+        assert_eq!(r, "\r\n".repeat(100));
+        assert_eq!(r.len_lines(), 101);
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn line_ending_stats_01() {
+        let r = Rope::from_str(&format!("{}\r\n{}\n", TEXT_LINES, TEXT).repeat(5));
+        let stats = r.line_ending_stats();
+        assert_eq!(stats.lf, 20);
+        assert_eq!(stats.crlf, 5);
+        assert_eq!(stats.total(), r.len_lines() - 1);
+        assert_eq!(stats.most_common(), Some(LineEnding::Lf));
+        assert!(stats.is_mixed());
+
+        assert_eq!(Rope::new().line_ending_stats(), LineEndingStats::default());
+    }
+
+    #[test]
+    fn write_to_with_line_ending_01() {
+        let text = format!("{}\r\n", TEXT_LINES).repeat(3);
+        let r = Rope::from_str(&text);
+
+        let mut out = Vec::new();
+        r.write_to_with_line_ending(&mut out, LineEnding::Crlf)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            text.replace("\r\n", "\n").replace('\n', "\r\n")
+        );
+
+        let mut out = Vec::new();
+        r.write_to_with_line_ending(&mut out, LineEnding::Lf)
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), text.replace("\r\n", "\n"));
+    }
+
     #[test]
     #[should_panic]
     fn utf16_cu_to_char_02() {
@@ -3381,6 +5927,34 @@ mod tests {
         assert_eq!(s, r2);
     }
 
+    #[test]
+    fn from_rope_slice_05() {
+        fn collect_leaves(node: &Arc<Node>, leaves: &mut Vec<Arc<Node>>) {
+            Node::for_each_leaf(node, &mut |leaf, _| leaves.push(Arc::clone(leaf)));
+        }
+
+        // Big enough to span many leaves, regardless of chunk size.
+        let text = TEXT.repeat(MAX_BYTES);
+        let r1 = Rope::from_str(&text);
+        let s = r1.slice(13..(r1.len_chars() - 13));
+        let r2: Rope = s.into();
+        assert_eq!(s, r2);
+
+        // Make sure all of the interior chunks are shared rather than
+        // copied.  Only the chunks at the two ends can be new.
+        let mut leaves_1 = Vec::new();
+        let mut leaves_2 = Vec::new();
+        collect_leaves(&r1.root, &mut leaves_1);
+        collect_leaves(&r2.root, &mut leaves_2);
+        assert!(leaves_2.len() > 10);
+        for leaf in &leaves_2[1..(leaves_2.len() - 1)] {
+            assert!(leaves_1.iter().any(|l| Arc::ptr_eq(l, leaf)));
+        }
+
+        r2.assert_integrity();
+        r2.assert_invariants();
+    }
+
     #[test]
     fn from_iter_01() {
         let r1 = Rope::from_str(TEXT);
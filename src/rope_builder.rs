@@ -1,3 +1,6 @@
+use std::fmt;
+use std::io;
+use std::ops::Range;
 use std::sync::Arc;
 
 use smallvec::SmallVec;
@@ -43,6 +46,16 @@ pub struct RopeBuilder {
     stack: SmallVec<[Arc<Node>; 4]>,
     buffer: String,
     last_chunk_len_bytes: usize,
+    // The bytes of an incomplete utf8 sequence at the end of the last
+    // `append_bytes_lossy()` call, waiting for the rest of the sequence.
+    partial_utf8: SmallVec<[u8; 4]>,
+    // Same, but for `io::Write::write()`, which never replaces them.
+    write_partial_utf8: SmallVec<[u8; 4]>,
+    // Total bytes appended so far, both as received and as they end up in
+    // the rope.  These only differ due to lossy replacements.
+    input_len_bytes: usize,
+    len_bytes: usize,
+    replacements: Vec<Utf8Replacement>,
 }
 
 impl RopeBuilder {
@@ -56,6 +69,11 @@ impl RopeBuilder {
             },
             buffer: String::new(),
             last_chunk_len_bytes: 0,
+            partial_utf8: SmallVec::new(),
+            write_partial_utf8: SmallVec::new(),
+            input_len_bytes: 0,
+            len_bytes: 0,
+            replacements: Vec::new(),
         }
     }
 
@@ -66,19 +84,169 @@ impl RopeBuilder {
     /// desired, but larger chunks are more efficient.
     ///
     /// `chunk` must be valid utf8 text.
+    ///
+    /// If an incomplete utf8 sequence was left at the end of the last
+    /// `append_bytes_lossy()` call, it is replaced with U+FFFD first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an incomplete utf8 sequence written through the builder's
+    /// `io::Write` impl is waiting for the rest of its bytes.
     pub fn append(&mut self, chunk: &str) {
-        self.append_internal(chunk, false);
+        self.assert_no_write_partial_utf8();
+        self.replace_partial_utf8();
+        self.append_str(chunk);
+    }
+
+    /// Appends `bytes` to the end of the in-progress `Rope`, replacing
+    /// invalid utf8 with U+FFFD (the replacement character).
+    ///
+    /// Like `String::from_utf8_lossy()`, each maximal invalid sequence is
+    /// replaced with a single replacement character.  A utf8 sequence can
+    /// be split across calls: an incomplete sequence at the end of `bytes`
+    /// is held back until the next call, and is only replaced if the bytes
+    /// that follow it (or the end of the input) show it to be invalid.
+    ///
+    /// The replacements made are reported by
+    /// [`finish_lossy()`](RopeBuilder::finish_lossy).
+    ///
+    /// # Panics
+    ///
+    /// Panics if an incomplete utf8 sequence written through the builder's
+    /// `io::Write` impl is waiting for the rest of its bytes.
+    pub fn append_bytes_lossy(&mut self, bytes: &[u8]) {
+        self.assert_no_write_partial_utf8();
+        let mut bytes = bytes;
+
+        // Finish off an incomplete sequence from the previous call first.
+        if !self.partial_utf8.is_empty() && !bytes.is_empty() {
+            let partial_len = self.partial_utf8.len();
+            let mut seq = [0u8; 7];
+            let seq_len = partial_len + bytes.len().min(7 - partial_len);
+            seq[..partial_len].copy_from_slice(&self.partial_utf8);
+            seq[partial_len..seq_len].copy_from_slice(&bytes[..(seq_len - partial_len)]);
+            let seq = &seq[..seq_len];
+
+            let consumed = match std::str::from_utf8(seq) {
+                // The sequence is complete and valid.
+                Ok(_) => utf8_seq_len(seq[0]),
+                Err(e) if e.valid_up_to() > 0 => utf8_seq_len(seq[0]),
+                // The sequence turned out to be invalid.
+                Err(e) => match e.error_len() {
+                    Some(len) => len,
+                    None => {
+                        // Still incomplete, so wait for more bytes.
+                        self.partial_utf8.extend_from_slice(bytes);
+                        return;
+                    }
+                },
+            };
+
+            match std::str::from_utf8(&seq[..consumed]) {
+                Ok(text) => self.append_str(text),
+                Err(_) => self.append_replacement(consumed),
+            }
+            self.partial_utf8.clear();
+            bytes = &bytes[(consumed - partial_len)..];
+        }
+
+        loop {
+            match std::str::from_utf8(bytes) {
+                Ok(text) => {
+                    self.append_str(text);
+                    return;
+                }
+                Err(e) => {
+                    let (valid, rest) = bytes.split_at(e.valid_up_to());
+                    // The unsafe block here is reinterpreting the bytes as
+                    // utf8.  This is safe because the bytes being
+                    // reinterpreted have already been validated as utf8
+                    // just above.
+                    self.append_str(unsafe { std::str::from_utf8_unchecked(valid) });
+                    match e.error_len() {
+                        Some(len) => {
+                            self.append_replacement(len);
+                            bytes = &rest[len..];
+                        }
+                        None => {
+                            self.partial_utf8.extend_from_slice(rest);
+                            return;
+                        }
+                    }
+                }
+            }
+        }
     }
 
     /// Finishes the build, and returns the `Rope`.
     ///
+    /// If an incomplete utf8 sequence was left at the end of the last
+    /// `append_bytes_lossy()` call, it is replaced with U+FFFD.
+    ///
     /// Note: this method consumes the builder.  If you want to continue
     /// building other ropes with the same prefix, you can clone the builder
     /// before calling `finish()`.
-    pub fn finish(mut self) -> Rope {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the text written through the builder's `io::Write` impl
+    /// ended with an incomplete utf8 sequence.  Use
+    /// [`try_finish()`](RopeBuilder::try_finish) to get an error instead.
+    pub fn finish(self) -> Rope {
+        self.finish_lossy().0
+    }
+
+    /// Same as `finish()`, but also returns the replacements made for
+    /// invalid utf8 by [`append_bytes_lossy()`](RopeBuilder::append_bytes_lossy),
+    /// in order.
+    ///
+    /// # Example
+    /// ```
+    /// # use ropey::RopeBuilder;
+    /// #
+    /// let mut builder = RopeBuilder::new();
+    ///
+    /// builder.append_bytes_lossy(b"Hello \xF0\x9F");
+    /// builder.append_bytes_lossy(b"\x90\xB8 world\xFF!");
+    ///
+    /// let (rope, replacements) = builder.finish_lossy();
+    ///
+    /// assert_eq!(rope, "Hello 🐸 world\u{FFFD}!");
+    /// assert_eq!(replacements.len(), 1);
+    /// assert_eq!(replacements[0].input_byte_range, 16..17);
+    /// assert_eq!(replacements[0].byte_idx, 16);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `finish()`.
+    pub fn finish_lossy(mut self) -> (Rope, Vec<Utf8Replacement>) {
+        self.assert_no_write_partial_utf8();
+        self.replace_partial_utf8();
+
         // Append the last leaf
         self.append_internal("", true);
-        self.finish_internal(true)
+        let replacements = std::mem::take(&mut self.replacements);
+        (self.finish_internal(true), replacements)
+    }
+
+    /// Same as `finish()`, but returns an error instead of panicking if the
+    /// text written through the builder's `io::Write` impl ended with an
+    /// incomplete utf8 sequence.
+    ///
+    /// This is meant for use with the builder's `io::Write` impl, whose
+    /// `write()` accepts a utf8 sequence split across calls.
+    ///
+    /// # Errors
+    ///
+    /// - If the written text ended with an incomplete utf8 sequence, an IO
+    ///   error with kind `InvalidData` is returned.
+    pub fn try_finish(self) -> io::Result<Rope> {
+        if self.write_partial_utf8.is_empty() {
+            Ok(self.finish())
+        } else {
+            Err(incomplete_utf8_error())
+        }
     }
 
     /// Builds a rope all at once from a single string slice.
@@ -115,8 +283,56 @@ impl RopeBuilder {
         self.finish_internal(false)
     }
 
+    /// Appends an existing leaf node to the in-progress rope, sharing it
+    /// rather than copying its text.
+    ///
+    /// This must not be used in conjunction with `append()`, and the caller
+    /// is responsible for not splitting CRLF pairs between leaves.
+    pub(crate) fn append_leaf(&mut self, leaf: Arc<Node>) {
+        self.last_chunk_len_bytes = leaf.leaf_text().len();
+        self.append_leaf_node(leaf);
+    }
+
     //-----------------------------------------------------------------
 
+    fn append_str(&mut self, text: &str) {
+        self.input_len_bytes += text.len();
+        self.len_bytes += text.len();
+        self.append_internal(text, false);
+    }
+
+    // Appends a replacement character for `invalid_len` bytes of invalid
+    // utf8, recording the replacement.
+    fn append_replacement(&mut self, invalid_len: usize) {
+        let start = self.input_len_bytes;
+        self.replacements.push(Utf8Replacement {
+            input_byte_range: start..(start + invalid_len),
+            byte_idx: self.len_bytes,
+        });
+        self.append_str("\u{FFFD}");
+        self.input_len_bytes = start + invalid_len;
+    }
+
+    // Replaces any incomplete utf8 sequence left over from
+    // `append_bytes_lossy()`, since nothing more can complete it.
+    fn replace_partial_utf8(&mut self) {
+        if !self.partial_utf8.is_empty() {
+            let len = self.partial_utf8.len();
+            self.partial_utf8.clear();
+            self.append_replacement(len);
+        }
+    }
+
+    // Panics if `io::Write::write()` left an incomplete utf8 sequence, which
+    // anything appended other than its remaining bytes would cut short.
+    fn assert_no_write_partial_utf8(&self) {
+        assert!(
+            self.write_partial_utf8.is_empty(),
+            "Attempt to append text while an incomplete utf8 sequence written \
+             through `io::Write` is pending"
+        );
+    }
+
     // Internal workings of `append()`.
     fn append_internal(&mut self, chunk: &str, is_last_chunk: bool) {
         let mut chunk = chunk;
@@ -297,12 +513,289 @@ impl Default for RopeBuilder {
     }
 }
 
+/// Appends formatted text, so that `write!()` can be used to build a `Rope`.
+///
+/// `write_str()` returns an error, appending nothing, if an incomplete utf8
+/// sequence written through the builder's `io::Write` impl is waiting for
+/// the rest of its bytes.
+impl fmt::Write for RopeBuilder {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if !self.write_partial_utf8.is_empty() {
+            return Err(fmt::Error);
+        }
+        self.append(s);
+        Ok(())
+    }
+}
+
+/// Appends utf8 text given as bytes.
+///
+/// A utf8 sequence can be split across `write()` calls: an incomplete
+/// sequence at the end of the written bytes is held back until the next
+/// call completes it.  `flush()` and
+/// [`try_finish()`](RopeBuilder::try_finish) return an error if there is an
+/// incomplete sequence left over.
+///
+/// Unlike [`append_bytes_lossy()`](RopeBuilder::append_bytes_lossy), invalid
+/// utf8 is never replaced: `write()` returns an error with kind
+/// `InvalidData` instead, having appended nothing from the invalid
+/// sequence on.
+///
+/// While an incomplete sequence is held back, nothing else may be appended
+/// to the builder: [`append()`](RopeBuilder::append) and
+/// [`finish()`](RopeBuilder::finish) panic, and `fmt::Write` returns an
+/// error.  An incomplete sequence left by
+/// [`append_bytes_lossy()`](RopeBuilder::append_bytes_lossy) is replaced
+/// with U+FFFD before writing, as with `append()`.
+///
+/// # Example
+/// ```
+/// # use std::io::Write;
+/// # use ropey::RopeBuilder;
+/// #
+/// let mut builder = RopeBuilder::new();
+///
+/// writeln!(builder, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+/// builder.write_all(b"\xF0\x9F").unwrap();
+/// builder.write_all(b"\x90\xB8").unwrap();
+/// builder.flush().unwrap();
+///
+/// assert_eq!(builder.try_finish().unwrap(), "1 + 2 = 3\n🐸");
+/// ```
+impl io::Write for RopeBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut written = 0;
+        self.replace_partial_utf8();
+
+        // Finish off an incomplete sequence from the previous call first.
+        if !self.write_partial_utf8.is_empty() && !buf.is_empty() {
+            let partial_len = self.write_partial_utf8.len();
+            let seq_len = utf8_seq_len(self.write_partial_utf8[0]);
+            let take = buf.len().min(seq_len - partial_len);
+            let mut seq = [0u8; 4];
+            seq[..partial_len].copy_from_slice(&self.write_partial_utf8);
+            seq[partial_len..(partial_len + take)].copy_from_slice(&buf[..take]);
+
+            match std::str::from_utf8(&seq[..(partial_len + take)]) {
+                Ok(text) => {
+                    self.write_partial_utf8.clear();
+                    self.append_str(text);
+                }
+                // Still incomplete, so wait for more bytes.
+                Err(e) if e.error_len().is_none() => {
+                    self.write_partial_utf8.extend_from_slice(&buf[..take]);
+                    return Ok(take);
+                }
+                Err(_) => return Err(invalid_utf8_error()),
+            }
+            written = take;
+        }
+
+        let bytes = &buf[written..];
+        match std::str::from_utf8(bytes) {
+            Ok(text) => {
+                self.append_str(text);
+                Ok(buf.len())
+            }
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                // The unsafe block here is reinterpreting the bytes as
+                // utf8.  This is safe because the bytes being
+                // reinterpreted have already been validated as utf8
+                // just above.
+                self.append_str(unsafe { std::str::from_utf8_unchecked(valid) });
+                if e.error_len().is_none() {
+                    // An incomplete sequence at the end, to be completed by
+                    // the next call.
+                    self.write_partial_utf8.extend_from_slice(rest);
+                    Ok(buf.len())
+                } else if written + valid.len() > 0 {
+                    // Report the valid part as written, so that the next
+                    // call starts at the invalid sequence and fails.
+                    Ok(written + valid.len())
+                } else {
+                    Err(invalid_utf8_error())
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.write_partial_utf8.is_empty() {
+            Ok(())
+        } else {
+            Err(incomplete_utf8_error())
+        }
+    }
+}
+
 enum NextText<'a> {
     None,
     UseBuffer,
     String(&'a str),
 }
 
+fn invalid_utf8_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "stream contained invalid UTF-8")
+}
+
+fn incomplete_utf8_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "stream ended with an incomplete UTF-8 sequence",
+    )
+}
+
+/// Returns the length of the utf8 sequence that starts with `lead_byte`.
+///
+/// `lead_byte` must be the first byte of a valid sequence.
+#[inline]
+fn utf8_seq_len(lead_byte: u8) -> usize {
+    match lead_byte {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        _ => 4,
+    }
+}
+
+/// An invalid utf8 sequence that was replaced with U+FFFD (the replacement
+/// character) while building a `Rope`.
+///
+/// See [`RopeBuilder::append_bytes_lossy()`] and
+/// [`Rope::from_reader_lossy()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Utf8Replacement {
+    /// The byte range of the invalid sequence in the input, i.e. in all the
+    /// text and bytes appended to the builder, in order.
+    pub input_byte_range: Range<usize>,
+    /// The byte index of the replacement character in the built `Rope`.
+    pub byte_idx: usize,
+}
+
+//===========================================================================
+
+/// Builds a `Rope` from a mix of new text and the leaf nodes of existing
+/// ropes.
+///
+/// Existing leaves are shared rather than copied whenever possible, so
+/// that rebuilding a rope with a handful of edits scattered through it only
+/// allocates new leaves around those edits.  New text is buffered and cut
+/// into full-sized leaves, and a leaf is only copied when it has to be
+/// merged with the new text next to it: when the new text would otherwise
+/// make an undersized leaf, or to avoid splitting a CRLF pair.
+#[derive(Debug, Clone)]
+pub(crate) struct LeafSplicer {
+    builder: RopeBuilder,
+    // The most recently added leaf, held back from the builder in case the
+    // text after it starts with the LF of a CRLF pair.
+    pending: Option<Arc<Node>>,
+    buffer: String,
+}
+
+impl LeafSplicer {
+    pub(crate) fn new() -> Self {
+        LeafSplicer {
+            builder: RopeBuilder::new(),
+            pending: None,
+            buffer: String::new(),
+        }
+    }
+
+    /// Appends new text.
+    pub(crate) fn push_str(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.buffer.is_empty() {
+            if let Some(ref leaf) = self.pending {
+                if !crlf::seam_is_break(leaf.leaf_text().as_bytes(), text.as_bytes()) {
+                    let leaf = self.pending.take().unwrap();
+                    self.buffer.push_str(leaf.leaf_text());
+                }
+            }
+        }
+        self.buffer.push_str(text);
+
+        // Cut full leaves off the front of the buffer, leaving enough
+        // behind to make at least one more full leaf.  The cut text is only
+        // removed from the buffer at the end, so that long texts don't get
+        // shifted down over and over.
+        let mut start = 0;
+        while self.buffer.len() - start >= MAX_BYTES * 2 {
+            let remaining = &self.buffer[start..];
+            let split_idx = crlf::find_good_split(MAX_BYTES, remaining.as_bytes(), true);
+            let leaf = Arc::new(Node::Leaf(NodeText::from_str(&remaining[..split_idx])));
+            start += split_idx;
+            self.push_node(leaf);
+        }
+        self.buffer.drain(..start);
+    }
+
+    /// Appends an existing leaf node.
+    pub(crate) fn push_leaf(&mut self, leaf: &Arc<Node>) {
+        let text = leaf.leaf_text();
+        if text.is_empty() {
+            return;
+        }
+
+        let can_share = if !self.buffer.is_empty() {
+            self.buffer.len() >= MIN_BYTES
+                && crlf::seam_is_break(self.buffer.as_bytes(), text.as_bytes())
+        } else if let Some(ref prev) = self.pending {
+            crlf::seam_is_break(prev.leaf_text().as_bytes(), text.as_bytes())
+        } else {
+            true
+        };
+
+        if can_share {
+            self.flush_buffer();
+            self.push_node(Arc::clone(leaf));
+        } else {
+            if self.buffer.is_empty() {
+                let prev = self.pending.take().unwrap();
+                self.buffer.push_str(prev.leaf_text());
+            }
+            self.buffer.push_str(text);
+            self.flush_buffer();
+        }
+    }
+
+    /// Finishes the build, and returns the `Rope`.
+    pub(crate) fn finish(mut self) -> Rope {
+        self.flush_buffer();
+        if let Some(leaf) = self.pending.take() {
+            self.builder.append_leaf(leaf);
+        }
+        self.builder.finish()
+    }
+
+    /// Turns all of the buffered text into leaves of roughly equal size.
+    fn flush_buffer(&mut self) {
+        let mut leaf_count = (self.buffer.len() + MAX_BYTES - 1) / MAX_BYTES;
+        let mut start = 0;
+        while leaf_count > 0 && start < self.buffer.len() {
+            let remaining = &self.buffer[start..];
+            let split_idx = if leaf_count == 1 {
+                remaining.len()
+            } else {
+                crlf::find_good_split(remaining.len() / leaf_count, remaining.as_bytes(), true)
+            };
+            let leaf = Arc::new(Node::Leaf(NodeText::from_str(&remaining[..split_idx])));
+            self.push_node(leaf);
+            start += split_idx;
+            leaf_count -= 1;
+        }
+        self.buffer.clear();
+    }
+
+    fn push_node(&mut self, leaf: Arc<Node>) {
+        if let Some(prev) = self.pending.replace(leaf) {
+            self.builder.append_leaf(prev);
+        }
+    }
+}
+
 //===========================================================================
 
 #[cfg(test)]
@@ -356,4 +849,219 @@ mod tests {
         r.assert_integrity();
         r.assert_invariants();
     }
+
+    #[test]
+    fn append_bytes_lossy_01() {
+        let mut b = RopeBuilder::new();
+
+        b.append_bytes_lossy(b"Hello there!  How're you doing?\r");
+        b.append_bytes_lossy(b"\nIt's a fine day, isn't it?\r\nAren't you ");
+        b.append_bytes_lossy(b"glad we're alive?\r\n\xE3\x81");
+        b.append_bytes_lossy(b"\x93");
+        b.append_bytes_lossy("んにちは、みんなさん！".as_bytes());
+
+        let (r, replacements) = b.finish_lossy();
+
+        assert_eq!(r, TEXT);
+        assert!(replacements.is_empty());
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn append_bytes_lossy_02() {
+        let mut b = RopeBuilder::new();
+
+        // Invalid byte, truncated sequence, and a sequence that turns out
+        // to be invalid in the next call.
+        b.append_bytes_lossy(b"a\xFFb\xE3\x81c\xF0");
+        b.append_bytes_lossy(b"\x9F");
+        b.append_bytes_lossy(b"!");
+        // A truncated sequence at the very end.
+        b.append_bytes_lossy(b"\xE3");
+
+        let (r, replacements) = b.finish_lossy();
+
+        assert_eq!(r, "a\u{FFFD}b\u{FFFD}c\u{FFFD}!\u{FFFD}");
+        assert_eq!(
+            replacements
+                .iter()
+                .map(|r| (r.input_byte_range.clone(), r.byte_idx))
+                .collect::<Vec<_>>(),
+            vec![(1..2, 1), (3..5, 5), (6..8, 9), (9..10, 13)]
+        );
+
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn append_bytes_lossy_03() {
+        let mut b = RopeBuilder::new();
+
+        // A sequence split over many calls, and `append()` cutting a
+        // sequence short.
+        b.append_bytes_lossy(b"\xF0");
+        b.append_bytes_lossy(b"");
+        b.append_bytes_lossy(b"\x9F");
+        b.append_bytes_lossy(b"\x90");
+        b.append_bytes_lossy(b"\xB8\xC3");
+        b.append("x");
+
+        let (r, replacements) = b.finish_lossy();
+
+        assert_eq!(r, "🐸\u{FFFD}x");
+        assert_eq!(replacements.len(), 1);
+        assert_eq!(replacements[0].input_byte_range, 4..5);
+        assert_eq!(replacements[0].byte_idx, 4);
+    }
+
+    #[test]
+    fn append_bytes_lossy_04() {
+        // Matches `String::from_utf8_lossy()` for every split point.
+        let bytes = b"\xE3\x81\x93a\xF4\x90\x80\x80\xED\xA0\x80b\xC2";
+        let expected = String::from_utf8_lossy(bytes);
+        for i in 0..=bytes.len() {
+            let mut b = RopeBuilder::new();
+            b.append_bytes_lossy(&bytes[..i]);
+            b.append_bytes_lossy(&bytes[i..]);
+            let (r, replacements) = b.finish_lossy();
+            assert_eq!(r, expected);
+            assert_eq!(replacements.len(), expected.matches('\u{FFFD}').count());
+        }
+    }
+
+    #[test]
+    fn fmt_write_01() {
+        use std::fmt::Write;
+
+        let mut b = RopeBuilder::new();
+        for i in 0..20 {
+            writeln!(b, "{}: {:?}", i, "こんにちは").unwrap();
+        }
+        let r = b.finish();
+
+        let mut expected = String::new();
+        for i in 0..20 {
+            writeln!(expected, "{}: {:?}", i, "こんにちは").unwrap();
+        }
+        assert_eq!(r, expected);
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn io_write_01() {
+        use std::io::Write;
+
+        // Every split point of a multi-byte sequence works.
+        let bytes = "a\u{e9}b\u{3053}c\u{1F438}d".as_bytes();
+        for i in 0..=bytes.len() {
+            for j in i..=bytes.len() {
+                let mut b = RopeBuilder::new();
+                b.write_all(&bytes[..i]).unwrap();
+                b.write_all(&bytes[i..j]).unwrap();
+                b.write_all(&bytes[j..]).unwrap();
+                b.flush().unwrap();
+                assert_eq!(b.try_finish().unwrap(), "a\u{e9}b\u{3053}c\u{1F438}d");
+            }
+        }
+    }
+
+    #[test]
+    fn io_write_02() {
+        use std::io::{ErrorKind, Write};
+
+        // Invalid utf8 is an error, and the valid text before it is kept.
+        let mut b = RopeBuilder::new();
+        assert_eq!(b.write(b"Hi\xFFthere").unwrap(), 2);
+        assert_eq!(
+            b.write(b"\xFFthere").unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(b.finish(), "Hi");
+
+        // Including when it completes a sequence from an earlier call.
+        let mut b = RopeBuilder::new();
+        b.write_all(b"Hi\xE3\x81").unwrap();
+        assert!(b.write_all(b"a").is_err());
+    }
+
+    #[test]
+    fn io_write_03() {
+        use std::io::{ErrorKind, Write};
+
+        // An incomplete sequence left at the end is an error.
+        let mut b = RopeBuilder::new();
+        b.write_all(b"Hi\xF0\x9F\x90").unwrap();
+        assert_eq!(b.flush().unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            b.clone().try_finish().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        // ...but not once it's been completed.
+        b.write_all(b"\xB8").unwrap();
+        b.flush().unwrap();
+        assert_eq!(b.try_finish().unwrap(), "Hi\u{1F438}");
+    }
+
+    #[test]
+    fn io_write_04() {
+        use std::fmt;
+        use std::io::Write;
+
+        // Nothing else can be appended while a written sequence is pending.
+        let mut b = RopeBuilder::new();
+        b.write_all(b"Hi\xF0\x9F").unwrap();
+        assert!(fmt::Write::write_str(&mut b, "there").is_err());
+        b.write_all(b"\x90\xB8").unwrap();
+        fmt::Write::write_str(&mut b, "there").unwrap();
+        assert_eq!(b.try_finish().unwrap(), "Hi\u{1F438}there");
+
+        // The lossy and strict incomplete sequences are kept apart.
+        let mut b = RopeBuilder::new();
+        b.append_bytes_lossy(b"Hi\xF0\x9F");
+        b.write_all(b"\x90\xB8").unwrap_err();
+        b.write_all(b"!").unwrap();
+        let (r, replacements) = b.finish_lossy();
+        assert_eq!(r, "Hi\u{FFFD}!");
+        assert_eq!(replacements.len(), 1);
+        assert_eq!(replacements[0].input_byte_range, 2..4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn io_write_05() {
+        use std::io::Write;
+
+        let mut b = RopeBuilder::new();
+        b.write_all(b"Hi\xF0\x9F").unwrap();
+        b.append("there");
+    }
+
+    #[test]
+    #[should_panic]
+    fn io_write_06() {
+        use std::io::Write;
+
+        let mut b = RopeBuilder::new();
+        b.write_all(b"Hi\xF0\x9F").unwrap();
+        b.finish();
+    }
+
+    #[test]
+    fn leaf_splicer_01() {
+        // Long texts are cut into leaves without splitting CRLF pairs.
+        let text = TEXT.repeat(50);
+        let mut splicer = LeafSplicer::new();
+        splicer.push_str("\r");
+        splicer.push_str(&text);
+        let r = splicer.finish();
+
+        assert_eq!(r, format!("\r{}", text));
+        r.assert_integrity();
+        r.assert_invariants();
+    }
 }
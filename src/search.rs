@@ -0,0 +1,323 @@
+//! Substring searching over text that's split into chunks.
+
+use std::sync::Arc;
+
+use memchr::memmem;
+
+/// A substring searcher that can be fed its haystack in pieces.
+///
+/// Matches within a piece are found with `memchr`'s `memmem` searchers.
+/// Matches that straddle pieces are found with Knuth-Morris-Pratt matching:
+/// the end of a piece that could be the start of a match is carried over to
+/// the next piece as a match state, so that earlier pieces never need to be
+/// looked at again.
+///
+/// A reverse `Finder` searches its haystack from back to front, carrying
+/// partial matches of the reversed needle.
+///
+/// Since both the needle and the haystack are valid utf8, byte-level
+/// matches always start and end on char boundaries.
+///
+/// The needle and its searchers are shared between clones, so cloning a
+/// `Finder` is cheap.
+#[derive(Debug, Clone)]
+pub(crate) struct Finder {
+    kmp: Kmp<u8>,
+    searcher: Searcher,
+}
+
+#[derive(Debug, Clone)]
+enum Searcher {
+    Forward(Arc<memmem::Finder<'static>>),
+    Reverse(Arc<memmem::FinderRev<'static>>),
+}
+
+impl Finder {
+    /// Creates a `Finder` for searching forward.
+    pub(crate) fn new(needle: &str) -> Finder {
+        Finder {
+            kmp: Kmp::from_vec(needle.as_bytes().to_vec()),
+            searcher: Searcher::Forward(Arc::new(
+                memmem::Finder::new(needle.as_bytes()).into_owned(),
+            )),
+        }
+    }
+
+    /// Creates a `Finder` for searching backward.
+    pub(crate) fn new_rev(needle: &str) -> Finder {
+        Finder {
+            kmp: Kmp::from_vec(needle.bytes().rev().collect()),
+            searcher: Searcher::Reverse(Arc::new(
+                memmem::FinderRev::new(needle.as_bytes()).into_owned(),
+            )),
+        }
+    }
+
+    /// Length of the needle, in bytes.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.kmp.len()
+    }
+
+    /// Searches `haystack` from front to back, continuing any partial match
+    /// in `state` from previous calls.
+    ///
+    /// Returns the index just past the end of the first match completed in
+    /// `haystack`, if any.  `state` is updated so that the search can be
+    /// resumed from there or continued with the next piece of haystack.
+    ///
+    /// Must only be used on a `Finder` created with `new()`, with a
+    /// non-empty needle.
+    pub(crate) fn find_in(&self, state: &mut usize, haystack: &[u8]) -> Option<usize> {
+        let searcher = match self.searcher {
+            Searcher::Forward(ref searcher) => searcher,
+            Searcher::Reverse(_) => panic!("find_in() called on a reverse Finder"),
+        };
+
+        // Finish or rule out the partial match from the previous piece.
+        let mut i = 0;
+        while *state > 0 && i < haystack.len() {
+            let completed = self.kmp.step(state, haystack[i]);
+            i += 1;
+            if completed {
+                return Some(i);
+            }
+        }
+
+        if let Some(start) = searcher.find(&haystack[i..]) {
+            return Some(i + start + self.kmp.len());
+        }
+
+        // No match, but the end of the haystack may start one.
+        let tail = haystack.len().saturating_sub(self.kmp.len() - 1).max(i);
+        for &byte in &haystack[tail..] {
+            self.kmp.step(state, byte);
+        }
+        None
+    }
+
+    /// Searches `haystack` from back to front, continuing any partial match
+    /// in `state` from previous calls.
+    ///
+    /// Returns the index of the start of the first match completed in
+    /// `haystack`, if any.  `state` is updated so that the search can be
+    /// resumed from there or continued with the previous piece of haystack.
+    ///
+    /// Must only be used on a `Finder` created with `new_rev()`, with a
+    /// non-empty needle.
+    pub(crate) fn rfind_in(&self, state: &mut usize, haystack: &[u8]) -> Option<usize> {
+        let searcher = match self.searcher {
+            Searcher::Reverse(ref searcher) => searcher,
+            Searcher::Forward(_) => panic!("rfind_in() called on a forward Finder"),
+        };
+
+        // Finish or rule out the partial match from the previous piece.
+        let mut i = haystack.len();
+        while *state > 0 && i > 0 {
+            let completed = self.kmp.step(state, haystack[i - 1]);
+            i -= 1;
+            if completed {
+                return Some(i);
+            }
+        }
+
+        if let Some(start) = searcher.rfind(&haystack[..i]) {
+            return Some(start);
+        }
+
+        // No match, but the start of the haystack may end one.
+        let head = (self.kmp.len() - 1).min(i);
+        for &byte in haystack[..head].iter().rev() {
+            self.kmp.step(state, byte);
+        }
+        None
+    }
+}
+
+/// A Knuth-Morris-Pratt matcher, fed its haystack one item at a time.
+///
+/// The needle and its match table are shared between clones, so cloning a
+/// `Kmp` is cheap.
+#[derive(Debug, Clone)]
+pub(crate) struct Kmp<T> {
+    needle: Arc<[T]>,
+    /// `fail[i]` is the length of the longest proper prefix of
+    /// `needle[..=i]` that is also a suffix of it.
+    fail: Arc<[usize]>,
+}
+
+impl<T: Copy + PartialEq> Kmp<T> {
+    /// Creates a `Kmp` for the given sequence of items.
+    pub(crate) fn from_vec(needle: Vec<T>) -> Kmp<T> {
+        let mut fail = vec![0; needle.len()];
+        let mut k = 0;
+        for i in 1..needle.len() {
+            while k > 0 && needle[i] != needle[k] {
+                k = fail[k - 1];
+            }
+            if needle[i] == needle[k] {
+                k += 1;
+            }
+            fail[i] = k;
+        }
+        Kmp {
+            needle: needle.into(),
+            fail: fail.into(),
+        }
+    }
+
+    /// Length of the needle, in items.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.needle.len()
+    }
+
+    /// Advances the match `state` by one item, and returns whether that
+    /// completed a match.
+    #[inline(always)]
+    pub(crate) fn step(&self, state: &mut usize, item: T) -> bool {
+        while *state > 0 && self.needle[*state] != item {
+            *state = self.fail[*state - 1];
+        }
+        if self.needle[*state] == item {
+            *state += 1;
+        }
+        if *state == self.needle.len() {
+            // Matches don't overlap, so start over from scratch.
+            *state = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the match state to continue from when a match just completed
+    /// by `step()` is rejected, so that matches overlapping it can still be
+    /// found.
+    #[inline]
+    pub(crate) fn overlap_state(&self) -> usize {
+        self.fail[self.needle.len() - 1]
+    }
+}
+
+/// Appends the full Unicode case folding of `c` to `out`.
+///
+/// The standard library doesn't provide case folding, but mapping a char to
+/// uppercase and then back to lowercase, twice over, puts chars into the
+/// same classes as full case folding does: "ß", "ẞ", and "SS" all become
+/// "ss", for example.  The one exception is the Turkish dotless "ı", which
+/// uppercases to a plain "I" but is its own case folding.
+pub(crate) fn fold_case(c: char, out: &mut Vec<char>) {
+    if c.is_ascii() {
+        out.push(c.to_ascii_lowercase());
+    } else if c == '\u{131}' {
+        out.push(c);
+    } else {
+        out.extend(
+            c.to_uppercase()
+                .flat_map(char::to_lowercase)
+                .flat_map(char::to_uppercase)
+                .flat_map(char::to_lowercase),
+        );
+    }
+}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_all(needle: &str, pieces: &[&str]) -> Vec<usize> {
+        let finder = Finder::new(needle);
+        let mut state = 0;
+        let mut offset = 0;
+        let mut ends = Vec::new();
+        for piece in pieces {
+            let mut i = 0;
+            while let Some(end) = finder.find_in(&mut state, &piece.as_bytes()[i..]) {
+                i += end;
+                ends.push(offset + i);
+            }
+            offset += piece.len();
+        }
+        ends
+    }
+
+    fn rfind_all(needle: &str, pieces: &[&str]) -> Vec<usize> {
+        let finder = Finder::new_rev(needle);
+        let mut state = 0;
+        let mut offset: usize = pieces.iter().map(|p| p.len()).sum();
+        let mut starts = Vec::new();
+        for piece in pieces.iter().rev() {
+            offset -= piece.len();
+            let mut i = piece.len();
+            while let Some(start) = finder.rfind_in(&mut state, &piece.as_bytes()[..i]) {
+                i = start;
+                starts.push(offset + i);
+            }
+        }
+        starts
+    }
+
+    #[test]
+    fn find_in_01() {
+        assert_eq!(find_all("abab", &["abababab"]), vec![4, 8]);
+        assert_eq!(find_all("abab", &["ab", "a", "bab", "ab"]), vec![4, 8]);
+        assert_eq!(find_all("aab", &["aaaa", "ab", "aab"]), vec![6, 9]);
+        assert_eq!(find_all("xyz", &["xy", "", "zx", "y"]), vec![3]);
+        assert_eq!(find_all("xyz", &["xy", "y", "z"]), vec![]);
+        assert_eq!(find_all("aab", &["xxa", "abxaa", "b"]), vec![5, 9]);
+        assert_eq!(
+            find_all("abc", &["zzzzzzzzab", "c", "zzabczzab"]),
+            vec![11, 16]
+        );
+    }
+
+    #[test]
+    fn rfind_in_01() {
+        assert_eq!(rfind_all("abab", &["abababab"]), vec![4, 0]);
+        assert_eq!(rfind_all("abab", &["ab", "a", "bab", "ab"]), vec![4, 0]);
+        assert_eq!(rfind_all("aab", &["aaaa", "ab", "aab"]), vec![6, 3]);
+        assert_eq!(rfind_all("aaa", &["a", "aaa", "a"]), vec![2]);
+        assert_eq!(rfind_all("xyz", &["xy", "y", "z"]), vec![]);
+        assert_eq!(rfind_all("aab", &["a", "abxaa", "bxx"]), vec![4, 0]);
+        assert_eq!(
+            rfind_all("abc", &["bczzabczz", "a", "bczzzzzzzz"]),
+            vec![9, 4]
+        );
+    }
+
+    #[test]
+    fn step_01() {
+        let finder = Kmp::from_vec(vec!['a', 'b', 'a']);
+        let mut state = 0;
+        let mut ends = Vec::new();
+        for (i, c) in "ababab".chars().enumerate() {
+            if finder.step(&mut state, c) {
+                ends.push(i + 1);
+                state = finder.overlap_state();
+            }
+        }
+        assert_eq!(ends, vec![3, 5]);
+    }
+
+    #[test]
+    fn fold_case_01() {
+        let fold = |text: &str| {
+            let mut out = Vec::new();
+            for c in text.chars() {
+                fold_case(c, &mut out);
+            }
+            out.into_iter().collect::<String>()
+        };
+        assert_eq!(fold("Hello WORLD!"), "hello world!");
+        assert_eq!(fold("Straße"), "strasse");
+        assert_eq!(fold("STRAẞE"), "strasse");
+        assert_eq!(fold("ΣΊΣΥΦΟΣ"), fold("σίσυφος"));
+        assert_eq!(fold("ﬁ"), "fi");
+        assert_eq!(fold("\u{212A}"), "k"); // Kelvin sign.
+        assert_eq!(fold("ı"), "ı");
+        assert_eq!(fold("İ"), "i\u{307}");
+    }
+}
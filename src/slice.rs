@@ -1,7 +1,18 @@
-use std::ops::RangeBounds;
+use std::ops::{Range, RangeBounds};
 use std::sync::Arc;
 
-use crate::iter::{Bytes, Chars, Chunks, Lines};
+use crate::iter::{
+    Bytes, CharIndices, Chars, ChunkIndices, Chunks, LineInfos, Lines, LinesWithoutEndings,
+    Matches, MatchesIgnoreCase, Pattern, Split,
+};
+#[cfg(feature = "graphemes")]
+use crate::iter::{GraphemeStepper, Graphemes};
+#[cfg(feature = "multi_search")]
+use crate::multi_search::{Automaton, MultiMatches, OverlappingMultiMatches};
+use crate::position::{LineCol, PositionEncoding};
+use crate::reader::RopeReader;
+#[cfg(feature = "regex")]
+use crate::regex::{Regex, RegexMatch, RegexMatches};
 use crate::rope::Rope;
 use crate::str_utils::{
     byte_to_char_idx, byte_to_line_idx, byte_to_utf16_surrogate_idx, char_to_byte_idx,
@@ -21,6 +32,10 @@ use crate::{end_bound_to_num, start_bound_to_num, Error, Result};
 /// In other words, the behavior of a `RopeSlice` is always identical to that
 /// of a full `Rope` created from the same text range.  Nothing should be
 /// surprising here.
+///
+/// A `RopeSlice` can be converted into an owned `Rope` with `Rope::from()`.
+/// That conversion shares data with the original `Rope` rather than copying
+/// the text, and runs in O(log N) time regardless of the slice's length.
 #[derive(Copy, Clone)]
 pub struct RopeSlice<'a>(pub(crate) RSEnum<'a>);
 
@@ -417,6 +432,46 @@ impl<'a> RopeSlice<'a> {
         self.try_utf16_cu_to_char(utf16_cu_idx).unwrap()
     }
 
+    /// Returns the line/column position of the given char.
+    ///
+    /// The column is counted from the start of the char's line, in the units
+    /// of `encoding`.
+    ///
+    /// Notes:
+    ///
+    /// - `char_idx` can be one-past-the-end, which will return the position
+    ///   of the end of the last line.
+    /// - If `char_idx` is between the CR and LF of a CRLF pair, the position
+    ///   of the start of the CRLF is returned, since columns end at the start
+    ///   of the line break.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn char_to_line_col(&self, char_idx: usize, encoding: PositionEncoding) -> LineCol {
+        self.try_char_to_line_col(char_idx, encoding).unwrap()
+    }
+
+    /// Returns the char index of the given line/column position.
+    ///
+    /// The column is interpreted in the units of `encoding`, and can be at
+    /// most the length of the line excluding its line break.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos.line` is out of bounds (i.e. `pos.line >= len_lines()`),
+    /// if `pos.col` is past the end of the line, or if `pos.col` is in the
+    /// middle of a char.
+    #[inline]
+    pub fn line_col_to_char(&self, pos: LineCol, encoding: PositionEncoding) -> usize {
+        self.try_line_col_to_char(pos, encoding).unwrap()
+    }
+
     /// Returns the byte index of the start of the given line.
     ///
     /// Notes:
@@ -801,6 +856,41 @@ impl<'a> RopeSlice<'a> {
         }
     }
 
+    /// Creates an iterator over the chars of the `RopeSlice`, along with their
+    /// positions.
+    ///
+    /// Each char is yielded as a `(char_idx, byte_idx, char)` tuple.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn char_indices(&self) -> CharIndices<'a> {
+        CharIndices::new(self.chars(), 0, 0)
+    }
+
+    /// Creates an iterator over the chars of the `RopeSlice`, along with their
+    /// positions, starting at char `char_idx`.
+    ///
+    /// If `char_idx == len_chars()` then an iterator at the end of the
+    /// `RopeSlice` is created (i.e. `next()` will return `None`).
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn char_indices_at(&self, char_idx: usize) -> CharIndices<'a> {
+        if let Some(out) = self.get_char_indices_at(char_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of RopeSlice: char index {}, RopeSlice char length {}",
+                char_idx,
+                self.len_chars()
+            );
+        }
+    }
+
     /// Creates an iterator over the lines of the `RopeSlice`.
     ///
     /// Runs in O(log N) time.
@@ -851,6 +941,76 @@ impl<'a> RopeSlice<'a> {
         }
     }
 
+    /// Creates an iterator over the lines of the `RopeSlice`, without their line
+    /// breaks.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn lines_without_endings(&self) -> LinesWithoutEndings<'a> {
+        LinesWithoutEndings::new(self.lines())
+    }
+
+    /// Creates an iterator over the lines of the `RopeSlice`, without their line
+    /// breaks, starting at line `line_idx`.
+    ///
+    /// If `line_idx == len_lines()` then an iterator at the end of the
+    /// `RopeSlice` is created (i.e. `next()` will return `None`).
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_idx` is out of bounds (i.e. `line_idx > len_lines()`).
+    #[inline]
+    pub fn lines_without_endings_at(&self, line_idx: usize) -> LinesWithoutEndings<'a> {
+        if let Some(out) = self.get_lines_without_endings_at(line_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of RopeSlice: line index {}, RopeSlice line length {}",
+                line_idx,
+                self.len_lines()
+            );
+        }
+    }
+
+    /// Creates an iterator over the lines of the `RopeSlice`, along with their
+    /// line breaks and positions.
+    ///
+    /// Each line is yielded as a [`LineInfo`](crate::iter::LineInfo), giving the line's text
+    /// without its line break, the kind of line break, and the char and byte
+    /// range of the whole line.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn line_infos(&self) -> LineInfos<'a> {
+        LineInfos::new(self.lines(), 0, 0)
+    }
+
+    /// Creates an iterator over the lines of the `RopeSlice`, along with their
+    /// line breaks and positions, starting at line `line_idx`.
+    ///
+    /// If `line_idx == len_lines()` then an iterator at the end of the
+    /// `RopeSlice` is created (i.e. `next()` will return `None`).
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_idx` is out of bounds (i.e. `line_idx > len_lines()`).
+    #[inline]
+    pub fn line_infos_at(&self, line_idx: usize) -> LineInfos<'a> {
+        if let Some(out) = self.get_line_infos_at(line_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of RopeSlice: line index {}, RopeSlice line length {}",
+                line_idx,
+                self.len_lines()
+            );
+        }
+    }
+
     /// Creates an iterator over the chunks of the `RopeSlice`.
     ///
     /// Runs in O(log N) time.
@@ -967,6 +1127,553 @@ impl<'a> RopeSlice<'a> {
             );
         }
     }
+
+    /// Creates an iterator over the chunks of the `RopeSlice`, along with their
+    /// positions.
+    ///
+    /// Each chunk is yielded as a `(chunk, byte_idx, char_idx, line_idx)`
+    /// tuple, where the indices are those of the start of the chunk.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn chunk_indices(&self) -> ChunkIndices<'a> {
+        ChunkIndices::new(self.chunks(), 0, 0, 0)
+    }
+
+    /// Creates an iterator over the chunks of the `RopeSlice`, along with their
+    /// positions, with the iterator starting at the chunk containing
+    /// `byte_idx`.
+    ///
+    /// If `byte_idx == len_bytes()` an iterator at the end of the `RopeSlice`
+    /// (yielding `None` on a call to `next()`) is created.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is out of bounds (i.e. `byte_idx > len_bytes()`).
+    #[inline]
+    pub fn chunk_indices_at_byte(&self, byte_idx: usize) -> ChunkIndices<'a> {
+        if let Some(out) = self.get_chunk_indices_at_byte(byte_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of RopeSlice: byte index {}, RopeSlice byte length {}",
+                byte_idx,
+                self.len_bytes()
+            );
+        }
+    }
+
+    /// Creates an iterator over the chunks of the `RopeSlice`, along with their
+    /// positions, with the iterator starting at the chunk containing
+    /// `char_idx`.
+    ///
+    /// If `char_idx == len_chars()` an iterator at the end of the `RopeSlice`
+    /// (yielding `None` on a call to `next()`) is created.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn chunk_indices_at_char(&self, char_idx: usize) -> ChunkIndices<'a> {
+        if let Some(out) = self.get_chunk_indices_at_char(char_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of RopeSlice: char index {}, RopeSlice char length {}",
+                char_idx,
+                self.len_chars()
+            );
+        }
+    }
+
+    /// Creates an iterator over the chunks of the `RopeSlice`, along with their
+    /// positions, with the iterator starting at the chunk containing
+    /// `line_break_idx`.
+    ///
+    /// If `line_break_idx == len_lines()` an iterator at the end of the `RopeSlice`
+    /// (yielding `None` on a call to `next()`) is created.
+    ///
+    /// See [`chunks_at_line_break()`](RopeSlice::chunks_at_line_break) for how
+    /// line break indices are interpreted.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_break_idx` is out of bounds (i.e. `line_break_idx > len_lines()`).
+    #[inline]
+    pub fn chunk_indices_at_line_break(&self, line_break_idx: usize) -> ChunkIndices<'a> {
+        if let Some(out) = self.get_chunk_indices_at_line_break(line_break_idx) {
+            out
+        } else {
+            panic!(
+                "Attempt to index past end of RopeSlice: line break index {}, RopeSlice line break max index {}",
+                line_break_idx,
+                self.len_lines()
+            );
+        }
+    }
+
+    /// Creates a reader over the contents of the `RopeSlice`.
+    ///
+    /// The reader implements `io::Read`, `io::BufRead`, and `io::Seek`.  See
+    /// [`RopeReader`] for details.
+    ///
+    /// Runs in O(log N) time.
+    #[inline]
+    pub fn reader(&self) -> RopeReader<'a> {
+        RopeReader::new(*self)
+    }
+
+    //-----------------------------------------------------------------------
+    // Grapheme methods
+
+    /// Creates an iterator over the extended grapheme clusters of the
+    /// `RopeSlice`.
+    ///
+    /// Graphemes are yielded as `RopeSlice`s, and are correctly found even
+    /// when they span chunk boundaries.
+    ///
+    /// Only available with the `graphemes` feature.
+    ///
+    /// Runs in O(log N) time.
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn graphemes(&self) -> Graphemes<'a> {
+        Graphemes::new(self)
+    }
+
+    /// Returns whether `char_idx` is on an extended grapheme cluster
+    /// boundary.
+    ///
+    /// The start and end of the `RopeSlice` are always boundaries.
+    ///
+    /// Only available with the `graphemes` feature.
+    ///
+    /// Runs in O(log N) time for each chunk that needs to be examined,
+    /// which is usually just one or two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn is_grapheme_boundary(&self, char_idx: usize) -> bool {
+        self.try_is_grapheme_boundary(char_idx).unwrap()
+    }
+
+    /// Returns the char index of the extended grapheme cluster boundary
+    /// before `char_idx`.
+    ///
+    /// Returns 0 if there is no boundary before `char_idx`.
+    ///
+    /// Only available with the `graphemes` feature.
+    ///
+    /// Runs in O(log N) time for each chunk that needs to be examined,
+    /// which is usually just one or two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn prev_grapheme_boundary(&self, char_idx: usize) -> usize {
+        self.try_prev_grapheme_boundary(char_idx).unwrap()
+    }
+
+    /// Returns the char index of the extended grapheme cluster boundary
+    /// after `char_idx`.
+    ///
+    /// Returns `len_chars()` if there is no boundary after `char_idx`.
+    ///
+    /// Only available with the `graphemes` feature.
+    ///
+    /// Runs in O(log N) time for each chunk that needs to be examined,
+    /// which is usually just one or two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn next_grapheme_boundary(&self, char_idx: usize) -> usize {
+        self.try_next_grapheme_boundary(char_idx).unwrap()
+    }
+
+    //-----------------------------------------------------------------------
+    // Search methods
+
+    /// Returns the char index of the first occurrence of `pattern` in the
+    /// `RopeSlice`, or `None` if there isn't one.
+    ///
+    /// Occurrences that span chunk boundaries are found without copying
+    /// the text.  An empty `pattern` matches at index 0.
+    ///
+    /// Runs in O(N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn find(&self, pattern: &str) -> Option<usize> {
+        self.find_from(0, pattern)
+    }
+
+    /// Returns the char index of the last occurrence of `pattern` in the
+    /// `RopeSlice`, or `None` if there isn't one.
+    ///
+    /// An empty `pattern` matches at `len_chars()`.
+    ///
+    /// Runs in O(N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn rfind(&self, pattern: &str) -> Option<usize> {
+        self.rfind_from(self.len_chars(), pattern)
+    }
+
+    /// Returns the char index of the first occurrence of `pattern` that
+    /// starts at or after `char_idx`, or `None` if there isn't one.
+    ///
+    /// Runs in O(log N + K + M) time, where K is the length of the text
+    /// searched and M is the length of `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn find_from(&self, char_idx: usize, pattern: &str) -> Option<usize> {
+        self.try_find_from(char_idx, pattern).unwrap()
+    }
+
+    /// Returns the char index of the last occurrence of `pattern` that
+    /// ends at or before `char_idx`, or `None` if there isn't one.
+    ///
+    /// Runs in O(log N + K + M) time, where K is the length of the text
+    /// searched and M is the length of `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn rfind_from(&self, char_idx: usize, pattern: &str) -> Option<usize> {
+        self.try_rfind_from(char_idx, pattern).unwrap()
+    }
+
+    /// Creates an iterator over the non-overlapping occurrences of
+    /// `pattern` in the `RopeSlice`, from front to back.
+    ///
+    /// Each occurrence is yielded as its char range.  An empty `pattern`
+    /// matches at every char boundary.
+    ///
+    /// Runs in O(log N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn matches(&self, pattern: &str) -> Matches<'a> {
+        Matches::new(self, pattern, 0, false)
+    }
+
+    /// Creates an iterator over the non-overlapping occurrences of
+    /// `pattern` in the `RopeSlice`, from back to front.
+    ///
+    /// When occurrences overlap, this can find different ones than
+    /// [`matches()`](RopeSlice::matches): for example, `"aa"` is found at
+    /// `1..3` in `"aaa"`, rather than at `0..2`.
+    ///
+    /// Runs in O(log N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn rmatches(&self, pattern: &str) -> Matches<'a> {
+        Matches::new(self, pattern, self.len_chars(), true)
+    }
+
+    /// Returns the char range of the first case-insensitive occurrence of
+    /// `pattern` in the `RopeSlice`, or `None` if there isn't one.
+    ///
+    /// The text and `pattern` are compared after full Unicode case folding,
+    /// so the occurrence can be a different length than `pattern`: for
+    /// example, `"SS"` is found at `4..5` in `"Straße"`.  Occurrences always
+    /// cover whole chars.
+    ///
+    /// Runs in O(N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn find_ignore_case(&self, pattern: &str) -> Option<Range<usize>> {
+        self.find_from_ignore_case(0, pattern)
+    }
+
+    /// Returns the char range of the last case-insensitive occurrence of
+    /// `pattern` in the `RopeSlice`, or `None` if there isn't one.
+    ///
+    /// Runs in O(N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn rfind_ignore_case(&self, pattern: &str) -> Option<Range<usize>> {
+        self.rfind_from_ignore_case(self.len_chars(), pattern)
+    }
+
+    /// Returns the char range of the first case-insensitive occurrence of
+    /// `pattern` that starts at or after `char_idx`, or `None` if there isn't
+    /// one.
+    ///
+    /// Runs in O(log N + K + M) time, where K is the length of the text
+    /// searched and M is the length of `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn find_from_ignore_case(&self, char_idx: usize, pattern: &str) -> Option<Range<usize>> {
+        self.try_find_from_ignore_case(char_idx, pattern).unwrap()
+    }
+
+    /// Returns the char range of the last case-insensitive occurrence of
+    /// `pattern` that ends at or before `char_idx`, or `None` if there isn't
+    /// one.
+    ///
+    /// Runs in O(log N + K + M) time, where K is the length of the text
+    /// searched and M is the length of `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[inline]
+    pub fn rfind_from_ignore_case(&self, char_idx: usize, pattern: &str) -> Option<Range<usize>> {
+        self.try_rfind_from_ignore_case(char_idx, pattern).unwrap()
+    }
+
+    /// Creates an iterator over the non-overlapping case-insensitive
+    /// occurrences of `pattern` in the `RopeSlice`, from front to back.
+    ///
+    /// See [`find_ignore_case()`](RopeSlice::find_ignore_case) for how the text is
+    /// compared.  An empty `pattern` matches at every char boundary.
+    ///
+    /// Runs in O(log N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn matches_ignore_case(&self, pattern: &str) -> MatchesIgnoreCase<'a> {
+        MatchesIgnoreCase::new(self, pattern, 0, false)
+    }
+
+    /// Creates an iterator over the non-overlapping case-insensitive
+    /// occurrences of `pattern` in the `RopeSlice`, from back to front.
+    ///
+    /// Runs in O(log N + M) time, where M is the length of `pattern`.
+    #[inline]
+    pub fn rmatches_ignore_case(&self, pattern: &str) -> MatchesIgnoreCase<'a> {
+        MatchesIgnoreCase::new(self, pattern, self.len_chars(), true)
+    }
+
+    //-----------------------------------------------------------------------
+    // Split methods
+
+    /// Creates an iterator over the pieces of the `RopeSlice` separated by
+    /// `pattern`, from front to back.
+    ///
+    /// `pattern` can be a `char`, a `&str`, or a `FnMut(char) -> bool`
+    /// predicate, and the pieces are the same as `str::split()` would give.
+    /// Each piece is yielded as a `RopeSlice`.
+    ///
+    /// Runs in O(1) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ropey::Rope;
+    /// let rope = Rope::from_str("name,age\tcity");
+    /// let slice = rope.slice(..);
+    /// let fields: Vec<_> = slice.split(|c| c == ',' || c == '\t').collect();
+    ///
+    /// assert_eq!(fields, ["name", "age", "city"]);
+    /// ```
+    #[inline]
+    pub fn split<P: Pattern>(&self, pattern: P) -> Split<'a, P> {
+        Split::new(self, pattern)
+    }
+
+    /// Creates an iterator over the pieces of the `RopeSlice` separated by
+    /// `pattern`, from back to front.
+    ///
+    /// The pieces are the same as `str::rsplit()` would give.
+    ///
+    /// Runs in O(1) time.
+    #[inline]
+    pub fn rsplit<P: Pattern>(&self, pattern: P) -> Split<'a, P> {
+        Split::new_at_end(self, pattern).reversed()
+    }
+
+    /// Like [`split()`](RopeSlice::split), except that a trailing empty piece is
+    /// skipped.
+    ///
+    /// This is useful for text where `pattern` terminates each piece rather
+    /// than separating them, such as lines that each end with a line break.
+    ///
+    /// Runs in O(1) time.
+    #[inline]
+    pub fn split_terminator<P: Pattern>(&self, pattern: P) -> Split<'a, P> {
+        Split::new(self, pattern).terminator()
+    }
+
+    /// Like [`split()`](RopeSlice::split), except that there are at most `n`
+    /// pieces.
+    ///
+    /// The last piece holds the rest of the `RopeSlice`, including any further
+    /// occurrences of `pattern`.
+    ///
+    /// Runs in O(1) time.
+    #[inline]
+    pub fn splitn<P: Pattern>(&self, n: usize, pattern: P) -> Split<'a, P> {
+        Split::new(self, pattern).limit(n)
+    }
+
+    /// Creates an iterator over the pieces of the `RopeSlice` separated by
+    /// whitespace, from front to back.
+    ///
+    /// Any amount of whitespace separates pieces, and leading and trailing
+    /// whitespace is ignored, as with `str::split_whitespace()`.
+    ///
+    /// Runs in O(1) time.
+    #[inline]
+    pub fn split_whitespace(&self) -> Split<'a, fn(char) -> bool> {
+        Split::new(self, char::is_whitespace as fn(char) -> bool).skip_empty()
+    }
+
+    //-----------------------------------------------------------------------
+    // Regex methods
+
+    /// Returns the first match of `regex` in the `RopeSlice`, or `None` if there
+    /// isn't one.
+    ///
+    /// The regex is run directly over the `RopeSlice`'s chunks, without copying
+    /// the text.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(N) time.
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex_find(&self, regex: &Regex) -> Option<RegexMatch> {
+        self.regex_find_iter(regex).next()
+    }
+
+    /// Returns the last match of `regex` in the `RopeSlice`, or `None` if there
+    /// isn't one.
+    ///
+    /// See [`regex_rfind_iter()`](RopeSlice::regex_rfind_iter) for how matches
+    /// are found when searching backward.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(N) time.
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex_rfind(&self, regex: &Regex) -> Option<RegexMatch> {
+        self.regex_rfind_iter(regex).next()
+    }
+
+    /// Returns the first match of `regex` that starts at or after
+    /// `char_idx`, or `None` if there isn't one.
+    ///
+    /// The text before `char_idx` is still taken into account for
+    /// look-around assertions like `\b`.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(log N + K) time, where K is the length of the text
+    /// searched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex_find_from(&self, char_idx: usize, regex: &Regex) -> Option<RegexMatch> {
+        self.try_regex_find_from(char_idx, regex).unwrap()
+    }
+
+    /// Returns the match of `regex` found by searching backward from
+    /// `char_idx`, or `None` if there isn't one.
+    ///
+    /// The match ends at or before `char_idx`.  See
+    /// [`regex_rfind_iter()`](RopeSlice::regex_rfind_iter) for how matches are
+    /// found when searching backward.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(log N + K) time, where K is the length of the text
+    /// searched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is out of bounds (i.e. `char_idx > len_chars()`).
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex_rfind_from(&self, char_idx: usize, regex: &Regex) -> Option<RegexMatch> {
+        self.try_regex_rfind_from(char_idx, regex).unwrap()
+    }
+
+    /// Creates an iterator over the non-overlapping matches of `regex` in
+    /// the `RopeSlice`, from front to back.
+    ///
+    /// Matches are found with the regex's usual leftmost-first semantics,
+    /// and empty matches never split a char.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(1) time.
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex_find_iter<'r>(&self, regex: &'r Regex) -> RegexMatches<'a, 'r> {
+        RegexMatches::new(self, regex, 0, false)
+    }
+
+    /// Creates an iterator over the non-overlapping matches of `regex` in
+    /// the `RopeSlice`, from back to front.
+    ///
+    /// Searching backward finds the match that starts the furthest back
+    /// among those that start as late as possible: for example, `\w+` finds
+    /// `"world"` rather than `"d"` at the end of `"hello world"`.  This can
+    /// find different matches than a forward search.
+    ///
+    /// Only available with the `regex` feature.
+    ///
+    /// Runs in O(1) time.
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex_rfind_iter<'r>(&self, regex: &'r Regex) -> RegexMatches<'a, 'r> {
+        RegexMatches::new(self, regex, self.len_bytes(), true)
+    }
+
+    //-----------------------------------------------------------------------
+    // Multi-pattern search methods
+
+    /// Creates an iterator over the non-overlapping matches of the patterns
+    /// of an Aho-Corasick `automaton` in the `RopeSlice`.
+    ///
+    /// Which matches are reported (e.g. leftmost-longest) is determined by
+    /// the automaton's match kind.  See the
+    /// [`multi_search`](crate::multi_search) module for details.
+    ///
+    /// Only available with the `multi_search` feature.
+    ///
+    /// Runs in O(log N) time.
+    #[cfg(feature = "multi_search")]
+    #[inline]
+    pub fn multi_find_iter<'r, A: Automaton>(&self, automaton: &'r A) -> MultiMatches<'a, 'r, A> {
+        MultiMatches::new(self, automaton)
+    }
+
+    /// Creates an iterator over all matches of the patterns of an
+    /// Aho-Corasick `automaton` in the `RopeSlice`, including overlapping ones.
+    ///
+    /// Only available with the `multi_search` feature.
+    ///
+    /// Runs in O(log N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the automaton's match kind isn't `MatchKind::Standard`.
+    #[cfg(feature = "multi_search")]
+    #[inline]
+    pub fn multi_find_overlapping_iter<'r, A: Automaton>(
+        &self,
+        automaton: &'r A,
+    ) -> OverlappingMultiMatches<'a, 'r, A> {
+        OverlappingMultiMatches::new(self, automaton)
+    }
 }
 
 /// # Non-Panicking
@@ -1092,6 +1799,88 @@ impl<'a> RopeSlice<'a> {
         }
     }
 
+    /// Non-panicking version of [`char_to_line_col()`](RopeSlice::char_to_line_col).
+    #[inline]
+    pub fn try_char_to_line_col(
+        &self,
+        char_idx: usize,
+        encoding: PositionEncoding,
+    ) -> Result<LineCol> {
+        let line_idx = self.try_char_to_line(char_idx)?;
+        let line_start = self.line_to_char(line_idx);
+        let char_idx = if char_idx > line_start
+            && char_idx < self.len_chars()
+            && self.char(char_idx) == '\n'
+            && self.char(char_idx - 1) == '\r'
+        {
+            char_idx - 1
+        } else {
+            char_idx
+        };
+        Ok(LineCol::new(
+            line_idx,
+            self.measure_char_range(line_start, char_idx, encoding),
+        ))
+    }
+
+    /// Non-panicking version of [`line_col_to_char()`](RopeSlice::line_col_to_char).
+    pub fn try_line_col_to_char(&self, pos: LineCol, encoding: PositionEncoding) -> Result<usize> {
+        // Bounds checks
+        if pos.line >= self.len_lines() {
+            return Err(Error::LineIndexOutOfBounds(pos.line, self.len_lines()));
+        }
+        let line_start = self.line_to_char(pos.line);
+        let line_len =
+            self.measure_char_range(line_start, self.line_content_end(pos.line), encoding);
+        if pos.col > line_len {
+            return Err(Error::ColumnIndexOutOfBounds(pos.col, line_len));
+        }
+
+        match encoding {
+            PositionEncoding::Utf8 => {
+                let byte_idx = self.char_to_byte(line_start) + pos.col;
+                let char_idx = self.byte_to_char(byte_idx);
+                if self.char_to_byte(char_idx) != byte_idx {
+                    return Err(Error::ColumnIndexNotCharBoundary(pos.col));
+                }
+                Ok(char_idx)
+            }
+            PositionEncoding::Utf16 => {
+                let utf16_cu_idx = self.char_to_utf16_cu(line_start) + pos.col;
+                let char_idx = self.utf16_cu_to_char(utf16_cu_idx);
+                if self.char_to_utf16_cu(char_idx) != utf16_cu_idx {
+                    return Err(Error::ColumnIndexNotCharBoundary(pos.col));
+                }
+                Ok(char_idx)
+            }
+            PositionEncoding::Utf32 => Ok(line_start + pos.col),
+        }
+    }
+
+    /// Returns the length of the char range `[start, end)` in the units of
+    /// `encoding`.
+    fn measure_char_range(&self, start: usize, end: usize, encoding: PositionEncoding) -> usize {
+        match encoding {
+            PositionEncoding::Utf8 => self.char_to_byte(end) - self.char_to_byte(start),
+            PositionEncoding::Utf16 => self.char_to_utf16_cu(end) - self.char_to_utf16_cu(start),
+            PositionEncoding::Utf32 => end - start,
+        }
+    }
+
+    /// Returns the char index of the end of the given line's contents, i.e.
+    /// the start of its line break, if any.
+    fn line_content_end(&self, line_idx: usize) -> usize {
+        let end = self.line_to_char(line_idx + 1);
+        if line_idx + 1 >= self.len_lines() {
+            // The last line never has a line break.
+            end
+        } else if end >= 2 && self.char(end - 1) == '\n' && self.char(end - 2) == '\r' {
+            end - 2
+        } else {
+            end - 1
+        }
+    }
+
     /// Non-panicking version of [`line_to_byte()`](RopeSlice::line_to_byte).
     #[inline]
     pub fn try_line_to_byte(&self, line_idx: usize) -> Result<usize> {
@@ -1489,6 +2278,17 @@ impl<'a> RopeSlice<'a> {
         }
     }
 
+    /// Non-panicking version of [`char_indices_at()`](RopeSlice::char_indices_at).
+    #[inline]
+    pub fn get_char_indices_at(&self, char_idx: usize) -> Option<CharIndices<'a>> {
+        let chars = self.get_chars_at(char_idx)?;
+        Some(CharIndices::new(
+            chars,
+            char_idx,
+            self.char_to_byte(char_idx),
+        ))
+    }
+
     /// Non-panicking version of [`lines_at()`](RopeSlice::lines_at).
     #[inline]
     pub fn get_lines_at(&self, line_idx: usize) -> Option<Lines<'a>> {
@@ -1523,6 +2323,23 @@ impl<'a> RopeSlice<'a> {
         }
     }
 
+    /// Non-panicking version of [`lines_without_endings_at()`](RopeSlice::lines_without_endings_at).
+    #[inline]
+    pub fn get_lines_without_endings_at(&self, line_idx: usize) -> Option<LinesWithoutEndings<'a>> {
+        self.get_lines_at(line_idx).map(LinesWithoutEndings::new)
+    }
+
+    /// Non-panicking version of [`line_infos_at()`](RopeSlice::line_infos_at).
+    #[inline]
+    pub fn get_line_infos_at(&self, line_idx: usize) -> Option<LineInfos<'a>> {
+        let lines = self.get_lines_at(line_idx)?;
+        Some(LineInfos::new(
+            lines,
+            self.line_to_char(line_idx),
+            self.line_to_byte(line_idx),
+        ))
+    }
+
     /// Non-panicking version of [`chunks_at_byte()`](RopeSlice::chunks_at_byte).
     #[inline]
     pub fn get_chunks_at_byte(&self, byte_idx: usize) -> Option<(Chunks<'a>, usize, usize, usize)> {
@@ -1715,6 +2532,133 @@ impl<'a> RopeSlice<'a> {
             None
         }
     }
+
+    /// Non-panicking version of [`chunk_indices_at_byte()`](RopeSlice::chunk_indices_at_byte).
+    #[inline]
+    pub fn get_chunk_indices_at_byte(&self, byte_idx: usize) -> Option<ChunkIndices<'a>> {
+        let (chunks, byte_idx, char_idx, line_idx) = self.get_chunks_at_byte(byte_idx)?;
+        Some(ChunkIndices::new(chunks, byte_idx, char_idx, line_idx))
+    }
+
+    /// Non-panicking version of [`chunk_indices_at_char()`](RopeSlice::chunk_indices_at_char).
+    #[inline]
+    pub fn get_chunk_indices_at_char(&self, char_idx: usize) -> Option<ChunkIndices<'a>> {
+        let (chunks, byte_idx, char_idx, line_idx) = self.get_chunks_at_char(char_idx)?;
+        Some(ChunkIndices::new(chunks, byte_idx, char_idx, line_idx))
+    }
+
+    /// Non-panicking version of [`chunk_indices_at_line_break()`](RopeSlice::chunk_indices_at_line_break).
+    #[inline]
+    pub fn get_chunk_indices_at_line_break(
+        &self,
+        line_break_idx: usize,
+    ) -> Option<ChunkIndices<'a>> {
+        let (chunks, byte_idx, char_idx, line_idx) =
+            self.get_chunks_at_line_break(line_break_idx)?;
+        Some(ChunkIndices::new(chunks, byte_idx, char_idx, line_idx))
+    }
+
+    /// Non-panicking version of [`is_grapheme_boundary()`](RopeSlice::is_grapheme_boundary).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn try_is_grapheme_boundary(&self, char_idx: usize) -> Result<bool> {
+        let byte_idx = self.try_char_to_byte(char_idx)?;
+        Ok(GraphemeStepper::new(self, byte_idx).is_boundary(self))
+    }
+
+    /// Non-panicking version of [`prev_grapheme_boundary()`](RopeSlice::prev_grapheme_boundary).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn try_prev_grapheme_boundary(&self, char_idx: usize) -> Result<usize> {
+        let byte_idx = self.try_char_to_byte(char_idx)?;
+        let boundary = GraphemeStepper::new(self, byte_idx).prev_boundary(self);
+        Ok(self.byte_to_char(boundary))
+    }
+
+    /// Non-panicking version of [`next_grapheme_boundary()`](RopeSlice::next_grapheme_boundary).
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn try_next_grapheme_boundary(&self, char_idx: usize) -> Result<usize> {
+        let byte_idx = self.try_char_to_byte(char_idx)?;
+        let boundary = GraphemeStepper::new(self, byte_idx).next_boundary(self);
+        Ok(self.byte_to_char(boundary))
+    }
+
+    /// Non-panicking version of [`find_from()`](RopeSlice::find_from).
+    #[inline]
+    pub fn try_find_from(&self, char_idx: usize, pattern: &str) -> Result<Option<usize>> {
+        if char_idx <= self.len_chars() {
+            Ok(Matches::new(self, pattern, char_idx, false)
+                .next()
+                .map(|m| m.start))
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`rfind_from()`](RopeSlice::rfind_from).
+    #[inline]
+    pub fn try_rfind_from(&self, char_idx: usize, pattern: &str) -> Result<Option<usize>> {
+        if char_idx <= self.len_chars() {
+            Ok(Matches::new(self, pattern, char_idx, true)
+                .next()
+                .map(|m| m.start))
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`find_from_ignore_case()`](RopeSlice::find_from_ignore_case).
+    #[inline]
+    pub fn try_find_from_ignore_case(
+        &self,
+        char_idx: usize,
+        pattern: &str,
+    ) -> Result<Option<Range<usize>>> {
+        if char_idx <= self.len_chars() {
+            Ok(MatchesIgnoreCase::new(self, pattern, char_idx, false).next())
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`rfind_from_ignore_case()`](RopeSlice::rfind_from_ignore_case).
+    #[inline]
+    pub fn try_rfind_from_ignore_case(
+        &self,
+        char_idx: usize,
+        pattern: &str,
+    ) -> Result<Option<Range<usize>>> {
+        if char_idx <= self.len_chars() {
+            Ok(MatchesIgnoreCase::new(self, pattern, char_idx, true).next())
+        } else {
+            Err(Error::CharIndexOutOfBounds(char_idx, self.len_chars()))
+        }
+    }
+
+    /// Non-panicking version of [`regex_find_from()`](RopeSlice::regex_find_from).
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn try_regex_find_from(
+        &self,
+        char_idx: usize,
+        regex: &Regex,
+    ) -> Result<Option<RegexMatch>> {
+        let byte_idx = self.try_char_to_byte(char_idx)?;
+        Ok(RegexMatches::new(self, regex, byte_idx, false).next())
+    }
+
+    /// Non-panicking version of [`regex_rfind_from()`](RopeSlice::regex_rfind_from).
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn try_regex_rfind_from(
+        &self,
+        char_idx: usize,
+        regex: &Regex,
+    ) -> Result<Option<RegexMatch>> {
+        let byte_idx = self.try_char_to_byte(char_idx)?;
+        Ok(RegexMatches::new(self, regex, byte_idx, true).next())
+    }
 }
 
 //==============================================================
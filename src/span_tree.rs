@@ -0,0 +1,579 @@
+//! A sequence of spans kept in a balanced tree.
+//!
+//! A `SpanTree` holds a sequence of items that each cover some length of
+//! text, and keeps track of the total length of the items before any given
+//! item.  Finding items by position, and inserting, removing, and resizing
+//! items anywhere in the sequence all take O(log n) time, since none of
+//! them need to touch the items after the edit.
+//!
+//! It's a treap whose nodes live in an arena and link to their parents, so
+//! an item can be referred to by a stable [`SpanId`] for as long as it's in
+//! the tree.
+
+use std::ops::Range;
+
+/// An item that covers some length of text.
+pub(crate) trait Span {
+    fn span_len(&self) -> usize;
+}
+
+/// Refers to an item in a `SpanTree`, for as long as the item is in it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct SpanId(usize);
+
+#[derive(Debug, Clone)]
+struct SpanNode<T> {
+    item: T,
+    priority: u64,
+    parent: Option<usize>,
+    left: Option<usize>,
+    right: Option<usize>,
+    /// Number of items in this node's subtree, and in its left subtree.
+    /// The latter is kept here so that searches only touch the nodes on
+    /// their path.
+    count: usize,
+    left_count: usize,
+    /// Total span length of the items in this node's subtree, and in its
+    /// left subtree.
+    len: usize,
+    left_len: usize,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SpanTree<T> {
+    /// Unused slots are `None`, and are listed in `free`.
+    nodes: Vec<Option<SpanNode<T>>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    /// Source of node priorities.
+    seed: u64,
+}
+
+impl<T: Span> SpanTree<T> {
+    pub fn new() -> Self {
+        SpanTree {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            seed: 0,
+        }
+    }
+
+    /// Number of items in the tree.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count_of(self.root)
+    }
+
+    /// Returns the id of the item at `rank`, or `None` if `rank` is out of
+    /// bounds.
+    pub fn get(&self, mut rank: usize) -> Option<SpanId> {
+        let mut node = self.root;
+        while let Some(i) = node {
+            let n = self.node(i);
+            let left_count = n.left_count;
+            if rank < left_count {
+                node = n.left;
+            } else if rank == left_count {
+                return Some(SpanId(i));
+            } else {
+                rank -= left_count + 1;
+                node = n.right;
+            }
+        }
+        None
+    }
+
+    #[inline]
+    pub fn item(&self, id: SpanId) -> &T {
+        &self.node(id.0).item
+    }
+
+    /// Returns the rank of the item `id`.
+    pub fn rank(&self, id: SpanId) -> usize {
+        let mut rank = self.node(id.0).left_count;
+        let mut i = id.0;
+        while let Some(parent) = self.node(i).parent {
+            let p = self.node(parent);
+            if p.right == Some(i) {
+                rank += p.left_count + 1;
+            }
+            i = parent;
+        }
+        rank
+    }
+
+    /// Total span length of the items before the item `id`.
+    pub fn offset(&self, id: SpanId) -> usize {
+        let mut offset = self.node(id.0).left_len;
+        let mut i = id.0;
+        while let Some(parent) = self.node(i).parent {
+            let p = self.node(parent);
+            if p.right == Some(i) {
+                offset += p.left_len + p.item.span_len();
+            }
+            i = parent;
+        }
+        offset
+    }
+
+    /// Total span length of the first `rank` items.
+    pub fn offset_of_rank(&self, mut rank: usize) -> usize {
+        let mut offset = 0;
+        let mut node = self.root;
+        while let Some(i) = node {
+            let n = self.node(i);
+            let left_count = n.left_count;
+            if rank <= left_count {
+                node = n.left;
+            } else {
+                rank -= left_count + 1;
+                offset += n.left_len + n.item.span_len();
+                node = n.right;
+            }
+        }
+        offset
+    }
+
+    /// Returns the number of leading items for which `pred` returns true,
+    /// like `slice::partition_point()`.
+    ///
+    /// `pred` is passed each item's start offset along with the item, and
+    /// must return true for a prefix of the items and false for the rest.
+    pub fn partition_point<F>(&self, mut pred: F) -> usize
+    where
+        F: FnMut(usize, &T) -> bool,
+    {
+        let mut rank = 0;
+        let mut offset = 0;
+        let mut node = self.root;
+        while let Some(i) = node {
+            let n = self.node(i);
+            let start = offset + n.left_len;
+            if pred(start, &n.item) {
+                rank += n.left_count + 1;
+                offset = start + n.item.span_len();
+                node = n.right;
+            } else {
+                node = n.left;
+            }
+        }
+        rank
+    }
+
+    /// Modifies the item `id` in place, including its span length.
+    pub fn update_item<F>(&mut self, id: SpanId, f: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        f(&mut self.node_mut(id.0).item);
+        let mut node = Some(id.0);
+        while let Some(i) = node {
+            self.update(i);
+            node = self.node(i).parent;
+        }
+    }
+
+    /// Inserts `item` so that it ends up at `rank`, and returns its id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rank > count()`.
+    pub fn insert(&mut self, rank: usize, item: T) -> SpanId {
+        assert!(rank <= self.count());
+        let i = self.alloc(item);
+        let (left, right) = self.split(self.root, rank);
+        let root = self.merge(left, Some(i));
+        let root = self.merge(root, right);
+        self.set_root(root);
+        SpanId(i)
+    }
+
+    /// Removes the items at `ranks`, and returns them in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is backwards or out of bounds.
+    pub fn remove_range(&mut self, ranks: Range<usize>) -> Vec<T> {
+        assert!(ranks.start <= ranks.end && ranks.end <= self.count());
+        let (rest, right) = self.split(self.root, ranks.end);
+        let (left, middle) = self.split(rest, ranks.start);
+        let root = self.merge(left, right);
+        self.set_root(root);
+
+        let mut items = Vec::with_capacity(ranks.end - ranks.start);
+        self.take_items(middle, &mut items);
+        items
+    }
+
+    /// Creates an iterator over the items at `ranks`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is backwards or out of bounds.
+    pub fn iter_range(&self, ranks: Range<usize>) -> SpanIter<'_, T> {
+        assert!(ranks.start <= ranks.end && ranks.end <= self.count());
+        let remaining = ranks.end - ranks.start;
+        SpanIter {
+            tree: self,
+            front: if remaining > 0 {
+                self.get(ranks.start)
+            } else {
+                None
+            },
+            back: if remaining > 0 {
+                self.get(ranks.end - 1)
+            } else {
+                None
+            },
+            remaining: remaining,
+        }
+    }
+
+    //-----------------------------------------------------------------------
+    // Internal utilities
+
+    #[inline(always)]
+    fn node(&self, i: usize) -> &SpanNode<T> {
+        self.nodes[i].as_ref().unwrap()
+    }
+
+    #[inline(always)]
+    fn node_mut(&mut self, i: usize) -> &mut SpanNode<T> {
+        self.nodes[i].as_mut().unwrap()
+    }
+
+    #[inline(always)]
+    fn count_of(&self, node: Option<usize>) -> usize {
+        node.map(|i| self.node(i).count).unwrap_or(0)
+    }
+
+    #[inline(always)]
+    fn len_of(&self, node: Option<usize>) -> usize {
+        node.map(|i| self.node(i).len).unwrap_or(0)
+    }
+
+    fn alloc(&mut self, item: T) -> usize {
+        // Splitmix64, to get well-distributed priorities from a counter.
+        self.seed = self.seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut priority = self.seed;
+        priority = (priority ^ (priority >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        priority = (priority ^ (priority >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        priority ^= priority >> 31;
+
+        let node = SpanNode {
+            len: item.span_len(),
+            item: item,
+            priority: priority,
+            parent: None,
+            left: None,
+            right: None,
+            count: 1,
+            left_count: 0,
+            left_len: 0,
+        };
+        if let Some(i) = self.free.pop() {
+            self.nodes[i] = Some(node);
+            i
+        } else {
+            self.nodes.push(Some(node));
+            self.nodes.len() - 1
+        }
+    }
+
+    /// Moves the items of the subtree `node` onto the end of `items`, in
+    /// order, and frees its nodes.
+    fn take_items(&mut self, node: Option<usize>, items: &mut Vec<T>) {
+        if let Some(i) = node {
+            let n = self.nodes[i].take().unwrap();
+            self.free.push(i);
+            self.take_items(n.left, items);
+            items.push(n.item);
+            self.take_items(n.right, items);
+        }
+    }
+
+    fn set_root(&mut self, root: Option<usize>) {
+        self.root = root;
+        if let Some(i) = root {
+            self.node_mut(i).parent = None;
+        }
+    }
+
+    /// Recomputes the summary of node `i` from its children, and points the
+    /// children back at it.
+    fn update(&mut self, i: usize) {
+        let (left, right) = (self.node(i).left, self.node(i).right);
+        let (left_count, left_len) = (self.count_of(left), self.len_of(left));
+        let count = left_count + 1 + self.count_of(right);
+        let len = left_len + self.node(i).item.span_len() + self.len_of(right);
+        for child in left.into_iter().chain(right) {
+            self.node_mut(child).parent = Some(i);
+        }
+        let n = self.node_mut(i);
+        n.count = count;
+        n.left_count = left_count;
+        n.len = len;
+        n.left_len = left_len;
+    }
+
+    /// Joins two subtrees, with all of `a`'s items before `b`'s.
+    fn merge(&mut self, a: Option<usize>, b: Option<usize>) -> Option<usize> {
+        match (a, b) {
+            (None, b) => b,
+            (a, None) => a,
+            (Some(a), Some(b)) => {
+                if self.node(a).priority > self.node(b).priority {
+                    let right = self.node(a).right;
+                    let merged = self.merge(right, Some(b));
+                    self.node_mut(a).right = merged;
+                    self.update(a);
+                    Some(a)
+                } else {
+                    let left = self.node(b).left;
+                    let merged = self.merge(Some(a), left);
+                    self.node_mut(b).left = merged;
+                    self.update(b);
+                    Some(b)
+                }
+            }
+        }
+    }
+
+    /// Splits a subtree into its first `rank` items and the rest.
+    fn split(&mut self, node: Option<usize>, rank: usize) -> (Option<usize>, Option<usize>) {
+        let i = match node {
+            Some(i) => i,
+            None => return (None, None),
+        };
+        let left = self.node(i).left;
+        let left_count = self.count_of(left);
+        if rank <= left_count {
+            let (a, b) = self.split(left, rank);
+            self.node_mut(i).left = b;
+            self.update(i);
+            (a, Some(i))
+        } else {
+            let right = self.node(i).right;
+            let (a, b) = self.split(right, rank - left_count - 1);
+            self.node_mut(i).right = a;
+            self.update(i);
+            (Some(i), b)
+        }
+    }
+
+    /// The node after `i` in order.
+    fn next_node(&self, i: usize) -> Option<usize> {
+        if let Some(mut j) = self.node(i).right {
+            while let Some(left) = self.node(j).left {
+                j = left;
+            }
+            return Some(j);
+        }
+        let mut j = i;
+        while let Some(parent) = self.node(j).parent {
+            if self.node(parent).left == Some(j) {
+                return Some(parent);
+            }
+            j = parent;
+        }
+        None
+    }
+
+    /// The node before `i` in order.
+    fn prev_node(&self, i: usize) -> Option<usize> {
+        if let Some(mut j) = self.node(i).left {
+            while let Some(right) = self.node(j).right {
+                j = right;
+            }
+            return Some(j);
+        }
+        let mut j = i;
+        while let Some(parent) = self.node(j).parent {
+            if self.node(parent).right == Some(j) {
+                return Some(parent);
+            }
+            j = parent;
+        }
+        None
+    }
+}
+
+impl<T: Span> Default for SpanTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//==============================================================
+
+/// An iterator over a range of the items of a `SpanTree`.
+#[derive(Debug, Clone)]
+pub(crate) struct SpanIter<'a, T> {
+    tree: &'a SpanTree<T>,
+    front: Option<SpanId>,
+    back: Option<SpanId>,
+    remaining: usize,
+}
+
+impl<'a, T: Span> Iterator for SpanIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let id = self.front.unwrap();
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.front = self.tree.next_node(id.0).map(SpanId);
+        }
+        Some(self.tree.item(id))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T: Span> DoubleEndedIterator for SpanIter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let id = self.back.unwrap();
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.back = self.tree.prev_node(id.0).map(SpanId);
+        }
+        Some(self.tree.item(id))
+    }
+}
+
+impl<'a, T: Span> ExactSizeIterator for SpanIter<'a, T> {}
+
+//==============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl Span for usize {
+        fn span_len(&self) -> usize {
+            *self
+        }
+    }
+
+    fn items(tree: &SpanTree<usize>) -> Vec<usize> {
+        tree.iter_range(0..tree.count()).cloned().collect()
+    }
+
+    /// Checks the tree's structure and summaries against its items.
+    fn assert_integrity(tree: &SpanTree<usize>) {
+        fn check(tree: &SpanTree<usize>, i: usize, parent: Option<usize>) -> (usize, usize) {
+            let n = tree.node(i);
+            assert_eq!(n.parent, parent);
+            let mut sums = [(0, 0), (0, 0)];
+            for (sum, child) in sums.iter_mut().zip([n.left, n.right].iter()) {
+                if let Some(child) = *child {
+                    assert!(tree.node(child).priority <= n.priority);
+                    *sum = check(tree, child, Some(i));
+                }
+            }
+            let count = sums[0].0 + 1 + sums[1].0;
+            let len = sums[0].1 + n.item + sums[1].1;
+            assert_eq!((n.count, n.len), (count, len));
+            assert_eq!((n.left_count, n.left_len), sums[0]);
+            (count, len)
+        }
+        if let Some(root) = tree.root {
+            check(tree, root, None);
+        }
+        let live = tree.nodes.iter().filter(|n| n.is_some()).count();
+        assert_eq!(live, tree.count());
+        assert_eq!(live + tree.free.len(), tree.nodes.len());
+    }
+
+    #[test]
+    fn insert_remove_01() {
+        // Compare against a `Vec` through a pseudo-random sequence of edits.
+        let mut tree = SpanTree::new();
+        let mut model: Vec<usize> = Vec::new();
+        let mut rng = fastrand::Rng::with_seed(12345);
+        for step in 0..2000 {
+            if step % 3 == 2 && !model.is_empty() {
+                let start = rng.usize(0..model.len());
+                let end = (start + rng.usize(0..4)).min(model.len());
+                let removed = tree.remove_range(start..end);
+                assert_eq!(removed, model.drain(start..end).collect::<Vec<_>>());
+            } else {
+                let rank = rng.usize(0..(model.len() + 1));
+                let len = rng.usize(0..10);
+                let id = tree.insert(rank, len);
+                model.insert(rank, len);
+                assert_eq!(*tree.item(id), len);
+            }
+        }
+        assert_integrity(&tree);
+        assert_eq!(items(&tree), model);
+        for rank in 0..=model.len() {
+            assert_eq!(
+                tree.offset_of_rank(rank),
+                model[..rank].iter().sum::<usize>()
+            );
+        }
+    }
+
+    #[test]
+    fn partition_point_01() {
+        let mut tree = SpanTree::new();
+        for (rank, &len) in [3usize, 0, 2, 5].iter().enumerate() {
+            tree.insert(rank, len);
+        }
+        assert_eq!(tree.partition_point(|start, &len| start + len <= 2), 0);
+        assert_eq!(tree.partition_point(|start, &len| start + len <= 3), 2);
+        assert_eq!(tree.partition_point(|start, _| start < 5), 3);
+        assert_eq!(tree.partition_point(|_, _| true), 4);
+    }
+
+    #[test]
+    fn update_item_01() {
+        let mut tree = SpanTree::new();
+        for rank in 0..20 {
+            tree.insert(rank, 1);
+        }
+        let id = tree.get(7).unwrap();
+        tree.update_item(id, |len| *len = 10);
+        assert_integrity(&tree);
+        assert_eq!(tree.offset_of_rank(20), 29);
+        assert_eq!(tree.offset_of_rank(8), 17);
+        assert_eq!(tree.rank(id), 7);
+        assert_eq!(tree.offset(id), 7);
+        let last = tree.get(19).unwrap();
+        assert_eq!((tree.rank(last), tree.offset(last)), (19, 28));
+        assert_eq!(tree.get(20), None);
+    }
+
+    #[test]
+    fn iter_range_01() {
+        let mut tree = SpanTree::new();
+        for rank in 0..50 {
+            tree.insert(rank, rank);
+        }
+        let fwd: Vec<_> = tree.iter_range(10..20).cloned().collect();
+        assert_eq!(fwd, (10..20).collect::<Vec<_>>());
+        let back: Vec<_> = tree.iter_range(10..20).rev().cloned().collect();
+        assert_eq!(back, (10..20).rev().collect::<Vec<_>>());
+
+        let mut iter = tree.iter_range(3..6);
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.next(), Some(&4));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(tree.iter_range(7..7).count(), 0);
+    }
+}
@@ -3,7 +3,7 @@ mod node_children;
 mod node_text;
 mod text_info;
 
-pub(crate) use self::node::Node;
+pub(crate) use self::node::{EditMetric, Node, NotCharBoundary, RangeEdit};
 pub(crate) use self::node_children::NodeChildren;
 pub(crate) use self::node_text::NodeText;
 pub(crate) use self::text_info::TextInfo;
@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
+use smallvec::SmallVec;
+
+use crate::crlf;
 use crate::str_utils::{
-    byte_to_char_idx, byte_to_line_idx, byte_to_utf16_surrogate_idx, char_to_byte_idx,
+    byte_to_char_idx, byte_to_line_idx, byte_to_utf16_surrogate_idx, char_to_byte_idx, count_chars,
 };
 use crate::tree::node_text::fix_segment_seam;
 use crate::tree::{
@@ -15,6 +18,36 @@ pub(crate) enum Node {
     Internal(NodeChildren),
 }
 
+/// The units that the indices of a batch of `RangeEdit`s are given in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum EditMetric {
+    Chars,
+    Bytes,
+}
+
+impl EditMetric {
+    #[inline(always)]
+    pub fn of(self, info: TextInfo) -> usize {
+        match self {
+            EditMetric::Chars => info.chars as usize,
+            EditMetric::Bytes => info.bytes as usize,
+        }
+    }
+}
+
+/// A single edit for `Node::edit_ranges()`: replaces `start..end` with `text`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct RangeEdit<'a> {
+    pub start: usize,
+    pub end: usize,
+    pub text: &'a str,
+}
+
+/// Returned by `Node::edit_ranges()` when a byte-indexed edit doesn't fall
+/// on char boundaries.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct NotCharBoundary;
+
 impl Node {
     /// Creates an empty node.
     #[inline(always)]
@@ -322,6 +355,331 @@ impl Node {
         }
     }
 
+    /// Applies a batch of edits to the node in a single pass, descending
+    /// only into the children that the edits touch.
+    ///
+    /// There are four parameters:
+    /// - edits: sorted, non-overlapping edits touching this node, with
+    ///          indices in `metric` units.  The parts of the edits that fall
+    ///          outside of this node are ignored, and the text of an edit
+    ///          goes into the node that contains its start.
+    /// - offset: the index of the start of this node, relative to the same
+    ///           origin as the edits.
+    /// - metric: the units of the indices.
+    /// - node_info: the text info of this node (see `edit_chunk_at_char()`).
+    ///
+    /// Leaves that grow too large are split, as are internal nodes that
+    /// end up with too many children, and children left undersized are
+    /// merged with their siblings.  CRLF pairs split by the edits are
+    /// mended as well.
+    ///
+    /// Returns the updated TextInfo of the node, and the new siblings (of
+    /// the same depth) that need to be inserted after it.  The node itself
+    /// may end up empty or undersized, which is up to the caller to handle.
+    ///
+    /// With `EditMetric::Bytes` only a single edit is supported, and it is
+    /// checked to be on char boundaries before anything is modified.
+    pub fn edit_ranges(
+        &mut self,
+        edits: &[RangeEdit],
+        offset: usize,
+        metric: EditMetric,
+        node_info: TextInfo,
+    ) -> Result<(TextInfo, Vec<(TextInfo, Arc<Node>)>), NotCharBoundary> {
+        debug_assert!(metric == EditMetric::Chars || edits.len() == 1);
+        let node_len = metric.of(node_info);
+
+        match *self {
+            Node::Leaf(ref mut leaf_text) => {
+                // Convert the edits to byte ranges within the leaf.
+                let mut ranges: SmallVec<[(usize, usize, &str); 4]> = SmallVec::new();
+                let mut char_idx = 0;
+                let mut byte_idx = 0;
+                for edit in edits {
+                    let edit = clip_edit(edit, offset, node_len);
+                    if metric == EditMetric::Bytes {
+                        if !leaf_text.is_char_boundary(edit.start)
+                            || !leaf_text.is_char_boundary(edit.end)
+                        {
+                            return Err(NotCharBoundary);
+                        }
+                        ranges.push((edit.start, edit.end, edit.text));
+                    } else {
+                        let start = byte_idx
+                            + char_to_byte_idx(&leaf_text[byte_idx..], edit.start - char_idx);
+                        let end =
+                            start + char_to_byte_idx(&leaf_text[start..], edit.end - edit.start);
+                        ranges.push((start, end, edit.text));
+                        char_idx = edit.end;
+                        byte_idx = end;
+                    }
+                }
+
+                let new_len = ranges
+                    .iter()
+                    .fold(leaf_text.len(), |len, &(start, end, text)| {
+                        len - (end - start) + text.len()
+                    });
+
+                if new_len <= MAX_BYTES {
+                    // Edit in place, back-to-front so that the byte ranges of
+                    // the earlier edits stay valid.
+                    for &(start, end, text) in ranges.iter().rev() {
+                        leaf_text.remove_range(start, end);
+                        leaf_text.insert_str(start, text);
+                    }
+                    Ok((TextInfo::from_str(leaf_text), Vec::new()))
+                } else {
+                    // Build the new text, and split it into multiple leaves.
+                    let mut text = String::with_capacity(new_len);
+                    let mut last_end = 0;
+                    for &(start, end, ins_text) in ranges.iter() {
+                        text.push_str(&leaf_text[last_end..start]);
+                        text.push_str(ins_text);
+                        last_end = end;
+                    }
+                    text.push_str(&leaf_text[last_end..]);
+
+                    let split_idx = next_leaf_len(&text);
+                    let mut siblings = Vec::new();
+                    let mut rest = &text[split_idx..];
+                    while !rest.is_empty() {
+                        let leaf_len = next_leaf_len(rest);
+                        let leaf = NodeText::from_str(&rest[..leaf_len]);
+                        siblings.push((TextInfo::from_str(&leaf), Arc::new(Node::Leaf(leaf))));
+                        rest = &rest[leaf_len..];
+                    }
+                    *leaf_text = NodeText::from_str(&text[..split_idx]);
+
+                    Ok((TextInfo::from_str(leaf_text), siblings))
+                }
+            }
+
+            Node::Internal(ref mut children) => {
+                // Find the children that the edits touch, and which edits
+                // touch each of them.  Stored as (child index, child offset,
+                // edits start, edits end).
+                let mut touched: SmallVec<[(usize, usize, usize, usize); 4]> = SmallVec::new();
+                {
+                    let last_child_i = children.len() - 1;
+                    let mut child_offset = offset;
+                    let mut edit_i = 0;
+                    for (child_i, info) in children.info().iter().enumerate() {
+                        if edit_i == edits.len() {
+                            break;
+                        }
+                        let child_end = child_offset + metric.of(*info);
+                        let touches = |edit: &RangeEdit| {
+                            if edit.start < child_offset {
+                                edit.end > child_offset
+                            } else {
+                                edit.start < child_end || child_i == last_child_i
+                            }
+                        };
+
+                        let mut edit_end = edit_i;
+                        while edit_end < edits.len() && touches(&edits[edit_end]) {
+                            edit_end += 1;
+                        }
+                        if edit_end > edit_i {
+                            touched.push((child_i, child_offset, edit_i, edit_end));
+                            // An edit that continues past the end of this
+                            // child also touches the next one.
+                            edit_i = if edits[edit_end - 1].end > child_end {
+                                edit_end - 1
+                            } else {
+                                edit_end
+                            };
+                        }
+                        child_offset = child_end;
+                    }
+                }
+
+                // If a byte-indexed edit spans multiple children, make sure
+                // both of its ends are on char boundaries before modifying
+                // any of them.
+                if metric == EditMetric::Bytes && touched.len() > 1 {
+                    for &(child_i, child_offset, edit_i, edit_end) in
+                        [touched[0], touched[touched.len() - 1]].iter()
+                    {
+                        let child = &children.nodes()[child_i];
+                        let child_len = metric.of(children.info()[child_i]);
+                        for edit in &edits[edit_i..edit_end] {
+                            let edit = clip_edit(edit, child_offset, child_len);
+                            if !child.is_char_boundary(edit.start)
+                                || !child.is_char_boundary(edit.end)
+                            {
+                                return Err(NotCharBoundary);
+                            }
+                        }
+                    }
+                }
+
+                // Recurse into the touched children.  Stored as (child index,
+                // result, whether its left edge changed, whether its right
+                // edge changed), where a `None` result means the child is
+                // removed entirely.
+                let mut results: SmallVec<[_; 4]> = SmallVec::new();
+                for &(child_i, child_offset, edit_i, edit_end) in touched.iter() {
+                    let info = children.info()[child_i];
+                    let child_len = metric.of(info);
+                    let first = clip_edit(&edits[edit_i], child_offset, child_len);
+                    let last = clip_edit(&edits[edit_end - 1], child_offset, child_len);
+                    let left_edge = first.start == 0;
+                    let right_edge = last.end == child_len;
+
+                    let result = if edit_end - edit_i == 1
+                        && left_edge
+                        && right_edge
+                        && first.text.is_empty()
+                    {
+                        None
+                    } else {
+                        Some(
+                            Arc::make_mut(&mut children.nodes_mut()[child_i]).edit_ranges(
+                                &edits[edit_i..edit_end],
+                                child_offset,
+                                metric,
+                                info,
+                            )?,
+                        )
+                    };
+                    results.push((child_i, result, left_edge, right_edge));
+                }
+
+                // Fast path: a single child was edited, and the tree is
+                // still in good shape around it.
+                if results.len() == 1 {
+                    let (child_i, ref result, left_edge, right_edge) = results[0];
+                    if let Some((new_info, ref siblings)) = *result {
+                        let nodes = children.nodes();
+                        let info = children.info();
+                        if siblings.is_empty()
+                            && new_info.bytes > 0
+                            && !nodes[child_i].is_undersized()
+                            && !(left_edge
+                                && child_i > 0
+                                && is_crlf_split_between(
+                                    info[child_i - 1],
+                                    &nodes[child_i - 1],
+                                    &nodes[child_i],
+                                ))
+                            && !(right_edge
+                                && child_i + 1 < nodes.len()
+                                && is_crlf_split_between(
+                                    new_info,
+                                    &nodes[child_i],
+                                    &nodes[child_i + 1],
+                                ))
+                        {
+                            let old_info = info[child_i];
+                            children.info_mut()[child_i] = new_info;
+                            return Ok((node_info - old_info + new_info, Vec::new()));
+                        }
+                    }
+                }
+
+                // Otherwise, rebuild the list of children.  Alongside each
+                // child we track whether it was touched, and whether there
+                // may be a CRLF pair split between it and the child before
+                // it.
+                let mut old_children = Vec::with_capacity(children.len());
+                while children.len() > 0 {
+                    old_children.push(children.pop());
+                }
+                old_children.reverse();
+
+                let mut entries: Vec<(TextInfo, Arc<Node>)> =
+                    Vec::with_capacity(old_children.len() + results.len());
+                let mut is_touched: Vec<bool> = Vec::with_capacity(entries.capacity());
+                let mut check_seam: Vec<bool> = Vec::with_capacity(entries.capacity());
+                let mut results = results.into_iter().peekable();
+                let mut pending_seam = false;
+                for (child_i, (info, node)) in old_children.into_iter().enumerate() {
+                    if results.peek().map_or(true, |r| r.0 != child_i) {
+                        entries.push((info, node));
+                        is_touched.push(false);
+                        check_seam.push(pending_seam);
+                        pending_seam = false;
+                        continue;
+                    }
+
+                    let (_, result, left_edge, right_edge) = results.next().unwrap();
+                    match result {
+                        Some((new_info, siblings)) if new_info.bytes > 0 => {
+                            entries.push((new_info, node));
+                            is_touched.push(true);
+                            check_seam.push(pending_seam || left_edge);
+                            for sibling in siblings {
+                                entries.push(sibling);
+                                is_touched.push(true);
+                                check_seam.push(false);
+                            }
+                            pending_seam = right_edge;
+                        }
+                        // The child was removed or emptied.
+                        _ => pending_seam = true,
+                    }
+                }
+
+                // Mend CRLF pairs split between children.
+                let mut i = 1;
+                while i < entries.len() {
+                    if check_seam[i]
+                        && is_crlf_split_between(entries[i - 1].0, &entries[i - 1].1, &entries[i].1)
+                    {
+                        fix_entry_seam(&mut entries, i);
+                        is_touched[i - 1] = true;
+                        is_touched[i] = true;
+                        if entries[i].0.bytes == 0 {
+                            entries.remove(i);
+                            is_touched.remove(i);
+                            check_seam.remove(i);
+                        } else if entries[i - 1].0.bytes == 0 {
+                            entries.remove(i - 1);
+                            is_touched.remove(i - 1);
+                            check_seam.remove(i - 1);
+                            continue;
+                        }
+                    }
+                    i += 1;
+                }
+
+                // Merge undersized children with their siblings.
+                let mut i = 0;
+                while i < entries.len() {
+                    if entries.len() > 1 && is_touched[i] && entries[i].1.is_undersized() {
+                        let l = i.saturating_sub(1);
+                        if merge_entries(&mut entries, l) {
+                            entries.remove(l + 1);
+                            is_touched.remove(l + 1);
+                            is_touched[l] = true;
+                            i = l;
+                            continue;
+                        }
+                    }
+                    i += 1;
+                }
+
+                // Put the children back, splitting this node if there are
+                // too many of them.
+                if entries.len() <= MAX_CHILDREN {
+                    for entry in entries {
+                        children.push(entry);
+                    }
+                    Ok((children.combined_info(), Vec::new()))
+                } else {
+                    let mut groups = group_children(entries).into_iter();
+                    *children = groups.next().unwrap();
+                    let siblings = groups
+                        .map(|group| (group.combined_info(), Arc::new(Node::Internal(group))))
+                        .collect();
+                    Ok((children.combined_info(), siblings))
+                }
+            }
+        }
+    }
+
     pub fn append_at_depth(&mut self, other: Arc<Node>, depth: usize) -> Option<Arc<Node>> {
         if depth == 0 {
             match *self {
@@ -421,42 +779,109 @@ impl Node {
         }
     }
 
-    /// Splits the `Node` at char index `char_idx`, returning
+    /// Splits the `Node` at index `idx` (in `metric` units), returning
     /// the right side of the split.
-    pub fn split(&mut self, char_idx: usize) -> Node {
-        debug_assert!(char_idx != 0);
-        debug_assert!(char_idx != (self.text_info().chars as usize));
+    ///
+    /// With `EditMetric::Bytes`, `idx` is checked to be on a char boundary
+    /// before anything is modified.
+    pub fn split(&mut self, idx: usize, metric: EditMetric) -> Result<Node, NotCharBoundary> {
+        debug_assert!(idx != 0);
+        debug_assert!(idx != metric.of(self.text_info()));
         match *self {
             Node::Leaf(ref mut text) => {
-                let byte_idx = char_to_byte_idx(text, char_idx);
-                Node::Leaf(text.split_off(byte_idx))
+                let byte_idx = match metric {
+                    EditMetric::Chars => char_to_byte_idx(text, idx),
+                    EditMetric::Bytes if text.is_char_boundary(idx) => idx,
+                    EditMetric::Bytes => return Err(NotCharBoundary),
+                };
+                Ok(Node::Leaf(text.split_off(byte_idx)))
             }
             Node::Internal(ref mut children) => {
-                let (child_i, acc_info) = children.search_char_idx(char_idx);
+                let (child_i, acc_info) = match metric {
+                    EditMetric::Chars => children.search_char_idx(idx),
+                    EditMetric::Bytes => children.search_byte_idx(idx),
+                };
+                let child_start = metric.of(acc_info);
                 let child_info = children.info()[child_i];
 
-                if char_idx == acc_info.chars as usize {
-                    Node::Internal(children.split_off(child_i))
-                } else if char_idx == (acc_info.chars as usize + child_info.chars as usize) {
-                    Node::Internal(children.split_off(child_i + 1))
+                if idx == child_start {
+                    Ok(Node::Internal(children.split_off(child_i)))
+                } else if idx == child_start + metric.of(child_info) {
+                    Ok(Node::Internal(children.split_off(child_i + 1)))
                 } else {
-                    let mut r_children = children.split_off(child_i + 1);
-
                     // Recurse
                     let r_node = Arc::make_mut(&mut children.nodes_mut()[child_i])
-                        .split(char_idx - acc_info.chars as usize);
+                        .split(idx - child_start, metric)?;
 
+                    let mut r_children = children.split_off(child_i + 1);
                     r_children.insert(0, (r_node.text_info(), Arc::new(r_node)));
 
                     children.update_child_info(child_i);
                     r_children.update_child_info(0);
 
-                    Node::Internal(r_children)
+                    Ok(Node::Internal(r_children))
                 }
             }
         }
     }
 
+    /// Attempts to merge `other` into this node, and if it's too much data
+    /// to merge equi-distributes it between the two.  The nodes must be
+    /// siblings.
+    ///
+    /// Returns:
+    ///
+    /// - True: merge was successful, and `other` should be discarded.
+    /// - False: merge failed, equidistributed instead.
+    pub fn merge_distribute_with(&mut self, other: &mut Node) -> bool {
+        match *self {
+            Node::Leaf(ref mut text1) => {
+                if let Node::Leaf(ref mut text2) = *other {
+                    if (text1.len() + text2.len()) <= MAX_BYTES {
+                        text1.push_str(text2);
+                        true
+                    } else {
+                        let right = text1.push_str_split(text2);
+                        *text2 = right;
+                        false
+                    }
+                } else {
+                    panic!("Siblings have different node types");
+                }
+            }
+
+            Node::Internal(ref mut children1) => {
+                if let Node::Internal(ref mut children2) = *other {
+                    if (children1.len() + children2.len()) <= MAX_CHILDREN {
+                        for _ in 0..children2.len() {
+                            children1.push(children2.remove(0));
+                        }
+                        true
+                    } else {
+                        children1.distribute_with(children2);
+                        false
+                    }
+                } else {
+                    panic!("Siblings have different node types");
+                }
+            }
+        }
+    }
+
+    /// Builds a tree out of a list of sibling nodes, adding as many levels
+    /// above them as needed.
+    pub fn from_siblings(mut nodes: Vec<(TextInfo, Arc<Node>)>) -> Arc<Node> {
+        while nodes.len() > 1 {
+            nodes = group_children(nodes)
+                .into_iter()
+                .map(|group| (group.combined_info(), Arc::new(Node::Internal(group))))
+                .collect();
+        }
+        nodes
+            .pop()
+            .map_or_else(|| Arc::new(Node::new()), |(_, node)| node)
+    }
+
     /// Returns the chunk that contains the given byte, and the TextInfo
     /// corresponding to the start of the chunk.
     pub fn get_chunk_at_byte(&self, byte_idx: usize) -> (&str, TextInfo) {
@@ -601,6 +1026,26 @@ impl Node {
         }
     }
 
+    /// Calls `f` on each leaf of the tree rooted at `node`, in order, along
+    /// with the leaf's char count.
+    pub fn for_each_leaf<F>(node: &Arc<Node>, f: &mut F)
+    where
+        F: FnMut(&Arc<Node>, usize),
+    {
+        match **node {
+            Node::Leaf(ref text) => f(node, count_chars(text)),
+            Node::Internal(ref children) => {
+                for (info, child) in children.iter() {
+                    if child.is_leaf() {
+                        f(child, info.chars as usize);
+                    } else {
+                        Node::for_each_leaf(child, f);
+                    }
+                }
+            }
+        }
+    }
+
     //-----------------------------------------
 
     pub fn child_count(&self) -> usize {
@@ -991,6 +1436,119 @@ impl Node {
 
 //===========================================================================
 
+/// Clips `edit` to the node spanning `offset..(offset + len)`, making it
+/// relative to the node.  The edit's text is dropped if it doesn't start
+/// within the node.
+#[inline]
+fn clip_edit<'a>(edit: &RangeEdit<'a>, offset: usize, len: usize) -> RangeEdit<'a> {
+    RangeEdit {
+        start: edit.start.max(offset) - offset,
+        end: edit.end.min(offset + len) - offset,
+        text: if edit.start >= offset { edit.text } else { "" },
+    }
+}
+
+/// Returns the length in bytes of the first leaf when splitting `text`
+/// into evenly sized leaves, without splitting CRLF pairs.
+fn next_leaf_len(text: &str) -> usize {
+    if text.len() <= MAX_BYTES {
+        return text.len();
+    }
+    let leaf_count = (text.len() + MAX_BYTES - 1) / MAX_BYTES;
+    crlf::find_good_split(text.len() / leaf_count, text.as_bytes(), true)
+}
+
+/// Returns whether there's a CRLF pair split between the end of `l` and
+/// the start of `r`.
+fn is_crlf_split_between(l_info: TextInfo, l: &Node, r: &Node) -> bool {
+    l_info.bytes > 0
+        && l.get_chunk_at_byte(l_info.bytes as usize - 1)
+            .0
+            .as_bytes()
+            .last()
+            == Some(&0x0D)
+        && r.get_chunk_at_byte(0).0.as_bytes().first() == Some(&0x0A)
+}
+
+/// Mends the CRLF pair split between `entries[i - 1]` and `entries[i]`.
+///
+/// This can leave one of the two empty.
+fn fix_entry_seam(entries: &mut [(TextInfo, Arc<Node>)], i: usize) {
+    let (left, right) = entries.split_at_mut(i);
+    let (ref mut l_info, ref mut l_node) = left[i - 1];
+    let (ref mut r_info, ref mut r_node) = right[0];
+    let l_node = Arc::make_mut(l_node);
+    let r_node = Arc::make_mut(r_node);
+
+    // Scope for borrow.
+    {
+        let (l_text, _) = l_node.get_chunk_at_byte_mut(l_info.bytes as usize);
+        let (r_text, _) = r_node.get_chunk_at_byte_mut(0);
+        fix_segment_seam(l_text, r_text);
+    }
+
+    l_node.fix_info_right();
+    r_node.fix_info_left();
+    l_node.zip_fix_right();
+    r_node.zip_fix_left();
+    *l_info = l_node.text_info();
+    *r_info = r_node.text_info();
+}
+
+/// Merges or equi-distributes `entries[l]` and `entries[l + 1]`.
+///
+/// Returns whether they were merged, in which case `entries[l + 1]` should
+/// be discarded.
+fn merge_entries(entries: &mut [(TextInfo, Arc<Node>)], l: usize) -> bool {
+    let (left, right) = entries.split_at_mut(l + 1);
+    let (ref mut l_info, ref mut l_node) = left[l];
+    let (ref mut r_info, ref mut r_node) = right[0];
+    let l_node = Arc::make_mut(l_node);
+    let r_node = Arc::make_mut(r_node);
+    let seam_idx = l_info.chars as usize;
+
+    // Merging internal nodes can leave undersized grandchildren on either
+    // side of the old seam, so fix those up as well.
+    let merged = l_node.merge_distribute_with(r_node);
+    if merged {
+        l_node.fix_tree_seam(seam_idx);
+    } else {
+        let l_chars = l_node.char_count();
+        if seam_idx < l_chars {
+            l_node.fix_tree_seam(seam_idx);
+        } else if seam_idx > l_chars {
+            r_node.fix_tree_seam(seam_idx - l_chars);
+        } else {
+            l_node.zip_fix_right();
+            r_node.zip_fix_left();
+        }
+        *r_info = r_node.text_info();
+    }
+    *l_info = l_node.text_info();
+
+    merged
+}
+
+/// Splits `nodes` into as few groups of at most MAX_CHILDREN as possible,
+/// distributing the nodes evenly between them.
+fn group_children(nodes: Vec<(TextInfo, Arc<Node>)>) -> Vec<NodeChildren> {
+    let len = nodes.len();
+    let group_count = (len + MAX_CHILDREN - 1) / MAX_CHILDREN;
+    let mut nodes = nodes.into_iter();
+    (0..group_count)
+        .map(|i| {
+            let group_len = (len * (i + 1) / group_count) - (len * i / group_count);
+            let mut group = NodeChildren::new();
+            for node in nodes.by_ref().take(group_len) {
+                group.push(node);
+            }
+            group
+        })
+        .collect()
+}
+
+//===========================================================================
+
 #[cfg(test)]
 mod tests {
     use crate::Rope;
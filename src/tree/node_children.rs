@@ -95,40 +95,7 @@ impl NodeChildren {
         assert!(idx2 < self.len());
         let remove_right = {
             let ((_, node1), (_, node2)) = self.get_two_mut(idx1, idx2);
-            let node1 = Arc::make_mut(node1);
-            let node2 = Arc::make_mut(node2);
-            match *node1 {
-                Node::Leaf(ref mut text1) => {
-                    if let Node::Leaf(ref mut text2) = *node2 {
-                        if (text1.len() + text2.len()) <= tree::MAX_BYTES {
-                            text1.push_str(text2);
-                            true
-                        } else {
-                            let right = text1.push_str_split(text2);
-                            *text2 = right;
-                            false
-                        }
-                    } else {
-                        panic!("Siblings have different node types");
-                    }
-                }
-
-                Node::Internal(ref mut children1) => {
-                    if let Node::Internal(ref mut children2) = *node2 {
-                        if (children1.len() + children2.len()) <= MAX_LEN {
-                            for _ in 0..children2.len() {
-                                children1.push(children2.remove(0));
-                            }
-                            true
-                        } else {
-                            children1.distribute_with(children2);
-                            false
-                        }
-                    } else {
-                        panic!("Siblings have different node types");
-                    }
-                }
-            }
+            Arc::make_mut(node1).merge_distribute_with(Arc::make_mut(node2))
         };
 
         if remove_right {
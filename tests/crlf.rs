@@ -59,3 +59,34 @@ fn crlf_removals() {
         tree.assert_invariants();
     }
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn crlf_replacements() {
+    let mut rng = fastrand::Rng::new();
+    let mut tree = Rope::new();
+
+    // Build tree.
+    for _ in 0..(1 << 9) {
+        let len = tree.len_chars().max(1);
+        tree.insert(rng.usize(0..len), "\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\nこんいちは、\n\r\n\r\n\r\n\r\n\r\rみんなさん！\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r");
+    }
+
+    // Do a bunch of random incoherent replacements
+    for _ in 0..(1 << 11) {
+        let start = rng.usize(0..tree.len_chars().max(1));
+        let end = (start + 5).min(tree.len_chars());
+        tree.replace(start..end, "\n\r\n");
+
+        let start = rng.usize(0..tree.len_chars().max(1));
+        let end = (start + 2).min(tree.len_chars());
+        tree.replace(start..end, "\r\n\r");
+
+        let start = rng.usize(0..tree.len_chars().max(1));
+        tree.replace(start..start, "\n");
+
+        // Make sure the tree is sound
+        tree.assert_integrity();
+        tree.assert_invariants();
+    }
+}
@@ -55,3 +55,87 @@ fn from_reader_03() {
         panic!("Should have returned an invalid data error.")
     }
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn from_reader_lossy_01() {
+    // Valid text comes through unchanged.
+    let (rope, replacements) = Rope::from_reader_lossy(Cursor::new(TEXT)).unwrap();
+
+    assert_eq!(rope, TEXT);
+    assert!(replacements.is_empty());
+
+    // Make sure the tree is sound
+    rope.assert_integrity();
+    rope.assert_invariants();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn from_reader_lossy_02() {
+    // Make text with a utf8-invalid byte sequence in it.
+    let mut text = Vec::new();
+    text.extend(TEXT.as_bytes());
+    text[6132] = 0b1100_0000;
+    text[6133] = 0b0100_0000;
+
+    let (rope, replacements) = Rope::from_reader_lossy(Cursor::new(&text)).unwrap();
+
+    assert_eq!(rope, String::from_utf8_lossy(&text));
+    assert_eq!(replacements.len(), 1);
+    assert_eq!(replacements[0].input_byte_range, 6132..6133);
+    assert_eq!(replacements[0].byte_idx, 6132);
+
+    // Make sure the tree is sound
+    rope.assert_integrity();
+    rope.assert_invariants();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn from_reader_lossy_03() {
+    // A reader that hands out its data a few bytes at a time, so that
+    // multi-byte sequences get split across reads.
+    struct TrickleReader<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl<'a> std::io::Read for TrickleReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.step.min(self.data.len()).min(buf.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            self.step = self.step % 5 + 1;
+            Ok(n)
+        }
+    }
+
+    let mut text = Vec::new();
+    text.extend(TEXT.as_bytes());
+    for i in (100..text.len()).step_by(997) {
+        text[i] = 0xFF;
+    }
+    text.extend(b"\xF0\x9F\x90");
+
+    let reader = TrickleReader {
+        data: &text,
+        step: 1,
+    };
+    let (rope, replacements) = Rope::from_reader_lossy(reader).unwrap();
+
+    let expected = String::from_utf8_lossy(&text);
+    assert_eq!(rope, expected);
+    assert_eq!(replacements.len(), expected.matches('\u{FFFD}').count());
+    for r in &replacements {
+        assert_eq!(rope.byte_slice(r.byte_idx..(r.byte_idx + 3)), "\u{FFFD}");
+    }
+    assert_eq!(
+        replacements.last().unwrap().input_byte_range,
+        (text.len() - 3)..text.len()
+    );
+
+    // Make sure the tree is sound
+    rope.assert_integrity();
+    rope.assert_invariants();
+}
@@ -2,13 +2,17 @@
 
 #[macro_use]
 extern crate proptest;
+#[cfg(feature = "multi_search")]
+extern crate aho_corasick;
 extern crate ropey;
+#[cfg(feature = "graphemes")]
+extern crate unicode_segmentation;
 
 use proptest::collection::vec;
 use proptest::test_runner::Config;
 use ropey::{
     str_utils::{byte_to_char_idx, byte_to_line_idx, char_to_byte_idx, char_to_line_idx},
-    Rope, MAX_BYTES,
+    Rope, RopeBuilder, MAX_BYTES,
 };
 
 fn string_insert(text: &mut String, char_idx: usize, text_ins: &str) {
@@ -31,6 +35,27 @@ fn string_slice(text: &str, char_start: usize, char_end: usize) -> &str {
     &text[..byte_end]
 }
 
+/// Builds a rope of `text` from chunks of at most `chunk_len` chars, so that
+/// even short texts span many chunks.  CRLF pairs are kept together, as in
+/// ropes built the normal way.
+fn rope_with_small_chunks(text: &str, chunk_len: usize) -> Rope {
+    let mut builder = RopeBuilder::new();
+    let mut chunk_start = 0;
+    let mut chunk_chars = 0;
+    for (i, c) in text.char_indices() {
+        if chunk_chars >= chunk_len && !(c == '\n' && text[..i].ends_with('\r')) {
+            builder._append_chunk(&text[chunk_start..i]);
+            chunk_start = i;
+            chunk_chars = 0;
+        }
+        chunk_chars += 1;
+    }
+    if chunk_start < text.len() {
+        builder._append_chunk(&text[chunk_start..]);
+    }
+    builder._finish_no_fix()
+}
+
 //===========================================================================
 
 proptest! {
@@ -91,6 +116,63 @@ proptest! {
         assert_eq!(rope, text);
     }
 
+    #[test]
+    fn pt_replace(range in (0usize..(CHAR_LEN+1), 0usize..(CHAR_LEN+1)), ref ins_text in "\\PC{0,20}") {
+        let mut rope = Rope::from_str(TEXT);
+        let mut text = String::from(TEXT);
+
+        let mut idx1 = range.0 % (rope.len_chars() + 1);
+        let mut idx2 = range.1 % (rope.len_chars() + 1);
+        if idx1 > idx2 {
+            std::mem::swap(&mut idx1, &mut idx2)
+        };
+
+        rope.replace(idx1..idx2, ins_text);
+        string_remove(&mut text, idx1, idx2);
+        string_insert(&mut text, idx1, ins_text);
+
+        rope.assert_integrity();
+        rope.assert_invariants();
+
+        assert_eq!(rope, text);
+    }
+
+    #[test]
+    fn pt_apply_edits_01(
+        ref text in "[a\u{e9}\r\n]{0,300}",
+        ref idxs in vec(0usize..1000000, 0..20),
+        ref ins_texts in vec("[a\u{e9}\r\n]{0,30}", 10),
+        repeat in 1usize..20,
+    ) {
+        let text = text.repeat(repeat);
+        let mut rope = Rope::from_str(&text);
+        let mut text = text;
+
+        // Pair up the sorted indices into non-overlapping ranges.
+        let mut idxs: Vec<usize> = idxs.iter().map(|idx| idx % (rope.len_chars() + 1)).collect();
+        idxs.sort();
+        let edits: Vec<_> = idxs
+            .chunks_exact(2)
+            .zip(ins_texts.iter().cycle())
+            .map(|(range, ins_text)| (range[0]..range[1], ins_text.as_str()))
+            .collect();
+
+        // Pass the edits in reverse, so that they have to be sorted.  The
+        // sort is stable, so insertions at the same index stay reversed.
+        rope.apply_edits(edits.iter().rev().cloned());
+        let mut sorted: Vec<_> = edits.iter().rev().collect();
+        sorted.sort_by_key(|(range, _)| (range.start, range.end));
+        for (range, ins_text) in sorted.iter().rev() {
+            string_remove(&mut text, range.start, range.end);
+            string_insert(&mut text, range.start, ins_text);
+        }
+
+        rope.assert_integrity();
+        rope.assert_invariants();
+
+        assert_eq!(rope, text);
+    }
+
     #[test]
     fn pt_split_off_and_append(mut idx in 0usize..(CHAR_LEN+1)) {
         let mut rope = Rope::from_str(TEXT);
@@ -609,6 +691,447 @@ proptest! {
         }
     }
 
+    #[test]
+    #[cfg(feature = "graphemes")]
+    fn pt_graphemes_iter_01(ref text in
+        "[a\\u{0303}\\u{1F1E6}-\\u{1F1FF}\\u{200D}\\u{1F600}\r\n]{0,1000}",
+        range in (0usize..1000000, 0usize..1000000),
+    ) {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let r = Rope::from_str(text);
+        let idx1 = range.0 % (r.len_chars() + 1);
+        let idx2 = range.1 % (r.len_chars() + 1);
+        let start = idx1.min(idx2);
+        let end = idx1.max(idx2);
+
+        let s = r.slice(start..end);
+        let text = string_slice(text, start, end);
+
+        let expected: Vec<&str> = text.graphemes(true).collect();
+        let forward: Vec<String> = s.graphemes().map(String::from).collect();
+        let mut backward: Vec<String> = s.graphemes().rev().map(String::from).collect();
+        backward.reverse();
+
+        assert_eq!(forward, expected);
+        assert_eq!(backward, expected);
+    }
+
+    #[test]
+    fn pt_matches_01(ref text in "[ab\u{e9}\r\n]{0,500}",
+        ref pattern in "[ab\u{e9}\r\n]{1,6}",
+        chunk_len in 1usize..64,
+        range in (0usize..1000000, 0usize..1000000),
+    ) {
+        let r = rope_with_small_chunks(text, chunk_len);
+        let idx1 = range.0 % (r.len_chars() + 1);
+        let idx2 = range.1 % (r.len_chars() + 1);
+        let start = idx1.min(idx2);
+        let end = idx1.max(idx2);
+
+        let s = r.slice(start..end);
+        let text = string_slice(text, start, end);
+        let to_chars = |(i, m): (usize, &str)| {
+            let i = text[..i].chars().count();
+            i..(i + m.chars().count())
+        };
+
+        let expected: Vec<_> = text.match_indices(pattern.as_str()).map(to_chars).collect();
+        let expected_rev: Vec<_> = text.rmatch_indices(pattern.as_str()).map(to_chars).collect();
+        assert_eq!(s.matches(pattern).collect::<Vec<_>>(), expected);
+        assert_eq!(s.rmatches(pattern).collect::<Vec<_>>(), expected_rev);
+
+        let idx = range.0 % (s.len_chars() + 1);
+        let byte_idx = s.char_to_byte(idx);
+        assert_eq!(
+            s.find_from(idx, pattern),
+            text[byte_idx..].find(pattern.as_str()).map(|i| text[..(byte_idx + i)].chars().count())
+        );
+        assert_eq!(
+            s.rfind_from(idx, pattern),
+            text[..byte_idx].rfind(pattern.as_str()).map(|i| text[..i].chars().count())
+        );
+    }
+
+    #[test]
+    fn pt_replace_all_01(ref text in "[ab\u{e9}\r\n]{0,500}",
+        ref pattern in "[ab\u{e9}\r\n]{0,4}",
+        ref replacement in "[xy\u{1f600}\r\n]{0,12}",
+    ) {
+        let mut r = Rope::from_str(text);
+        let count = r.replace_all(pattern, replacement);
+
+        assert_eq!(count, text.matches(pattern.as_str()).count());
+        assert_eq!(r, text.replace(pattern.as_str(), replacement));
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn pt_split_01(ref text in "[ab\u{e9} \r\n]{0,300}",
+        ref pattern in "[ab\u{e9} \r\n]{0,3}",
+        n in 0usize..10,
+        chunk_len in 1usize..64,
+        range in (0usize..1000000, 0usize..1000000),
+    ) {
+        let r = rope_with_small_chunks(text, chunk_len);
+        let idx1 = range.0 % (r.len_chars() + 1);
+        let idx2 = range.1 % (r.len_chars() + 1);
+        let s = r.slice(idx1.min(idx2)..idx1.max(idx2));
+        let text = string_slice(text, idx1.min(idx2), idx1.max(idx2));
+        let pattern = pattern.as_str();
+        let strs = |pieces: Vec<ropey::RopeSlice>| -> Vec<String> {
+            pieces.iter().map(|p| p.to_string()).collect()
+        };
+
+        assert_eq!(strs(s.split(pattern).collect()), text.split(pattern).collect::<Vec<_>>());
+        assert_eq!(strs(s.rsplit(pattern).collect()), text.rsplit(pattern).collect::<Vec<_>>());
+        assert_eq!(
+            strs(s.split_terminator(pattern).collect()),
+            text.split_terminator(pattern).collect::<Vec<_>>()
+        );
+        assert_eq!(strs(s.splitn(n, pattern).collect()), text.splitn(n, pattern).collect::<Vec<_>>());
+        assert_eq!(strs(s.split_whitespace().collect()), text.split_whitespace().collect::<Vec<_>>());
+
+        // Walking back over the pieces gives them in reverse.
+        let c = pattern.chars().next().unwrap_or('a');
+        let mut split = s.split(c);
+        let mut forward = strs(split.by_ref().collect());
+        forward.reverse();
+        assert_eq!(strs(split.reversed().collect()), forward);
+    }
+
+    #[test]
+    fn pt_char_indices_01(ref text in "\\PC*\\r?\\n?\\PC*", idx in 0usize..1000000) {
+        let r = Rope::from_str(text);
+        let idx = idx % (r.len_chars() + 1);
+        let expected: Vec<_> = text
+            .char_indices()
+            .enumerate()
+            .map(|(i, (b, c))| (i, b, c))
+            .collect();
+
+        assert_eq!(r.char_indices_at(idx).collect::<Vec<_>>(), &expected[idx..]);
+        let mut before: Vec<_> = r.char_indices_at(idx).reversed().collect();
+        before.reverse();
+        assert_eq!(before, &expected[..idx]);
+    }
+
+    #[test]
+    fn pt_chunk_indices_01(ref text in "\\PC*\\r?\\n?\\PC*\\r\\n\\PC*", idx in 0usize..1000000) {
+        let r = Rope::from_str(text);
+        let idx = idx % (r.len_bytes() + 1);
+
+        let (mut chunks, byte_idx, char_idx, line_idx) = r.chunks_at_byte(idx);
+        let mut itr = r.chunk_indices_at_byte(idx);
+        assert_eq!(itr.next(), chunks.next().map(|c| (c, byte_idx, char_idx, line_idx)));
+        itr.reverse();
+        for (chunk, byte_idx, char_idx, line_idx) in itr {
+            assert_eq!(&text[byte_idx..(byte_idx + chunk.len())], chunk);
+            assert_eq!(char_idx, r.byte_to_char(byte_idx));
+            assert_eq!(line_idx, r.byte_to_line(byte_idx));
+        }
+    }
+
+    #[test]
+    fn pt_line_infos_01(ref text in "[a\u{e9}\r\n\u{85}\u{2028}]{0,300}", range in (0usize..1000000, 0usize..1000000)) {
+        let r = Rope::from_str(text);
+        let idx1 = range.0 % (r.len_chars() + 1);
+        let idx2 = range.1 % (r.len_chars() + 1);
+        let s = r.slice(idx1.min(idx2)..idx1.max(idx2));
+
+        let mut rebuilt = String::new();
+        let mut byte_idx = 0;
+        for (info, content) in s.line_infos().zip(s.lines_without_endings()) {
+            assert_eq!(info.content, content);
+            assert_eq!(info.byte_range.start, byte_idx);
+            assert_eq!(s.byte_to_char(byte_idx), info.char_range.start);
+            byte_idx = info.byte_range.end;
+            rebuilt.push_str(&info.content.to_string());
+            rebuilt.push_str(info.ending.map_or("", |e| e.as_str()));
+        }
+        assert_eq!(rebuilt, s.to_string());
+        assert_eq!(s.line_infos().count(), s.len_lines());
+    }
+
+    #[test]
+    fn pt_append_bytes_lossy_01(
+        ref bytes in vec(prop_oneof![0x00u8..0x80, 0x80u8..0xC0, 0xC0u8..=0xFF], 0..300),
+        ref splits in vec(0usize..1000000, 0..10),
+    ) {
+        let mut split_idxs: Vec<_> = splits.iter().map(|i| i % (bytes.len() + 1)).collect();
+        split_idxs.sort();
+        split_idxs.push(bytes.len());
+
+        let mut builder = RopeBuilder::new();
+        let mut last = 0;
+        for &i in &split_idxs {
+            builder.append_bytes_lossy(&bytes[last..i]);
+            last = i;
+        }
+        let (r, replacements) = builder.finish_lossy();
+
+        let expected = String::from_utf8_lossy(bytes);
+        assert_eq!(r, expected);
+        // The input can itself contain valid U+FFFD sequences, which aren't
+        // replacements.
+        let literal_count = bytes.windows(3).filter(|w| *w == b"\xEF\xBF\xBD").count();
+        assert_eq!(
+            replacements.len(),
+            expected.matches('\u{FFFD}').count() - literal_count
+        );
+        for rep in &replacements {
+            assert_eq!(r.byte_slice(rep.byte_idx..(rep.byte_idx + 3)), "\u{FFFD}");
+            assert!(std::str::from_utf8(&bytes[rep.input_byte_range.clone()]).is_err());
+        }
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn pt_encoding_round_trip_01(ref text in "\\PC{0,300}", encoding_idx in 0usize..5, with_bom: bool) {
+        use ropey::encoding::{self, Encoding};
+
+        let encoding = [
+            Encoding::Utf8,
+            Encoding::Utf16Le,
+            Encoding::Utf16Be,
+            Encoding::Utf32Le,
+            Encoding::Utf32Be,
+        ][encoding_idx];
+        let r = Rope::from_str(text);
+
+        let mut bytes = Vec::new();
+        encoding::write_to(r.slice(..), &mut bytes, encoding, with_bom).unwrap();
+        let (r2, detected, has_bom) = encoding::from_reader(&bytes[..], encoding).unwrap();
+
+        assert_eq!(r2, r);
+        assert_eq!(detected, encoding);
+        assert_eq!(has_bom, with_bom);
+        r2.assert_integrity();
+        r2.assert_invariants();
+    }
+
+    #[test]
+    fn pt_normalize_line_endings_01(ref text in "[a\u{e9}\r\n\u{85}\u{2028}]{0,300}", ending_idx in 0usize..8) {
+        use ropey::LineEnding;
+
+        // Only convert to line breaks that are recognized, so that the line
+        // count is preserved.
+        let endings = [
+            LineEnding::Lf,
+            LineEnding::Crlf,
+            LineEnding::Cr,
+            LineEnding::Vt,
+            LineEnding::Ff,
+            LineEnding::Nel,
+            LineEnding::Ls,
+            LineEnding::Ps,
+        ];
+        let recognized = if cfg!(feature = "unicode_lines") {
+            8
+        } else if cfg!(feature = "cr_lines") {
+            3
+        } else {
+            2
+        };
+        let ending = endings[ending_idx % recognized];
+        let mut r = Rope::from_str(text);
+        assert_eq!(r.line_ending_stats().total(), r.len_lines() - 1);
+
+        // Build the expected text line by line.
+        let mut expected = String::new();
+        let mut expected_count = 0;
+        for info in r.line_infos() {
+            expected.push_str(&info.content.to_string());
+            if let Some(e) = info.ending {
+                expected.push_str(ending.as_str());
+                expected_count += (e != ending) as usize;
+            }
+        }
+
+        let mut written = Vec::new();
+        r.write_to_with_line_ending(&mut written, ending).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), expected);
+
+        let len_lines = r.len_lines();
+        assert_eq!(r.normalize_line_endings(ending), expected_count);
+        assert_eq!(r, expected.as_str());
+        assert_eq!(r.len_lines(), len_lines);
+        if cfg!(feature = "cr_lines") {
+            assert_eq!(r.line_ending_stats().count(ending), len_lines - 1);
+        } else {
+            // Without `cr_lines` a lone CR that isn't a line break can end up
+            // in front of an LF, making a CRLF.
+            assert_eq!(r.line_ending_stats().total(), len_lines - 1);
+        }
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn pt_io_write_01(ref text in "\\PC{0,300}", ref splits in vec(0usize..1000000, 0..10)) {
+        use std::io::Write;
+
+        let bytes = text.as_bytes();
+        let mut split_idxs: Vec<_> = splits.iter().map(|i| i % (bytes.len() + 1)).collect();
+        split_idxs.push(0);
+        split_idxs.push(bytes.len());
+        split_idxs.sort_unstable();
+
+        let mut b = RopeBuilder::new();
+        for w in split_idxs.windows(2) {
+            b.write_all(&bytes[w[0]..w[1]]).unwrap();
+        }
+        b.flush().unwrap();
+        let r = b.try_finish().unwrap();
+
+        assert_eq!(r, text.as_str());
+        r.assert_integrity();
+        r.assert_invariants();
+    }
+
+    #[test]
+    fn pt_reader_01(ref text in "\\PC{0,300}",
+        range in (0usize..1000000, 0usize..1000000),
+        ref seeks in vec((0usize..1000000, 0usize..20), 0..10),
+    ) {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let r = Rope::from_str(text);
+        let idx1 = range.0 % (r.len_chars() + 1);
+        let idx2 = range.1 % (r.len_chars() + 1);
+        let s = r.slice(idx1.min(idx2)..idx1.max(idx2));
+        let bytes = s.to_string().into_bytes();
+
+        let mut reader = s.reader();
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(all, bytes);
+
+        for &(pos, len) in seeks.iter() {
+            let pos = pos % (bytes.len() + 2);
+            assert_eq!(reader.seek(SeekFrom::Start(pos as u64)).unwrap(), pos as u64);
+            let mut buf = vec![0u8; len];
+            let n = reader.read(&mut buf).unwrap();
+            let expected = &bytes[pos.min(bytes.len())..(pos + len).min(bytes.len())];
+            assert_eq!(&buf[..n], expected);
+        }
+    }
+
+    #[test]
+    fn pt_matches_ignore_case_01(ref text in "[asS\u{df}\u{1e9e}\u{fb01}fiI\u{130}\u{131}]{0,200}",
+        ref pattern in "[asS\u{df}\u{1e9e}\u{fb01}fiI\u{130}\u{131}]{1,4}",
+        chunk_len in 1usize..32,
+        range in (0usize..1000000, 0usize..1000000),
+    ) {
+        let r = rope_with_small_chunks(text, chunk_len);
+        let idx1 = range.0 % (r.len_chars() + 1);
+        let idx2 = range.1 % (r.len_chars() + 1);
+        let start = idx1.min(idx2);
+        let end = idx1.max(idx2);
+
+        let s = r.slice(start..end);
+        let text: Vec<char> = string_slice(text, start, end).chars().collect();
+
+        // Brute force: compare the case folding of every char range with the
+        // pattern's, as computed by `str::to_uppercase()`/`to_lowercase()`.
+        let fold = |chars: &[char]| -> String {
+            chars
+                .iter()
+                .map(|&c| match c {
+                    '\u{131}' => c.to_string(),
+                    _ => c.to_uppercase().to_string().to_lowercase().to_uppercase().to_lowercase(),
+                })
+                .collect()
+        };
+        let pattern_chars: Vec<char> = pattern.chars().collect();
+        let folded_pattern = fold(&pattern_chars);
+        let match_at = |i: usize| {
+            (i..=text.len())
+                .find(|&j| fold(&text[i..j]) == folded_pattern)
+                .map(|j| i..j)
+        };
+        let mut expected = Vec::new();
+        let mut i = 0;
+        while i < text.len() {
+            match match_at(i) {
+                Some(m) => {
+                    i = m.end;
+                    expected.push(m);
+                }
+                None => i += 1,
+            }
+        }
+
+        assert_eq!(s.matches_ignore_case(pattern).collect::<Vec<_>>(), expected);
+        for m in s.rmatches_ignore_case(pattern) {
+            assert_eq!(fold(&text[m.clone()]), folded_pattern);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn pt_regex_find_iter_01(ref text in "[ab\u{e9} \n]{0,500}",
+        pattern_idx in 0usize..6,
+        chunk_len in 1usize..64,
+        range in (0usize..1000000, 0usize..1000000),
+    ) {
+        use ropey::regex::Regex;
+
+        let patterns = [r"a+b", r"(?m)^\w+", r"\w+(?-u:\b)", r"b*", r"[^a\n]{2,}", r"\n\n|a$"];
+        let regex = Regex::new(patterns[pattern_idx]).unwrap();
+
+        let r = rope_with_small_chunks(text, chunk_len);
+        let idx1 = range.0 % (r.len_chars() + 1);
+        let idx2 = range.1 % (r.len_chars() + 1);
+        let start = idx1.min(idx2);
+        let end = idx1.max(idx2);
+
+        let s = r.slice(start..end);
+        let text = string_slice(text, start, end);
+
+        let mut cache = regex.create_cache();
+        let expected: Vec<_> = regex.find_iter(&mut cache, text).map(|m| m.range()).collect();
+        let found: Vec<_> = s.regex_find_iter(&regex).map(|m| m.byte_range()).collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "multi_search")]
+    fn pt_multi_find_iter_01(ref text in "[ab\u{e9}\n]{0,500}",
+        ref patterns in vec("[ab\u{e9}\n]{1,8}", 1..8),
+        kind_idx in 0usize..3,
+        chunk_len in 1usize..64,
+        range in (0usize..1000000, 0usize..1000000),
+    ) {
+        use aho_corasick::AhoCorasick;
+        use ropey::multi_search::{MatchKind, DFA};
+
+        let kind = [MatchKind::Standard, MatchKind::LeftmostFirst, MatchKind::LeftmostLongest][kind_idx];
+        let dfa = DFA::builder().match_kind(kind).build(patterns).unwrap();
+        let ac = AhoCorasick::builder().match_kind(kind).build(patterns).unwrap();
+
+        let r = rope_with_small_chunks(text, chunk_len);
+        let idx1 = range.0 % (r.len_chars() + 1);
+        let idx2 = range.1 % (r.len_chars() + 1);
+        let start = idx1.min(idx2);
+        let end = idx1.max(idx2);
+
+        let s = r.slice(start..end);
+        let text = string_slice(text, start, end);
+
+        let expected: Vec<_> = ac.find_iter(text).map(|m| (m.pattern(), m.range())).collect();
+        let found: Vec<_> = s.multi_find_iter(&dfa).map(|m| (m.pattern(), m.byte_range())).collect();
+        assert_eq!(found, expected);
+
+        if kind == MatchKind::Standard {
+            let expected: Vec<_> = ac.find_overlapping_iter(text).map(|m| (m.pattern(), m.range())).collect();
+            let found: Vec<_> = s.multi_find_overlapping_iter(&dfa).map(|m| (m.pattern(), m.byte_range())).collect();
+            assert_eq!(found, expected);
+        }
+    }
+
     #[test]
     fn pt_bytes_at_01(idx in 0usize..TEXT.len()) {
         let r = Rope::from_str(TEXT);